@@ -8,13 +8,25 @@
 extern crate flipper0;
 extern crate alloc;
 
+use core::error::Error;
 use core::ffi::CStr;
 use alloc::boxed::Box;
 use flipper0::fs::{Metadata, Storage};
 use flipper0::path::{PathBuf, Path};
 
 
-type Result<T = (), E = Box<dyn core::error::Error>> = core::result::Result<T, E>;
+type Result<T = (), E = Box<dyn Error>> = core::result::Result<T, E>;
+
+
+/// Prints `err` and then each of its sources.
+fn print_err(err: &dyn Error) {
+	println!("  err: {err}");
+	let mut source = err.source();
+	while let Some(err) = source {
+		println!("    caused by: {err}");
+		source = err.source();
+	}
+}
 
 
 #[main]
@@ -23,28 +35,28 @@ pub unsafe fn main() -> Result {
 	let storage = Storage::open_default()?;
 
 	// Get info for external flash (SD):
-	let info = storage.info(Path::from_ptr(flipper0::ffi::STORAGE_EXT_PATH_PREFIX.as_ptr() as _));
+	let info = storage.info(Path::ext());
 	println!("Storage info: {info:#?}");
 
 	// There are simple read_dir example with custom buffer size using `with_buf_len`.
 	//
-	// Create path from null-terminated string:
-	// let path = PathBuf::from_vec_with_nul_unchecked(b"/ext/apps/Misc\0".to_vec());
-	let path = PathBuf::from_vec_with_nul_unchecked(b"/ext/apps\0".to_vec());
+	// Create path from string:
+	// let path = Path::ext().join(PathBuf::from("apps/Misc"));
+	let path = PathBuf::from("/ext/apps");
 	println!("read_dir: {path:?}");
 
 	for entry in storage.read_dir(path)?.with_buf_len::<64>().with_info() {
 		match entry {
 			Ok(entry) => println!("  entry: {entry}"),
-			Err(err) => println!("  err: {err}"),
+			Err(err) => print_err(&flipper0::error::Error::from(err)),
 		}
 	}
 
 
 	// Now same but recursive with filter, using walk_dir.
 	//
-	// Create path from null-terminated string:
-	let path = PathBuf::from_vec_with_nul_unchecked(b"/ext/apps\0".to_vec());
+	// Create path from string, trailing slash is removed:
+	let path = PathBuf::from("/ext/apps/");
 	println!("walk_dir: {path:?}");
 	// Create filter predicate (name, info) -> bool:
 	let filter = |name: &CStr, info: Option<Metadata>| {
@@ -55,7 +67,7 @@ pub unsafe fn main() -> Result {
 	for entry in storage.walk_dir(path)?.recursive(true).filter(filter) {
 		match entry {
 			Ok(entry) => println!("  entry: {entry}"),
-			Err(err) => println!("  err: {err}"),
+			Err(err) => print_err(&flipper0::error::Error::from(err)),
 		}
 	}
 
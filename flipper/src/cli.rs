@@ -0,0 +1,275 @@
+//! CLI commands with closure callbacks and typed arguments.
+//!
+//! ```ignore
+//! let _command = Command::register("hello", CommandFlag::Default, |session, args| {
+//!     match args.read::<String>() {
+//!         Some(name) => writeln!(session, "Hello, {name}!").ok(),
+//!         None => writeln!(session, "usage: hello <name>").ok(),
+//!     };
+//! })?;
+//! ```
+
+use core::ffi::{c_void, CStr};
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::io;
+use crate::record::{self, Record};
+use crate::string::{IntoCString, OsString};
+use crate::time::ticks;
+use crate::AsPtr;
+
+
+type CommandFn = dyn FnMut(&mut CliSession, &mut Args<'_>) + Send;
+
+
+/// Command registered in the CLI, deleted on drop.
+///
+/// The command must not be running when it is dropped.
+pub struct Command {
+	cli: Record<record::Cli>,
+	name: CString,
+	_callback: Box<Box<CommandFn>>,
+}
+
+impl Command {
+	/// Registers command `name` calling `f` in the CLI thread, replaces the command with the same name.
+	///
+	/// `name` is truncated at the first nul, see [`IntoCString`].
+	pub fn register<S, F>(name: S, flag: CommandFlag, f: F) -> Result<Self, NullPointerError>
+		where S: IntoCString,
+		      F: FnMut(&mut CliSession, &mut Args<'_>) + Send + 'static {
+		let cli = Record::<record::Cli>::open()?;
+		let name = name.into_c_string();
+		let mut callback: Box<Box<CommandFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<CommandFn> as *mut c_void;
+		unsafe { ffi::cli_add_command(cli.as_ptr(), name.as_ptr(), flag.into(), Some(command_proxy), context) };
+		Ok(Self { cli,
+		          name,
+		          _callback: callback })
+	}
+
+	pub fn name(&self) -> &CStr { &self.name }
+}
+
+impl Drop for Command {
+	fn drop(&mut self) { unsafe { ffi::cli_delete_command(self.cli.as_ptr(), self.name.as_ptr()) } }
+}
+
+unsafe extern "C" fn command_proxy(cli: *mut ffi::Cli, args: *mut ffi::FuriString, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<CommandFn>);
+	if let (Some(cli), Some(args)) = (NonNull::new(cli), NonNull::new(args)) {
+		callback(&mut CliSession(cli), &mut Args(args, PhantomData));
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommandFlag {
+	/// Loader lock is held while the command runs, so no application is running.
+	#[default]
+	Default,
+	/// Can run in parallel with applications.
+	ParallelSafe,
+	/// Can run in insomnia mode.
+	InsomniaSafe,
+}
+
+impl From<CommandFlag> for ffi::CliCommandFlag {
+	fn from(flag: CommandFlag) -> Self {
+		match flag {
+			CommandFlag::Default => ffi::CliCommandFlag::CliCommandFlagDefault,
+			CommandFlag::ParallelSafe => ffi::CliCommandFlag::CliCommandFlagParallelSafe,
+			CommandFlag::InsomniaSafe => ffi::CliCommandFlag::CliCommandFlagInsomniaSafe,
+		}
+	}
+}
+
+
+/// Terminal of the running command.
+pub struct CliSession(NonNull<ffi::Cli>);
+
+impl CliSession {
+	/// Reads up to `buf.len()` bytes, waits up to `timeout`.
+	pub fn read_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> usize {
+		unsafe { ffi::cli_read_timeout(self.as_ptr(), buf.as_mut_ptr(), buf.len(), ticks(timeout)) }
+	}
+
+	/// Reads the line until `Enter` appending it to `line` without line ending, echoes input and handles backspace.
+	///
+	/// Returns `false` if interrupted by `Ctrl+C` or the terminal is disconnected, then `line` is unchanged.
+	pub fn read_line(&mut self, line: &mut String) -> bool {
+		let mut bytes = Vec::new();
+		loop {
+			let mut c = 0;
+			if self.read_timeout(core::slice::from_mut(&mut c), Duration::from_millis(100)) == 0 {
+				if !self.is_connected() {
+					return false;
+				}
+				continue;
+			}
+			match c {
+				b'\r' | b'\n' => break,
+				// Ctrl+C:
+				0x03 => return false,
+				// backspace or delete:
+				0x08 | 0x7F => {
+					if bytes.pop().is_some() {
+						self.write_bytes(b"\x08 \x08");
+					}
+				},
+				c => {
+					bytes.push(c);
+					self.write_bytes(&[c]);
+				},
+			}
+		}
+		self.write_bytes(b"\r\n");
+		line.push_str(&String::from_utf8_lossy(&bytes));
+		true
+	}
+
+	/// Checks whether `Ctrl+C` is received.
+	pub fn is_interrupted(&self) -> bool { unsafe { ffi::cli_cmd_interrupt_received(self.as_ptr()) } }
+
+	pub fn is_connected(&self) -> bool { unsafe { ffi::cli_is_connected(self.as_ptr()) } }
+
+	fn write_bytes(&mut self, buf: &[u8]) { unsafe { ffi::cli_write(self.as_ptr(), buf.as_ptr(), buf.len()) } }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Cli { self.0.as_ptr() }
+}
+
+impl io::Read for CliSession {
+	/// Waits for at least one byte.
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if buf.is_empty() {
+			return Ok(0);
+		}
+		Ok(unsafe { ffi::cli_read(self.as_ptr(), buf.as_mut_ptr(), buf.len()) })
+	}
+}
+
+impl io::Write for CliSession {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.write_bytes(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl fmt::Write for CliSession {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		self.write_bytes(s.as_bytes());
+		Ok(())
+	}
+}
+
+
+/// Arguments of the command, each read argument is trimmed from the start.
+pub struct Args<'a>(NonNull<ffi::FuriString>, PhantomData<&'a mut ffi::FuriString>);
+
+impl<'a> Args<'a> {
+	/// # Safety
+	/// `raw` must be valid for `'a`.
+	pub unsafe fn from_raw(raw: NonNull<ffi::FuriString>) -> Self { Self(raw, PhantomData) }
+
+	/// Reads the next argument of type `T`, the argument is trimmed only if parsed.
+	pub fn read<T: Arg>(&mut self) -> Option<T> { T::read(self) }
+
+	/// Reads the next word as decimal integer.
+	pub fn int(&mut self) -> Option<i32> {
+		let mut value = 0;
+		unsafe { ffi::args_read_int_and_trim(self.as_ptr(), &mut value) }.then_some(value)
+	}
+
+	/// Reads the next word until space.
+	pub fn word(&mut self) -> Option<OsString> {
+		let word = OsString::new().ok()?;
+		unsafe { ffi::args_read_string_and_trim(self.as_ptr(), word.as_ptr()) }.then_some(word)
+	}
+
+	/// Reads the next word or the string in double quotes without them.
+	pub fn quoted(&mut self) -> Option<OsString> {
+		let word = OsString::new().ok()?;
+		unsafe { ffi::args_read_probably_quoted_string_and_trim(self.as_ptr(), word.as_ptr()) }.then_some(word)
+	}
+
+	/// Reads the next word of exactly `2 * bytes.len()` hex digits into `bytes`.
+	pub fn hex_bytes(&mut self, bytes: &mut [u8]) -> bool {
+		let read = unsafe { ffi::args_read_hex_bytes(self.as_ptr(), bytes.as_mut_ptr(), bytes.len()) };
+		// firmware doesn't trim it:
+		read && self.word().is_some()
+	}
+
+	/// Unread arguments.
+	pub fn rest(&self) -> &CStr { unsafe { CStr::from_ptr(ffi::furi_string_get_cstr(self.as_ptr())) } }
+
+	pub fn is_empty(&self) -> bool { unsafe { ffi::args_length(self.as_ptr()) == 0 } }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriString { self.0.as_ptr() }
+}
+
+
+/// Type which can be read from [`Args`].
+pub trait Arg: Sized {
+	fn read(args: &mut Args<'_>) -> Option<Self>;
+}
+
+impl Arg for i32 {
+	fn read(args: &mut Args<'_>) -> Option<Self> { args.int() }
+}
+
+/// Word or quoted string.
+impl Arg for OsString {
+	fn read(args: &mut Args<'_>) -> Option<Self> { args.quoted() }
+}
+
+/// Word or quoted string, invalid UTF-8 is replaced.
+impl Arg for String {
+	fn read(args: &mut Args<'_>) -> Option<Self> {
+		args.quoted()
+		    .map(|s| String::from_utf8_lossy(s.as_c_str().to_bytes()).into_owned())
+	}
+}
+
+/// Hex string of exactly `2 * N` digits.
+impl<const N: usize> Arg for [u8; N] {
+	fn read(args: &mut Args<'_>) -> Option<Self> {
+		let mut bytes = [0; N];
+		args.hex_bytes(&mut bytes).then_some(bytes)
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn args() {
+		let raw = CStr::from_bytes_with_nul(b"42 x \"hello world\" 0aFf -7 rest\0").unwrap();
+		let raw = OsString::try_from(raw).unwrap();
+		let mut args = unsafe { Args::from_raw(NonNull::new(raw.as_ptr()).unwrap()) };
+		assert_eq!(Some(42), args.read::<i32>());
+		assert_eq!(None, args.read::<i32>());
+		assert_eq!(Some(String::from("x")), args.read());
+		assert_eq!(Some(String::from("hello world")), args.read());
+		assert_eq!(None, args.read::<[u8; 1]>());
+		assert_eq!(Some([0x0A, 0xFF]), args.read());
+		assert_eq!(Some(-7), args.int());
+		assert_eq!(b"rest", args.rest().to_bytes());
+		assert_eq!(b"rest", args.word().unwrap().as_c_str().to_bytes());
+		assert!(args.is_empty());
+		assert_eq!(None, args.word());
+	}
+}
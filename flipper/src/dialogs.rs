@@ -0,0 +1,204 @@
+//! Modal dialogs of the `Dialogs` service: message boxes and file browser.
+//!
+//! Texts are converted with [`IntoCString`], so `&str`s are truncated at the first nul.
+//!
+//! ```ignore
+//! let buttons = Buttons { left: Some("No"), right: Some("Yes"), ..Default::default() };
+//! if dialogs::message("Delete", "Are you sure?", buttons)? == DialogResult::Right {
+//!     if let Some(path) = dialogs::file_browser(Path::ext(), ".txt", None)? {
+//!         println!("{path}");
+//!     }
+//! }
+//! ```
+
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::error::NullPointerError;
+use crate::gui::{Align, Icon};
+use crate::path::{Path, PathBuf};
+use crate::record::{self, Record};
+use crate::string::{IntoCString, OsString};
+use crate::AsPtr;
+
+
+/// Shows the message with `header` and `text` centered, waits for a button press.
+///
+/// Returns `Err` if the dialogs record or the message can't be allocated.
+pub fn message(header: &str, text: &str, buttons: Buttons<'_>) -> Result<DialogResult, NullPointerError> {
+	let mut message = Message::new()?;
+	message.set_header(header, 64, 0, Align::Center, Align::Top);
+	message.set_text(text, 64, 32, Align::Center, Align::Center);
+	message.set_buttons(buttons);
+	Ok(Dialogs::open()?.show(&message))
+}
+
+/// Shows the file browser starting at `start_dir` with files of `extension`, e.g. `".txt"` or `"*"`.
+///
+/// Returns `Ok(None)` if the user cancelled, `Err` if the dialogs record can't be opened.
+pub fn file_browser<P: AsRef<Path>>(start_dir: P,
+                                    extension: &str,
+                                    icon: Option<&'static Icon>)
+                                    -> Result<Option<PathBuf>, NullPointerError> {
+	let options = FileBrowserOptions::new(extension, icon);
+	Ok(Dialogs::open()?.file_browser(start_dir, &options))
+}
+
+
+/// Opened dialogs service.
+pub struct Dialogs(Record<record::Dialogs>);
+
+impl Dialogs {
+	pub fn open() -> Result<Self, NullPointerError> { Record::<record::Dialogs>::open().map(Self) }
+
+	/// Shows the `message` and waits for a button press.
+	pub fn show(&self, message: &Message) -> DialogResult {
+		let button = unsafe { ffi::dialog_message_show(self.as_ptr(), message.as_ptr()) };
+		DialogResult::try_from(&button).unwrap_or(DialogResult::Back)
+	}
+
+	/// Shows the file browser starting at `start`, which can be a directory or a preselected file.
+	///
+	/// Returns `None` if the user cancelled.
+	pub fn file_browser<P: AsRef<Path>>(&self, start: P, options: &FileBrowserOptions) -> Option<PathBuf> {
+		let start = OsString::try_from(start.as_ref().as_c_str()).ok()?;
+		let result = OsString::new().ok()?;
+		let options = options.to_raw();
+		let selected = unsafe { ffi::dialog_file_browser_show(self.as_ptr(), result.as_ptr(), start.as_ptr(), &options) };
+		selected.then(|| PathBuf::from(result))
+	}
+
+	/// Shows the storage error with `text`.
+	pub fn show_storage_error<S: IntoCString>(&self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_message_show_storage_error(self.as_ptr(), text.as_ptr()) }
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::DialogsApp { self.0.as_ptr() }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DialogResult {
+	Back,
+	Left,
+	Center,
+	Right,
+}
+
+impl TryFrom<&ffi::DialogMessageButton> for DialogResult {
+	type Error = ();
+
+	fn try_from(button: &ffi::DialogMessageButton) -> Result<Self, Self::Error> {
+		match button {
+			ffi::DialogMessageButton::DialogMessageButtonBack => Ok(DialogResult::Back),
+			ffi::DialogMessageButton::DialogMessageButtonLeft => Ok(DialogResult::Left),
+			ffi::DialogMessageButton::DialogMessageButtonCenter => Ok(DialogResult::Center),
+			ffi::DialogMessageButton::DialogMessageButtonRight => Ok(DialogResult::Right),
+			_ => Err(()),
+		}
+	}
+}
+
+
+/// Texts of the message buttons, `None` hides the button.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Buttons<'a> {
+	pub left: Option<&'a str>,
+	pub center: Option<&'a str>,
+	pub right: Option<&'a str>,
+}
+
+
+/// Owned `DialogMessage` with its texts.
+pub struct Message {
+	raw: NonNull<ffi::DialogMessage>,
+	header: Option<CString>,
+	text: Option<CString>,
+	buttons: [Option<CString>; 3],
+}
+
+impl Message {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::dialog_message_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          text: None,
+		          buttons: Default::default() })
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_message_set_header(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.header = Some(text);
+	}
+
+	pub fn set_text<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_message_set_text(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.text = Some(text);
+	}
+
+	/// Sets the icon, `None` removes it.
+	pub fn set_icon(&mut self, x: u8, y: u8, icon: Option<&'static Icon>) {
+		let icon = icon.map_or(core::ptr::null(), |icon| icon as *const Icon);
+		unsafe { ffi::dialog_message_set_icon(self.as_ptr(), icon, x, y) }
+	}
+
+	pub fn set_buttons(&mut self, buttons: Buttons<'_>) {
+		self.buttons = [buttons.left, buttons.center, buttons.right].map(|text| text.map(IntoCString::into_c_string));
+		let text = |i: usize| self.buttons[i].as_ref().map_or(core::ptr::null(), |text| text.as_ptr());
+		unsafe { ffi::dialog_message_set_buttons(self.as_ptr(), text(0), text(1), text(2)) }
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::DialogMessage { self.raw.as_ptr() }
+}
+
+impl Drop for Message {
+	fn drop(&mut self) { unsafe { ffi::dialog_message_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for Message {}
+
+
+/// Options of the file browser.
+#[derive(Debug, Clone)]
+pub struct FileBrowserOptions {
+	extension: CString,
+	icon: Option<&'static Icon>,
+	skip_assets: bool,
+	hide_ext: bool,
+}
+
+impl FileBrowserOptions {
+	/// Shows files of `extension`, e.g. `".txt"` or `"*"`, with `icon`.
+	pub fn new<S: IntoCString>(extension: S, icon: Option<&'static Icon>) -> Self {
+		Self { extension: extension.into_c_string(),
+		       icon,
+		       skip_assets: true,
+		       hide_ext: true }
+	}
+
+	/// Hides the assets folder, `true` by default.
+	pub fn skip_assets(mut self, skip: bool) -> Self {
+		self.skip_assets = skip;
+		self
+	}
+
+	/// Hides extensions of files, `true` by default.
+	pub fn hide_ext(mut self, hide: bool) -> Self {
+		self.hide_ext = hide;
+		self
+	}
+
+	fn to_raw(&self) -> ffi::DialogsFileBrowserOptions {
+		ffi::DialogsFileBrowserOptions { extension: self.extension.as_ptr(),
+		                                 skip_assets: self.skip_assets,
+		                                 icon: self.icon.map_or(core::ptr::null(), |icon| icon as *const Icon),
+		                                 hide_ext: self.hide_ext,
+		                                 item_loader_callback: None,
+		                                 item_loader_context: core::ptr::null_mut() }
+	}
+}
@@ -0,0 +1,360 @@
+//! Key-value format of the firmware used for saved keys, signals and settings.
+//!
+//! ```ignore
+//! let mut file = FlipperFormat::create(Path::app_data("my_app").join("settings.fff"))?;
+//! file.write_header("My App Settings", 1)?;
+//! file.write_u32("Speed", 3)?;
+//! file.write_hex("Key", &[0xDE, 0xAD])?;
+//!
+//! let mut file = FlipperFormat::open(path)?;
+//! let version = file.check_header("My App Settings", 1..=1)?;
+//! let speed = file.read_u32("Speed")?;
+//! ```
+//!
+//! Values are read from the current position to the end, so keys must be read in the order of writing,
+//! or after [`rewind`](FlipperFormat::rewind).
+//!
+//! Keys and string values are written as C strings, so `&str`s are truncated at the first nul.
+//!
+//! With feature `serde` structs can be (de)serialized with [`FlipperFormat::serialize`] and [`FlipperFormat::deserialize`].
+
+use core::fmt;
+use core::ops::RangeBounds;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::alloc::string::{String, ToString};
+use crate::alloc::vec;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::path::Path;
+use crate::record::{self, Record};
+use crate::string::{IntoCString, OsString};
+use crate::AsPtr;
+
+#[cfg(feature = "serde")]
+pub use self::serde::{load, save};
+
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// Instance or its buffer can't be allocated.
+	NullPointer,
+	/// File can't be opened or closed, or the instance isn't backed by a file.
+	Open,
+	/// Key isn't found or its value can't be parsed as requested type.
+	Read(String),
+	/// Value of the key can't be written.
+	Write(String),
+	/// Header has another file type or unsupported version.
+	Header { filetype: String, version: u32 },
+	/// Error of the (de)serialization.
+	Custom(String),
+}
+
+impl core::error::Error for Error {}
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::NullPointer => write!(f, "allocation failed"),
+			Error::Open => write!(f, "unable to open or close file"),
+			Error::Read(key) => write!(f, "unable to read key `{key}`"),
+			Error::Write(key) => write!(f, "unable to write key `{key}`"),
+			Error::Header { filetype, version } => write!(f, "unsupported file `{filetype}` version {version}"),
+			Error::Custom(msg) => write!(f, "{msg}"),
+		}
+	}
+}
+
+impl From<NullPointerError> for Error {
+	fn from(_: NullPointerError) -> Self { Error::NullPointer }
+}
+
+
+enum Backend {
+	String,
+	File(Record<record::Storage>),
+	BufferedFile(Record<record::Storage>),
+}
+
+
+/// Instance of the `FlipperFormat` backed by a string or a file.
+pub struct FlipperFormat {
+	raw: NonNull<ffi::FlipperFormat>,
+	backend: Backend,
+}
+
+impl FlipperFormat {
+	/// Allocates instance backed by the string in memory.
+	pub fn string() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::flipper_format_string_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::String })
+	}
+
+	/// Allocates instance backed by a file, it must be opened before use.
+	pub fn file() -> Result<Self, NullPointerError> {
+		let storage = Record::<record::Storage>::open()?;
+		let raw = NonNull::new(unsafe { ffi::flipper_format_file_alloc(storage.as_ptr()) }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::File(storage) })
+	}
+
+	/// Allocates instance backed by a buffered file, it can be only [opened existing](Self::open_existing).
+	pub fn buffered_file() -> Result<Self, NullPointerError> {
+		let storage = Record::<record::Storage>::open()?;
+		let raw = NonNull::new(unsafe { ffi::flipper_format_buffered_file_alloc(storage.as_ptr()) }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::BufferedFile(storage) })
+	}
+
+	/// Opens existing file at `path` for reading.
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let mut file = Self::buffered_file()?;
+		file.open_existing(path)?;
+		Ok(file)
+	}
+
+	/// Creates file at `path`, truncates it if exists.
+	pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let mut file = Self::file()?;
+		file.open_always(path)?;
+		Ok(file)
+	}
+
+
+	/// Opens existing file at `path`.
+	pub fn open_existing<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+		let path = path.as_ref().as_ptr();
+		let opened = match self.backend {
+			Backend::File(_) => unsafe { ffi::flipper_format_file_open_existing(self.as_ptr(), path) },
+			Backend::BufferedFile(_) => unsafe { ffi::flipper_format_buffered_file_open_existing(self.as_ptr(), path) },
+			Backend::String => false,
+		};
+		opened.then_some(()).ok_or(Error::Open)
+	}
+
+	/// Opens existing file at `path` for writing to the end.
+	pub fn open_append<P: AsRef<Path>>(&mut self, path: P) -> Result<()> { self.open_file(path, ffi::flipper_format_file_open_append) }
+
+	/// Opens file at `path`, creates it if doesn't exist or truncates otherwise.
+	pub fn open_always<P: AsRef<Path>>(&mut self, path: P) -> Result<()> { self.open_file(path, ffi::flipper_format_file_open_always) }
+
+	/// Creates new file at `path`, fails if it exists.
+	pub fn open_new<P: AsRef<Path>>(&mut self, path: P) -> Result<()> { self.open_file(path, ffi::flipper_format_file_open_new) }
+
+	fn open_file<P: AsRef<Path>>(&mut self,
+	                             path: P,
+	                             open: unsafe extern "C" fn(*mut ffi::FlipperFormat, *const core::ffi::c_char) -> bool)
+	                             -> Result<()> {
+		match self.backend {
+			Backend::File(_) if unsafe { open(self.as_ptr(), path.as_ref().as_ptr()) } => Ok(()),
+			_ => Err(Error::Open),
+		}
+	}
+
+	/// Closes the file, it is also closed on drop.
+	pub fn close(&mut self) -> Result<()> {
+		let closed = match self.backend {
+			Backend::File(_) => unsafe { ffi::flipper_format_file_close(self.as_ptr()) },
+			Backend::BufferedFile(_) => unsafe { ffi::flipper_format_buffered_file_close(self.as_ptr()) },
+			Backend::String => false,
+		};
+		closed.then_some(()).ok_or(Error::Open)
+	}
+
+
+	/// In strict mode reading fails if the next key isn't requested one instead of skipping it, off by default.
+	pub fn set_strict_mode(&mut self, strict: bool) { unsafe { ffi::flipper_format_set_strict_mode(self.as_ptr(), strict) } }
+
+	/// Moves to the start.
+	pub fn rewind(&mut self) -> bool { unsafe { ffi::flipper_format_rewind(self.as_ptr()) } }
+
+	/// Moves to the end, e.g. to write after reading.
+	pub fn seek_to_end(&mut self) -> bool { unsafe { ffi::flipper_format_seek_to_end(self.as_ptr()) } }
+
+	/// Checks whether `key` exists after the current position.
+	pub fn key_exists(&mut self, key: &str) -> bool {
+		let c_key = key.into_c_string();
+		unsafe { ffi::flipper_format_key_exist(self.as_ptr(), c_key.as_ptr()) }
+	}
+
+	/// Number of values of `key` after the current position, e.g. bytes of hex value.
+	pub fn value_count(&mut self, key: &str) -> Result<usize> {
+		let c_key = key.into_c_string();
+		let mut count = 0;
+		let ok = unsafe { ffi::flipper_format_get_value_count(self.as_ptr(), c_key.as_ptr(), &mut count) };
+		ok.then_some(count as usize).ok_or_else(|| Error::Read(key.to_string()))
+	}
+
+	/// Removes the first `key` after the current position with its value.
+	pub fn delete_key(&mut self, key: &str) -> Result<()> {
+		let c_key = key.into_c_string();
+		let ok = unsafe { ffi::flipper_format_delete_key(self.as_ptr(), c_key.as_ptr()) };
+		ok.then_some(()).ok_or_else(|| Error::Write(key.to_string()))
+	}
+
+
+	/// Reads the header: file type and version.
+	pub fn read_header(&mut self) -> Result<(OsString, u32)> {
+		let filetype = OsString::new()?;
+		let mut version = 0;
+		let ok = unsafe { ffi::flipper_format_read_header(self.as_ptr(), filetype.as_ptr(), &mut version) };
+		ok.then_some((filetype, version))
+		  .ok_or_else(|| Error::Read("Filetype".to_string()))
+	}
+
+	/// Reads the header and checks that file type is `filetype` and version is in `versions`, returns the version.
+	pub fn check_header<R: RangeBounds<u32>>(&mut self, filetype: &str, versions: R) -> Result<u32> {
+		let (actual, version) = self.read_header()?;
+		if actual.as_c_str().to_bytes() == filetype.as_bytes() && versions.contains(&version) {
+			Ok(version)
+		} else {
+			let filetype = actual.as_c_str().to_string_lossy().into_owned();
+			Err(Error::Header { filetype, version })
+		}
+	}
+
+	pub fn write_header(&mut self, filetype: &str, version: u32) -> Result<()> {
+		let c_filetype = filetype.into_c_string();
+		let ok = unsafe { ffi::flipper_format_write_header_cstr(self.as_ptr(), c_filetype.as_ptr(), version) };
+		ok.then_some(()).ok_or_else(|| Error::Write("Filetype".to_string()))
+	}
+
+	pub fn write_comment<S: IntoCString>(&mut self, text: S) -> Result<()> {
+		let text = text.into_c_string();
+		let ok = unsafe { ffi::flipper_format_write_comment_cstr(self.as_ptr(), text.as_ptr()) };
+		ok.then_some(()).ok_or_else(|| Error::Write("#".to_string()))
+	}
+
+
+	/// Reads the rest of the line after `key`.
+	pub fn read_string(&mut self, key: &str) -> Result<OsString> {
+		let c_key = key.into_c_string();
+		let value = OsString::new()?;
+		let ok = unsafe { ffi::flipper_format_read_string(self.as_ptr(), c_key.as_ptr(), value.as_ptr()) };
+		ok.then_some(value).ok_or_else(|| Error::Read(key.to_string()))
+	}
+
+	/// Writes `value` which must be a single line, anything after a nul in `key` or `value` is dropped.
+	pub fn write_string<S: IntoCString>(&mut self, key: &str, value: S) -> Result<()> {
+		let c_key = key.into_c_string();
+		let value = value.into_c_string();
+		let ok = unsafe { ffi::flipper_format_write_string_cstr(self.as_ptr(), c_key.as_ptr(), value.as_ptr()) };
+		ok.then_some(()).ok_or_else(|| Error::Write(key.to_string()))
+	}
+
+	/// Reads bytes written as hex, e.g. `DE AD BE EF`.
+	pub fn read_hex(&mut self, key: &str) -> Result<Vec<u8>> { self.read_values(key, ffi::flipper_format_read_hex) }
+
+	/// Writes bytes as hex, e.g. `DE AD BE EF`.
+	pub fn write_hex(&mut self, key: &str, value: &[u8]) -> Result<()> { self.write_values(key, value, ffi::flipper_format_write_hex) }
+
+
+	fn read_values<T: Copy + Default>(&mut self, key: &str, read: ReadFn<T>) -> Result<Vec<T>> {
+		let len = self.value_count(key)?;
+		let mut values = vec![T::default(); len];
+		self.read_values_into(key, &mut values, read)?;
+		Ok(values)
+	}
+
+	fn read_values_into<T>(&mut self, key: &str, values: &mut [T], read: ReadFn<T>) -> Result<()> {
+		let c_key = key.into_c_string();
+		let len = values.len().try_into().map_err(|_| Error::Read(key.to_string()))?;
+		let ok = unsafe { read(self.as_ptr(), c_key.as_ptr(), values.as_mut_ptr(), len) };
+		ok.then_some(()).ok_or_else(|| Error::Read(key.to_string()))
+	}
+
+	fn write_values<T>(&mut self, key: &str, values: &[T], write: WriteFn<T>) -> Result<()> {
+		let c_key = key.into_c_string();
+		let len = values.len().try_into().map_err(|_| Error::Write(key.to_string()))?;
+		let ok = unsafe { write(self.as_ptr(), c_key.as_ptr(), values.as_ptr(), len) };
+		ok.then_some(()).ok_or_else(|| Error::Write(key.to_string()))
+	}
+
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FlipperFormat { self.raw.as_ptr() }
+}
+
+type ReadFn<T> = unsafe extern "C" fn(*mut ffi::FlipperFormat, *const core::ffi::c_char, *mut T, u16) -> bool;
+type WriteFn<T> = unsafe extern "C" fn(*mut ffi::FlipperFormat, *const core::ffi::c_char, *const T, u16) -> bool;
+
+
+macro_rules! values {
+	($($ty:ty: $read:ident, $read_array:ident, $write:ident, $write_array:ident => $ffi_read:ident, $ffi_write:ident;)*) => {
+		impl FlipperFormat {
+			$(
+				pub fn $read(&mut self, key: &str) -> Result<$ty> {
+					let mut value = <$ty>::default();
+					self.read_values_into(key, core::slice::from_mut(&mut value), ffi::$ffi_read)?;
+					Ok(value)
+				}
+
+				/// Reads all values of the `key`.
+				pub fn $read_array(&mut self, key: &str) -> Result<Vec<$ty>> { self.read_values(key, ffi::$ffi_read) }
+
+				pub fn $write(&mut self, key: &str, value: $ty) -> Result<()> { self.write_values(key, &[value], ffi::$ffi_write) }
+
+				pub fn $write_array(&mut self, key: &str, values: &[$ty]) -> Result<()> { self.write_values(key, values, ffi::$ffi_write) }
+			)*
+		}
+	};
+}
+
+values! {
+	u32: read_u32, read_u32_array, write_u32, write_u32_array => flipper_format_read_uint32, flipper_format_write_uint32;
+	i32: read_i32, read_i32_array, write_i32, write_i32_array => flipper_format_read_int32, flipper_format_write_int32;
+	bool: read_bool, read_bool_array, write_bool, write_bool_array => flipper_format_read_bool, flipper_format_write_bool;
+	f32: read_float, read_float_array, write_float, write_float_array => flipper_format_read_float, flipper_format_write_float;
+}
+
+
+impl Drop for FlipperFormat {
+	/// Closes the file if opened.
+	fn drop(&mut self) { unsafe { ffi::flipper_format_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for FlipperFormat {}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn write_read() {
+		let mut format = FlipperFormat::string().unwrap();
+		format.write_header("Test File", 2).unwrap();
+		format.write_comment("values").unwrap();
+		format.write_u32_array("Numbers", &[1, 2, 3]).unwrap();
+		format.write_i32("Offset", -5).unwrap();
+		format.write_bool("Enabled", true).unwrap();
+		format.write_float("Ratio", 0.5).unwrap();
+		format.write_hex("Key", &[0xDE, 0xAD]).unwrap();
+		format.write_string("Name", "hello world").unwrap();
+
+		assert!(format.rewind());
+		assert_eq!(Ok(2), format.check_header("Test File", 1..=2));
+		assert_eq!(Ok(vec![1, 2, 3]), format.read_u32_array("Numbers"));
+		assert_eq!(Ok(-5), format.read_i32("Offset"));
+		assert_eq!(Ok(true), format.read_bool("Enabled"));
+		assert_eq!(Ok(0.5), format.read_float("Ratio"));
+		assert_eq!(Ok(vec![0xDE, 0xAD]), format.read_hex("Key"));
+		assert_eq!(b"hello world", format.read_string("Name").unwrap().as_c_str().to_bytes());
+		// already passed:
+		assert_eq!(Err(Error::Read("Offset".to_string())), format.read_i32("Offset"));
+
+		assert!(format.rewind());
+		let header = Error::Header { filetype: "Test File".to_string(),
+		                             version: 2 };
+		assert_eq!(Err(header), format.check_header("Test File", 3..));
+		assert_eq!(Err(Error::Open), format.open_existing(Path::ext()));
+	}
+}
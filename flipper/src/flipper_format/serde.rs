@@ -0,0 +1,628 @@
+//! Serde (de)serializer of structs, fields are keys of the `FlipperFormat`.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Settings {
+//!     speed: u32,
+//!     key: [u8; 4],
+//!     name: String,
+//!     mode: Mode,
+//!     offset: Option<i32>,
+//! }
+//!
+//! flipper_format::save(&path, "My App Settings", 1, &settings)?;
+//! let settings: Settings = flipper_format::load(&path, "My App Settings", 1..=1)?;
+//! ```
+//!
+//! Supported values:
+//! - `bool`, signed integers as `i32`, unsigned integers as `u32`, floats as `f32`
+//! - strings, chars and unit enum variants as strings
+//! - bytes, sequences and tuples of `u8` as hex
+//! - sequences and tuples of other numbers or `bool`s as arrays
+//! - `Option`, `None` isn't written
+//!
+//! Nested structs, maps and enum variants with data are not supported.
+
+use core::fmt::Display;
+use core::ops::RangeBounds;
+use ::serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Impossible, Serialize};
+use ::serde::forward_to_deserialize_any;
+use crate::alloc::format;
+use crate::alloc::string::{String, ToString};
+use crate::alloc::vec;
+use crate::alloc::vec::Vec;
+use crate::path::Path;
+use super::{Error, FlipperFormat, Result};
+
+
+/// Creates file at `path` with header and `value`, truncates it if exists.
+pub fn save<P, T>(path: P, filetype: &str, version: u32, value: &T) -> Result<()>
+	where P: AsRef<Path>,
+	      T: Serialize + ?Sized {
+	let mut file = FlipperFormat::create(path)?;
+	file.write_header(filetype, version)?;
+	file.serialize(value)
+}
+
+/// Reads value from the file at `path` with header of `filetype` and version in `versions`.
+pub fn load<P, T, R>(path: P, filetype: &str, versions: R) -> Result<T>
+	where P: AsRef<Path>,
+	      T: DeserializeOwned,
+	      R: RangeBounds<u32> {
+	let mut file = FlipperFormat::open(path)?;
+	file.check_header(filetype, versions)?;
+	file.deserialize()
+}
+
+
+impl FlipperFormat {
+	/// Writes fields of the struct `value` at the current position.
+	pub fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> { value.serialize(Serializer(self)) }
+
+	/// Reads fields of the struct, each from the start, so their order doesn't matter.
+	pub fn deserialize<T: DeserializeOwned>(&mut self) -> Result<T> { T::deserialize(Deserializer(self)) }
+}
+
+
+impl ser::Error for Error {
+	fn custom<T: Display>(msg: T) -> Self { Error::Custom(msg.to_string()) }
+}
+
+impl de::Error for Error {
+	fn custom<T: Display>(msg: T) -> Self { Error::Custom(msg.to_string()) }
+}
+
+fn unsupported<T>(what: &str) -> Result<T> { Err(Error::Custom(format!("{what} is not supported"))) }
+
+
+macro_rules! unsupported {
+	($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+		$(
+			fn $method(self, $(_: $arg),*) -> Result<$ok> { unsupported(stringify!($method)) }
+		)*
+	};
+}
+
+
+/// Serializer of the struct.
+pub struct Serializer<'a>(pub &'a mut FlipperFormat);
+
+impl<'a> ser::Serializer for Serializer<'a> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = Impossible<(), Error>;
+	type SerializeTuple = Impossible<(), Error>;
+	type SerializeTupleStruct = Impossible<(), Error>;
+	type SerializeTupleVariant = Impossible<(), Error>;
+	type SerializeMap = Impossible<(), Error>;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Impossible<(), Error>;
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<()> { value.serialize(self) }
+
+	fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self> { Ok(self) }
+
+	unsupported! {
+		serialize_bool(bool) -> ();
+		serialize_i8(i8) -> ();
+		serialize_i16(i16) -> ();
+		serialize_i32(i32) -> ();
+		serialize_i64(i64) -> ();
+		serialize_u8(u8) -> ();
+		serialize_u16(u16) -> ();
+		serialize_u32(u32) -> ();
+		serialize_u64(u64) -> ();
+		serialize_f32(f32) -> ();
+		serialize_f64(f64) -> ();
+		serialize_char(char) -> ();
+		serialize_str(&str) -> ();
+		serialize_bytes(&[u8]) -> ();
+		serialize_none() -> ();
+		serialize_unit() -> ();
+		serialize_unit_struct(&'static str) -> ();
+		serialize_unit_variant(&'static str, u32, &'static str) -> ();
+		serialize_seq(Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_map(Option<usize>) -> Self::SerializeMap;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> { value.serialize(self) }
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<()> {
+		unsupported("serialize_newtype_variant")
+	}
+}
+
+impl<'a> ser::SerializeStruct for Serializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+		value.serialize(ValueSerializer { format: self.0, key })
+	}
+
+	fn end(self) -> Result<()> { Ok(()) }
+}
+
+
+/// Serializer of the field `key`.
+struct ValueSerializer<'a> {
+	format: &'a mut FlipperFormat,
+	key: &'static str,
+}
+
+impl<'a> ValueSerializer<'a> {
+	fn range<T, R: TryFrom<T>>(&self, value: T) -> Result<R> { R::try_from(value).map_err(|_| Error::Write(self.key.to_string())) }
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = ArraySerializer<'a>;
+	type SerializeTuple = ArraySerializer<'a>;
+	type SerializeTupleStruct = ArraySerializer<'a>;
+	type SerializeTupleVariant = Impossible<(), Error>;
+	type SerializeMap = Impossible<(), Error>;
+	type SerializeStruct = Impossible<(), Error>;
+	type SerializeStructVariant = Impossible<(), Error>;
+
+	fn serialize_bool(self, v: bool) -> Result<()> { self.format.write_bool(self.key, v) }
+	fn serialize_i8(self, v: i8) -> Result<()> { self.format.write_i32(self.key, v.into()) }
+	fn serialize_i16(self, v: i16) -> Result<()> { self.format.write_i32(self.key, v.into()) }
+	fn serialize_i32(self, v: i32) -> Result<()> { self.format.write_i32(self.key, v) }
+	fn serialize_i64(self, v: i64) -> Result<()> { self.format.write_i32(self.key, self.range(v)?) }
+	fn serialize_u8(self, v: u8) -> Result<()> { self.format.write_u32(self.key, v.into()) }
+	fn serialize_u16(self, v: u16) -> Result<()> { self.format.write_u32(self.key, v.into()) }
+	fn serialize_u32(self, v: u32) -> Result<()> { self.format.write_u32(self.key, v) }
+	fn serialize_u64(self, v: u64) -> Result<()> { self.format.write_u32(self.key, self.range(v)?) }
+	fn serialize_f32(self, v: f32) -> Result<()> { self.format.write_float(self.key, v) }
+	fn serialize_f64(self, v: f64) -> Result<()> { self.format.write_float(self.key, v as f32) }
+	fn serialize_char(self, v: char) -> Result<()> { self.format.write_string(self.key, v.encode_utf8(&mut [0; 4]) as &str) }
+	fn serialize_str(self, v: &str) -> Result<()> { self.format.write_string(self.key, v) }
+	fn serialize_bytes(self, v: &[u8]) -> Result<()> { self.format.write_hex(self.key, v) }
+	fn serialize_none(self) -> Result<()> { Ok(()) }
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> { value.serialize(self) }
+
+	fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<()> {
+		self.format.write_string(self.key, variant)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<()> { value.serialize(self) }
+
+	fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+		Ok(ArraySerializer { format: self.format,
+		                     key: self.key,
+		                     values: Values::Empty })
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> { self.serialize_seq(Some(len)) }
+
+	fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<Self::SerializeTupleStruct> {
+		self.serialize_seq(Some(len))
+	}
+
+	unsupported! {
+		serialize_unit() -> ();
+		serialize_unit_struct(&'static str) -> ();
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_map(Option<usize>) -> Self::SerializeMap;
+		serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<()> {
+		unsupported("serialize_newtype_variant")
+	}
+}
+
+
+/// Element of the array, `u8` is written as hex.
+#[derive(Clone, Copy)]
+enum Element {
+	Hex(u8),
+	U32(u32),
+	I32(i32),
+	Bool(bool),
+	Float(f32),
+}
+
+/// Values of the array, all of the same type.
+enum Values {
+	Empty,
+	Hex(Vec<u8>),
+	U32(Vec<u32>),
+	I32(Vec<i32>),
+	Bool(Vec<bool>),
+	Float(Vec<f32>),
+}
+
+struct ArraySerializer<'a> {
+	format: &'a mut FlipperFormat,
+	key: &'static str,
+	values: Values,
+}
+
+impl<'a> ArraySerializer<'a> {
+	fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+		match (&mut self.values, value.serialize(ElementSerializer)?) {
+			(Values::Hex(values), Element::Hex(v)) => values.push(v),
+			(Values::U32(values), Element::U32(v)) => values.push(v),
+			(Values::I32(values), Element::I32(v)) => values.push(v),
+			(Values::Bool(values), Element::Bool(v)) => values.push(v),
+			(Values::Float(values), Element::Float(v)) => values.push(v),
+			(Values::Empty, Element::Hex(v)) => self.values = Values::Hex(vec![v]),
+			(Values::Empty, Element::U32(v)) => self.values = Values::U32(vec![v]),
+			(Values::Empty, Element::I32(v)) => self.values = Values::I32(vec![v]),
+			(Values::Empty, Element::Bool(v)) => self.values = Values::Bool(vec![v]),
+			(Values::Empty, Element::Float(v)) => self.values = Values::Float(vec![v]),
+			_ => return Err(Error::Write(self.key.to_string())),
+		}
+		Ok(())
+	}
+
+	fn write(self) -> Result<()> {
+		match &self.values {
+			Values::Empty => self.format.write_u32_array(self.key, &[]),
+			Values::Hex(values) => self.format.write_hex(self.key, values),
+			Values::U32(values) => self.format.write_u32_array(self.key, values),
+			Values::I32(values) => self.format.write_i32_array(self.key, values),
+			Values::Bool(values) => self.format.write_bool_array(self.key, values),
+			Values::Float(values) => self.format.write_float_array(self.key, values),
+		}
+	}
+}
+
+impl<'a> ser::SerializeSeq for ArraySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> { self.push(value) }
+	fn end(self) -> Result<()> { self.write() }
+}
+
+impl<'a> ser::SerializeTuple for ArraySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> { self.push(value) }
+	fn end(self) -> Result<()> { self.write() }
+}
+
+impl<'a> ser::SerializeTupleStruct for ArraySerializer<'a> {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> { self.push(value) }
+	fn end(self) -> Result<()> { self.write() }
+}
+
+
+struct ElementSerializer;
+
+impl ser::Serializer for ElementSerializer {
+	type Ok = Element;
+	type Error = Error;
+	type SerializeSeq = Impossible<Element, Error>;
+	type SerializeTuple = Impossible<Element, Error>;
+	type SerializeTupleStruct = Impossible<Element, Error>;
+	type SerializeTupleVariant = Impossible<Element, Error>;
+	type SerializeMap = Impossible<Element, Error>;
+	type SerializeStruct = Impossible<Element, Error>;
+	type SerializeStructVariant = Impossible<Element, Error>;
+
+	fn serialize_bool(self, v: bool) -> Result<Element> { Ok(Element::Bool(v)) }
+	fn serialize_i8(self, v: i8) -> Result<Element> { Ok(Element::I32(v.into())) }
+	fn serialize_i16(self, v: i16) -> Result<Element> { Ok(Element::I32(v.into())) }
+	fn serialize_i32(self, v: i32) -> Result<Element> { Ok(Element::I32(v)) }
+	fn serialize_i64(self, v: i64) -> Result<Element> { v.try_into().map(Element::I32).or_else(|_| unsupported("i64 out of i32")) }
+	fn serialize_u8(self, v: u8) -> Result<Element> { Ok(Element::Hex(v)) }
+	fn serialize_u16(self, v: u16) -> Result<Element> { Ok(Element::U32(v.into())) }
+	fn serialize_u32(self, v: u32) -> Result<Element> { Ok(Element::U32(v)) }
+	fn serialize_u64(self, v: u64) -> Result<Element> { v.try_into().map(Element::U32).or_else(|_| unsupported("u64 out of u32")) }
+	fn serialize_f32(self, v: f32) -> Result<Element> { Ok(Element::Float(v)) }
+	fn serialize_f64(self, v: f64) -> Result<Element> { Ok(Element::Float(v as f32)) }
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<Element> { value.serialize(self) }
+
+	unsupported! {
+		serialize_char(char) -> Element;
+		serialize_str(&str) -> Element;
+		serialize_bytes(&[u8]) -> Element;
+		serialize_none() -> Element;
+		serialize_unit() -> Element;
+		serialize_unit_struct(&'static str) -> Element;
+		serialize_unit_variant(&'static str, u32, &'static str) -> Element;
+		serialize_seq(Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_map(Option<usize>) -> Self::SerializeMap;
+		serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Element> { unsupported("serialize_some") }
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<Element> {
+		unsupported("serialize_newtype_variant")
+	}
+}
+
+
+/// Deserializer of the struct.
+pub struct Deserializer<'a>(pub &'a mut FlipperFormat);
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> { unsupported("deserialize_any") }
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+		visitor.visit_map(Fields { format: self.0,
+		                           fields: fields.iter(),
+		                           key: "" })
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+		seq tuple tuple_struct map enum identifier ignored_any
+	}
+}
+
+
+/// Existing fields of the struct.
+struct Fields<'a> {
+	format: &'a mut FlipperFormat,
+	fields: core::slice::Iter<'static, &'static str>,
+	key: &'static str,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Fields<'a> {
+	type Error = Error;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+		for key in self.fields.by_ref() {
+			self.format.rewind();
+			if self.format.key_exists(key) {
+				self.key = key;
+				return seed.deserialize(key.into_deserializer()).map(Some);
+			}
+		}
+		Ok(None)
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+		seed.deserialize(ValueDeserializer { format: self.format,
+		                                     key: self.key })
+	}
+}
+
+
+/// Deserializer of the field `key`, reads it from the start.
+struct ValueDeserializer<'a> {
+	format: &'a mut FlipperFormat,
+	key: &'static str,
+}
+
+impl<'a> ValueDeserializer<'a> {
+	fn read<T>(self, read: fn(&mut FlipperFormat, &str) -> Result<T>) -> Result<T> {
+		self.format.rewind();
+		read(self.format, self.key)
+	}
+
+	fn string(self) -> Result<String> {
+		let key = self.key;
+		let value = self.read(FlipperFormat::read_string)?;
+		let value = value.as_c_str().to_str().map_err(|_| Error::Read(key.to_string()))?;
+		Ok(value.to_string())
+	}
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { visitor.visit_string(self.string()?) }
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_bool(self.read(FlipperFormat::read_bool)?)
+	}
+	fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_i32(visitor) }
+	fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_i32(visitor) }
+	fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_i32(self.read(FlipperFormat::read_i32)?)
+	}
+	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_i32(visitor) }
+	fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_u32(visitor) }
+	fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_u32(visitor) }
+	fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_u32(self.read(FlipperFormat::read_u32)?)
+	}
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_u32(visitor) }
+	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_f32(self.read(FlipperFormat::read_float)?)
+	}
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_f32(visitor) }
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_any(visitor) }
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_any(visitor) }
+	fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_any(visitor) }
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_byte_buf(visitor) }
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_byte_buf(self.read(FlipperFormat::read_hex)?)
+	}
+	/// Missing field is `None`, because only existing fields are deserialized.
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { visitor.visit_some(self) }
+	fn deserialize_unit<V: Visitor<'de>>(self, _: V) -> Result<V::Value> { unsupported("deserialize_unit") }
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _: &'static str, _: V) -> Result<V::Value> {
+		unsupported("deserialize_unit_struct")
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		self.format.rewind();
+		let len = self.format.value_count(self.key)?;
+		visitor.visit_seq(Array { format: self.format,
+		                          key: self.key,
+		                          len,
+		                          index: 0,
+		                          values: Values::Empty })
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> { self.deserialize_seq(visitor) }
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _: &'static str, _: usize, visitor: V) -> Result<V::Value> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, _: V) -> Result<V::Value> { unsupported("deserialize_map") }
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], _: V) -> Result<V::Value> {
+		unsupported("deserialize_struct")
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], visitor: V) -> Result<V::Value> {
+		visitor.visit_enum(self.string()?.into_deserializer())
+	}
+
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { self.deserialize_any(visitor) }
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> { visitor.visit_unit() }
+}
+
+
+/// Values of the sequence, read on the first element as its type.
+struct Array<'a> {
+	format: &'a mut FlipperFormat,
+	key: &'static str,
+	len: usize,
+	index: usize,
+	values: Values,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Array<'a> {
+	type Error = Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+		if self.index == self.len {
+			return Ok(None);
+		}
+		let value = seed.deserialize(ElementDeserializer(self)).map(Some);
+		self.index += 1;
+		value
+	}
+
+	fn size_hint(&self) -> Option<usize> { Some(self.len - self.index) }
+}
+
+
+struct ElementDeserializer<'b, 'a>(&'b mut Array<'a>);
+
+macro_rules! element {
+	($($method:ident: $variant:ident($read:ident) => $visit:ident;)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				let array = self.0;
+				if let Values::Empty = array.values {
+					array.format.rewind();
+					array.values = Values::$variant(array.format.$read(array.key)?);
+				}
+				match &array.values {
+					Values::$variant(values) if array.index < values.len() => visitor.$visit(values[array.index]),
+					_ => Err(Error::Read(array.key.to_string())),
+				}
+			}
+		)*
+	};
+}
+
+impl<'de, 'b, 'a> de::Deserializer<'de> for ElementDeserializer<'b, 'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> { unsupported("deserialize_any") }
+
+	element! {
+		deserialize_bool: Bool(read_bool_array) => visit_bool;
+		deserialize_i8: I32(read_i32_array) => visit_i32;
+		deserialize_i16: I32(read_i32_array) => visit_i32;
+		deserialize_i32: I32(read_i32_array) => visit_i32;
+		deserialize_i64: I32(read_i32_array) => visit_i32;
+		deserialize_u8: Hex(read_hex) => visit_u8;
+		deserialize_u16: U32(read_u32_array) => visit_u32;
+		deserialize_u32: U32(read_u32_array) => visit_u32;
+		deserialize_u64: U32(read_u32_array) => visit_u32;
+		deserialize_f32: Float(read_float_array) => visit_f32;
+		deserialize_f64: Float(read_float_array) => visit_f32;
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	forward_to_deserialize_any! {
+		i128 u128 char str string bytes byte_buf option unit unit_struct
+		seq tuple tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use ::serde::{Deserialize, Serialize};
+	use super::*;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Mode {
+		Slow,
+		Fast,
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Settings {
+		speed: u16,
+		offset: i8,
+		enabled: bool,
+		ratio: f32,
+		key: [u8; 4],
+		data: Vec<u8>,
+		channels: Vec<u32>,
+		name: String,
+		mode: Mode,
+		extra: Option<i32>,
+	}
+
+	#[test]
+	fn round_trip() {
+		let settings = Settings { speed: 300,
+		                          offset: -3,
+		                          enabled: true,
+		                          ratio: 1.5,
+		                          key: [0xDE, 0xAD, 0xBE, 0xEF],
+		                          data: Vec::new(),
+		                          channels: vec![1, 2, 3],
+		                          name: "my settings".to_string(),
+		                          mode: Mode::Fast,
+		                          extra: None };
+		let mut format = FlipperFormat::string().unwrap();
+		format.write_header("Settings", 1).unwrap();
+		format.serialize(&settings).unwrap();
+
+		assert!(format.rewind());
+		assert_eq!(Ok(1), format.check_header("Settings", 1..=1));
+		assert_eq!(Ok(vec![0xDE, 0xAD, 0xBE, 0xEF]), format.read_hex("key"));
+		assert!(format.rewind());
+		assert!(!format.key_exists("extra"));
+		assert_eq!(Ok(settings), format.deserialize());
+
+		assert!(format.seek_to_end());
+		format.write_i32("extra", 7).unwrap();
+		assert_eq!(Ok(Some(7)), format.deserialize::<Settings>().map(|settings| settings.extra));
+	}
+}
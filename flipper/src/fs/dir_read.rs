@@ -3,7 +3,6 @@ use crate::AsPtr;
 use sys::ffi;
 use sys::error::fs::Error;
 use sys::error::fs::Status;
-use super::record::Record;
 use super::Storage;
 use super::Metadata;
 use super::File;
@@ -41,21 +40,22 @@ use super::Result;
 pub struct ReadDir<const NAME_BUF_LEN: usize = { FILE_NAME_LEN_MAX as usize }, const WITH_INFO: bool = false>(File);
 
 
-impl<T: Record> Storage<T> where [(); T::LEN]: Sized {
-	/// Note: `path` of directory must not ends with a trailing slash.
+impl Storage {
+	/// Trailing slashes of the `path` are removed, because the firmware fails to open such directory.
 	pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> Result<ReadDir> {
-		let path = path.as_ref();
+		let path = path.as_ref().normalize();
 
 		unsafe {
-			let dir = ffi::storage_file_alloc(self.0.as_ptr());
+			// freed on drop, also on the error path:
+			let dir = NonNull::new(ffi::storage_file_alloc(self.0.as_ptr())).map(File)
+			                                                                .ok_or(Error::Internal)?;
 
-			// TODO: remove trailing slash from path
-			if ffi::storage_dir_open(dir, path.as_ptr() as _) {
-			} else {
-				ffi::storage_file_get_error(dir)?;
+			if !ffi::storage_dir_open(dir.as_ptr(), path.as_ptr() as _) {
+				ffi::storage_file_get_error(dir.as_ptr())?;
+				return Err(Error::Internal);
 			}
 
-			NonNull::new(dir).map(File).ok_or(Error::Internal).map(ReadDir::new)
+			Ok(ReadDir::new(dir))
 		}
 	}
 }
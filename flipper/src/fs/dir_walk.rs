@@ -6,7 +6,6 @@ use crate::string::OsString;
 use sys::ffi;
 use sys::error::fs::Error;
 use sys::error::fs::Status;
-use super::record::Record;
 use super::Storage;
 use super::Metadata;
 use super::Path;
@@ -58,23 +57,21 @@ impl crate::AsPtr<ffi::DirWalk> for CloseOnDrop {
 pub struct WalkDir<const WITH_INFO: bool = false>(CloseOnDrop);
 
 
-impl<T: Record> Storage<T> where [(); T::LEN]: Sized {
-	/// Note: `path` of directory must not ends with a trailing slash.
+impl Storage {
+	/// Trailing slashes of the `path` are removed, because the firmware fails to open such directory.
 	pub fn walk_dir<P: AsRef<Path>>(&self, path: P) -> Result<WalkDir> {
-		let path = path.as_ref();
+		let path = path.as_ref().normalize();
 
 		unsafe {
-			let dir = ffi::dir_walk_alloc(self.0.as_ptr());
+			// closed and freed on drop, also on the error path:
+			let dir = WalkDir::new(ffi::dir_walk_alloc(self.0.as_ptr()))?;
 
-			// TODO: remove trailing slash from path
-			if ffi::dir_walk_open(dir, path.as_ptr() as _) {
-				crate::println!("dir_walk_open success");
-			} else {
-				crate::println!("dir_walk_open FAIL");
-				ffi::dir_walk_get_error(dir)?;
+			if !ffi::dir_walk_open(dir.0.as_ptr(), path.as_ptr() as _) {
+				ffi::dir_walk_get_error(dir.0.as_ptr())?;
+				return Err(Error::Internal);
 			}
 
-			WalkDir::new(dir)
+			Ok(dir)
 		}
 	}
 }
@@ -193,7 +190,7 @@ pub struct Entry<Info> {
 
 
 impl<Meta> Entry<Meta> {
-	pub fn path(&self) -> &CStr { self.path.as_c_str() }
+	pub fn path(&self) -> &Path { Path::new(self.path.as_c_str()) }
 }
 
 
@@ -4,44 +4,46 @@ use core::ptr::NonNull;
 use crate::AsPtr;
 use crate::error::NullPointerError;
 use crate::path::Path;
+use crate::io;
+use crate::alloc::vec::Vec;
 use sys::ffi;
 use sys::ffi::{FS_AccessMode, FS_OpenMode};
 pub use sys::error::fs::Error;
 
-pub use record::{Record, RecordExt};
 pub use dir_read::ReadDir;
 pub use dir_walk::WalkDir;
+pub use open_options::OpenOptions;
+#[allow(deprecated)]
+pub use record::{Record, RecordExt};
 
 
+#[deprecated(note = "use `crate::record` instead")]
 pub mod record;
 pub mod dir_read;
 pub mod dir_walk;
+pub mod open_options;
 
 
 pub type Result<T, E = Error> = sys::result::Result<T, E>;
 const FILE_NAME_LEN_MAX: u16 = 256;
+type StorageRecord = crate::record::Record<crate::record::Storage>;
 
 
-// XXX: use Unique<T> instead?
-pub struct Storage<T: Record = record::Storage>(NonNull<ffi::Storage>, record::CloseOnDrop<T>) where [(); T::LEN]: Sized;
+/// Opened storage record, closed on drop.
+pub struct Storage(StorageRecord);
 
 
-impl Storage<record::Storage> {
-	pub fn open_default() -> Result<Self, NullPointerError> { Self::open(record::Storage) }
-}
+impl Storage {
+	/// Opens the storage record.
+	/// Returns `Err` if got null pointer by API.
+	pub fn open_default() -> Result<Self, NullPointerError> { StorageRecord::open().map(Self) }
 
-
-impl<T: Record> Storage<T> where [(); T::LEN]: Sized {
-	/// Allocate & open `Storage` instance.
-	/// Returns `None` if got null pointer by API.
-	pub fn open(record: T) -> Result<Storage<T>, NullPointerError> {
-		let raw = unsafe { ffi::furi_record_open(record.tag().as_ptr() as _) };
-		let p = NonNull::new(raw as *mut ffi::Storage);
-		p.map(|p| Self(p, record::CloseOnDrop::new(record))).ok_or(NullPointerError)
-	}
+	#[deprecated(note = "`Storage` is no longer generic over the record, use `Storage::open_default` instead")]
+	#[allow(deprecated)]
+	pub fn open(_record: record::Storage) -> Result<Self, NullPointerError> { Self::open_default() }
 
 	#[inline]
-	pub fn close(self) { core::mem::drop(self.1); }
+	pub fn close(self) { core::mem::drop(self); }
 
 
 	pub fn metadata<P: AsRef<Path>>(&self, path: P) -> Result<MetadataOwned> {
@@ -103,8 +105,9 @@ impl<T: Record> Storage<T> where [(); T::LEN]: Sized {
 
 	/// File-system information.
 	/// ```ignore
-	/// use flipper0::fs::{Storage, Path};
-	/// Storage::default().info(Path::from_ptr(flipper0::ffi::STORAGE_EXT_PATH_PREFIX.as_ptr() as _));
+	/// use flipper0::fs::Storage;
+	/// use flipper0::path::Path;
+	/// Storage::default().info(Path::ext());
 	/// ```
 	pub fn info<P: AsRef<Path>>(&self, fs: P) -> Result<StorageInfo> {
 		let path = fs.as_ref();
@@ -134,7 +137,11 @@ pub struct StorageInfo {
 }
 
 
-impl<T: Record> const crate::AsPtr<ffi::Storage> for Storage<T> where [(); T::LEN]: Sized {
+impl From<StorageRecord> for Storage {
+	fn from(record: StorageRecord) -> Self { Self(record) }
+}
+
+impl const crate::AsPtr<ffi::Storage> for Storage {
 	#[inline]
 	fn as_ptr(&self) -> *mut ffi::Storage { self.0.as_ptr() }
 }
@@ -146,8 +153,7 @@ pub struct File(NonNull<ffi::File>);
 impl File {
 	/// Allocates `File` instance.
 	/// Returns `None` if got null pointer by API.
-	pub fn new<T: Record>(storage: &Storage<T>) -> Option<Self>
-		where [(); T::LEN]: Sized {
+	pub fn new(storage: &Storage) -> Option<Self> {
 		unsafe {
 			let raw = ffi::storage_file_alloc(storage.as_ptr());
 			NonNull::new(raw).map(Self)
@@ -159,7 +165,10 @@ impl File {
 	pub fn is_dir(&self) -> bool { unsafe { ffi::storage_file_is_dir(self.as_ptr()) } }
 
 
-	pub fn open<P: AsRef<Path>>(&mut self, path: P, access_mode: FS_AccessMode, open_mode: FS_OpenMode) -> Result<()> {
+	/// Opens the file with raw firmware modes.
+	///
+	/// See also [`OpenOptions`], [`File::open`] and [`File::create`].
+	pub fn open_with<P: AsRef<Path>>(&mut self, path: P, access_mode: FS_AccessMode, open_mode: FS_OpenMode) -> Result<()> {
 		let path = path.as_ref();
 		unsafe {
 			if ffi::storage_file_open(self.as_ptr(), path.as_ptr() as _, access_mode, open_mode) {
@@ -185,27 +194,6 @@ impl File {
 	}
 
 
-	pub fn read(&mut self, buf: &mut [u8]) -> Result<u16> {
-		unsafe { Ok(ffi::storage_file_read(self.as_ptr(), buf.as_mut_ptr() as _, buf.len() as _)) }
-	}
-
-	pub fn write(&mut self, buf: &[u8]) -> Result<u16> {
-		unsafe { Ok(ffi::storage_file_write(self.as_ptr(), buf.as_ptr() as _, buf.len() as _)) }
-	}
-
-
-	pub fn seek(&mut self, offset: u32, from_start: bool) -> Result<()> {
-		unsafe {
-			if ffi::storage_file_seek(self.as_ptr(), offset, from_start) {
-				Ok(())
-			} else {
-				ffi::storage_file_get_error(self.as_ptr())?;
-				// should be unreachable
-				unreachable!();
-			}
-		}
-	}
-
 	pub fn tell(&mut self) -> u64 { unsafe { ffi::storage_file_tell(self.as_ptr()) } }
 	pub fn size(&mut self) -> u64 { unsafe { ffi::storage_file_size(self.as_ptr()) } }
 	pub fn eof(&mut self) -> bool { unsafe { ffi::storage_file_eof(self.as_ptr()) } }
@@ -243,34 +231,97 @@ impl const crate::AsPtr<ffi::File> for File {
 	fn as_ptr(&self) -> *mut ffi::File { self.0.as_ptr() }
 }
 
-impl core::fmt::Write for File {
-	fn write_str(&mut self, s: &str) -> core::fmt::Result {
-		let bytes = s.as_bytes();
-		if bytes.len() != self.write(bytes).map_err(|_| core::fmt::Error)? as usize {
-			Err(core::fmt::Error)
-		} else {
-			Ok(())
+impl io::Read for File {
+	/// Reads up to `u16::MAX` bytes at once.
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = buf.len().min(u16::MAX as usize) as u16;
+		unsafe {
+			let read = ffi::storage_file_read(self.as_ptr(), buf.as_mut_ptr() as _, len);
+			if read < len {
+				ffi::storage_file_get_error(self.as_ptr())?;
+			}
+			Ok(read as usize)
+		}
+	}
+
+	fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+		let remaining = self.size().saturating_sub(self.tell());
+		buf.reserve(remaining as usize);
+
+		let start = buf.len();
+		loop {
+			if buf.len() == buf.capacity() {
+				buf.reserve(32);
+			}
+			// reads right into the spare capacity, up to `u16::MAX` bytes at once:
+			let spare = buf.spare_capacity_mut();
+			let len = spare.len().min(u16::MAX as usize) as u16;
+			unsafe {
+				let read = ffi::storage_file_read(self.as_ptr(), spare.as_mut_ptr() as _, len);
+				buf.set_len(buf.len() + read as usize);
+				if read < len {
+					ffi::storage_file_get_error(self.as_ptr())?;
+				}
+				if read == 0 {
+					return Ok(buf.len() - start);
+				}
+			}
 		}
 	}
 }
 
+impl io::Write for File {
+	/// Writes up to `u16::MAX` bytes at once.
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let len = buf.len().min(u16::MAX as usize) as u16;
+		unsafe {
+			let written = ffi::storage_file_write(self.as_ptr(), buf.as_ptr() as _, len);
+			if written < len {
+				ffi::storage_file_get_error(self.as_ptr())?;
+			}
+			Ok(written as usize)
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> { self.sync().map_err(Into::into) }
+}
+
+impl io::Seek for File {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		let offset = match pos {
+			io::SeekFrom::Start(offset) => Some(offset),
+			io::SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+			io::SeekFrom::Current(offset) => self.tell().checked_add_signed(offset),
+		};
+		let offset = offset.and_then(|offset| u32::try_from(offset).ok())
+		                   .ok_or(io::Error::InvalidInput)?;
+
+		unsafe {
+			if !ffi::storage_file_seek(self.as_ptr(), offset, true) {
+				ffi::storage_file_get_error(self.as_ptr())?;
+			}
+		}
+		Ok(self.tell())
+	}
+}
+
+impl core::fmt::Write for File {
+	fn write_str(&mut self, s: &str) -> core::fmt::Result { io::Write::write_all(self, s.as_bytes()).map_err(|_| core::fmt::Error) }
+}
+
 /// Very gentle drop with close & dealloc.
 impl Drop for File {
 	fn drop(&mut self) {
 		unsafe {
 			let ptr = self.as_ptr();
-			if !ptr.is_null() {
-				let success = if ffi::storage_file_is_dir(ptr) {
-					ffi::storage_dir_close(ptr)
+			if ffi::storage_file_is_open(ptr) {
+				if ffi::storage_file_is_dir(ptr) {
+					ffi::storage_dir_close(ptr);
 				} else {
-					ffi::storage_file_close(ptr)
-				};
-
-				// if `close` failed, then we need to destroy the file descriptor
-				if !success && !ptr.is_null() {
-					ffi::storage_file_free(ptr);
+					ffi::storage_file_close(ptr);
 				}
 			}
+			ffi::storage_file_free(ptr);
 		}
 	}
 }
@@ -325,3 +376,142 @@ impl<'a> core::fmt::Debug for Metadata<'a> {
 		 .finish()
 	}
 }
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use core::ffi::CStr;
+	use crate::alloc::string::String;
+	use crate::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+	fn path(bytes: &[u8]) -> &CStr { CStr::from_bytes_with_nul(bytes).unwrap() }
+
+
+	#[test]
+	fn write_read_remove() {
+		let storage = Storage::open_default().unwrap();
+		let dir = path(b"/ext/write_read_remove\0");
+		let file_path = path(b"/ext/write_read_remove/file.txt\0");
+		storage.create_dir(dir).unwrap();
+
+		let mut file = File::new(&storage).unwrap();
+		file.open_with(file_path, FS_AccessMode::FSAM_WRITE, FS_OpenMode::FSOM_CREATE_ALWAYS)
+		    .unwrap();
+		assert_eq!(5, file.write(b"hello").unwrap());
+		drop(file);
+
+		let meta = storage.metadata(file_path).unwrap();
+		assert!(!meta.is_dir());
+		assert_eq!(5, meta.size());
+
+		let mut file = File::new(&storage).unwrap();
+		file.open_with(file_path, FS_AccessMode::FSAM_READ, FS_OpenMode::FSOM_OPEN_EXISTING)
+		    .unwrap();
+		let mut buf = [0; 8];
+		assert_eq!(5, file.read(&mut buf).unwrap());
+		assert_eq!(b"hello", &buf[..5]);
+		drop(file);
+
+		let mut entries = storage.read_dir(dir).unwrap().with_info();
+		let entry = entries.next().unwrap().unwrap();
+		assert_eq!(path(b"file.txt\0"), entry.file_name().unwrap());
+		assert_eq!(5, entry.metadata().size());
+		assert!(entries.next().is_none());
+		drop(entries);
+
+		storage.remove(file_path).unwrap();
+		storage.remove(dir).unwrap();
+		assert!(matches!(storage.metadata(dir), Err(Error::NotExist)));
+		assert!(matches!(storage.read_dir(dir), Err(Error::NotExist)));
+		assert!(matches!(storage.walk_dir(dir), Err(Error::NotExist)));
+	}
+
+
+	#[test]
+	fn io_traits() {
+		let storage = Storage::open_default().unwrap();
+		let file_path = path(b"/ext/io_traits.txt\0");
+
+		let mut file = File::new(&storage).unwrap();
+		file.open_with(file_path, FS_AccessMode::FSAM_READ_WRITE, FS_OpenMode::FSOM_CREATE_ALWAYS)
+		    .unwrap();
+		let mut writer = BufWriter::with_capacity(4, file);
+		writeln!(writer, "first").unwrap();
+		writer.write_all(b"second\r\nthird").unwrap();
+		let mut file = writer.into_inner().unwrap();
+
+		assert_eq!(5, file.seek(SeekFrom::End(-14)).unwrap());
+		assert_eq!(0, file.seek(SeekFrom::Current(-5)).unwrap());
+		assert!(matches!(file.seek(SeekFrom::Current(-1)), Err(crate::io::Error::InvalidInput)));
+
+		let mut content = String::new();
+		assert_eq!(19, file.read_to_string(&mut content).unwrap());
+		assert_eq!("first\nsecond\r\nthird", content);
+
+		file.rewind().unwrap();
+		let mut reader = BufReader::with_capacity(4, file);
+		let mut buf = [0; 2];
+		reader.read_exact(&mut buf).unwrap();
+		assert_eq!(b"fi", &buf);
+		let lines: Result<Vec<_>, _> = reader.by_ref().lines().collect();
+		assert_eq!(["rst", "second", "third"].as_slice(), lines.unwrap());
+
+		let mut file = reader.into_inner();
+		assert!(matches!(file.read_exact(&mut buf), Err(crate::io::Error::UnexpectedEof)));
+		drop(file);
+
+		storage.remove(file_path).unwrap();
+	}
+
+	#[test]
+	fn read_to_end_large() {
+		let storage = Storage::open_default().unwrap();
+		let file_path = path(b"/ext/read_to_end_large.bin\0");
+		let data: Vec<u8> = (0..70_000u32).map(|i| i as u8).collect();
+		storage.write(file_path, &data).unwrap();
+
+		let mut buf = Vec::from(*b"head");
+		assert_eq!(
+		           data.len(),
+		           File::open(&storage, file_path).unwrap().read_to_end(&mut buf).unwrap()
+		);
+		assert_eq!(b"head", &buf[..4]);
+		assert_eq!(data, buf[4..]);
+
+		let mut reader = BufReader::with_capacity(16, File::open(&storage, file_path).unwrap());
+		let mut buf = Vec::new();
+		assert_eq!(data.len(), reader.read_to_end(&mut buf).unwrap());
+		assert_eq!(data, buf);
+		drop(reader);
+
+		storage.remove(file_path).unwrap();
+	}
+
+	#[test]
+	fn open_options() {
+		let storage = Storage::open_default().unwrap();
+		let file_path = path(b"/ext/open_options.txt\0");
+
+		storage.write(file_path, "hello").unwrap();
+		assert_eq!(b"hello", storage.read(file_path).unwrap().as_slice());
+
+		let mut file = OpenOptions::new().append(true).open(&storage, file_path).unwrap();
+		file.write_all(b", world").unwrap();
+		drop(file);
+		assert_eq!("hello, world", storage.read_to_string(file_path).unwrap());
+
+		let result = OpenOptions::new().write(true).create_new(true).open(&storage, file_path);
+		assert!(matches!(result, Err(Error::Exist)));
+		assert!(matches!(OpenOptions::new().open(&storage, file_path), Err(Error::InvalidParameter)));
+
+		drop(OpenOptions::new().write(true)
+		                       .truncate(true)
+		                       .open(&storage, file_path)
+		                       .unwrap());
+		assert_eq!(0, storage.metadata(file_path).unwrap().size());
+
+		storage.remove(file_path).unwrap();
+		assert!(matches!(File::open(&storage, file_path), Err(Error::NotExist)));
+	}
+}
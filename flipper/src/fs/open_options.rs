@@ -0,0 +1,158 @@
+use sys::ffi::{FS_AccessMode, FS_OpenMode};
+use crate::path::Path;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::io::{self, Read, Write};
+use super::{Error, File, Result, Storage};
+
+
+/// Options and flags which can be used to configure how a file is opened.
+///
+/// Like in the std, but has to be opened with [`Storage`]:
+/// ```ignore
+/// let storage = Storage::open_default()?;
+/// let file = OpenOptions::new().append(true).open(&storage, path)?;
+/// ```
+///
+/// Flags are mapped onto [`FS_OpenMode`] this way:
+/// - `create_new` - `FSOM_CREATE_NEW`
+/// - `create` + `truncate` - `FSOM_CREATE_ALWAYS`
+/// - `append` - `FSOM_OPEN_APPEND`, note that firmware creates missing file in this mode
+/// - `create` - `FSOM_OPEN_ALWAYS`
+/// - otherwise - `FSOM_OPEN_EXISTING`, with following truncation if `truncate` is set.
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+	read: bool,
+	write: bool,
+	append: bool,
+	truncate: bool,
+	create: bool,
+	create_new: bool,
+}
+
+
+impl OpenOptions {
+	/// Creates options with all flags set to `false`.
+	pub fn new() -> Self { Self::default() }
+
+	pub fn read(&mut self, read: bool) -> &mut Self {
+		self.read = read;
+		self
+	}
+
+	pub fn write(&mut self, write: bool) -> &mut Self {
+		self.write = write;
+		self
+	}
+
+	/// Sets the option for the append mode, implies `write`.
+	pub fn append(&mut self, append: bool) -> &mut Self {
+		self.append = append;
+		self
+	}
+
+	/// Sets the option for truncating a previous file, requires `write`.
+	pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+		self.truncate = truncate;
+		self
+	}
+
+	/// Sets the option to create a new file or open it if it already exists, requires `write`.
+	pub fn create(&mut self, create: bool) -> &mut Self {
+		self.create = create;
+		self
+	}
+
+	/// Sets the option to create a new file, failing if it already exists, requires `write`.
+	///
+	/// `create` and `truncate` are ignored if set.
+	pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+		self.create_new = create_new;
+		self
+	}
+
+
+	/// Returns modes for the `storage_file_open`.
+	///
+	/// Returns `Error::InvalidParameter` for incompatible set of options.
+	pub fn modes(&self) -> Result<(FS_AccessMode, FS_OpenMode)> {
+		let write = self.write || self.append;
+		let access = match (self.read, write) {
+			(true, false) => FS_AccessMode::FSAM_READ,
+			(false, true) => FS_AccessMode::FSAM_WRITE,
+			(true, true) => FS_AccessMode::FSAM_READ_WRITE,
+			(false, false) => return Err(Error::InvalidParameter),
+		};
+
+		if !write && (self.truncate || self.create || self.create_new) {
+			return Err(Error::InvalidParameter);
+		}
+		if self.append && self.truncate && !self.create_new {
+			return Err(Error::InvalidParameter);
+		}
+
+		let mode = if self.create_new {
+			FS_OpenMode::FSOM_CREATE_NEW
+		} else if self.create && self.truncate {
+			FS_OpenMode::FSOM_CREATE_ALWAYS
+		} else if self.append {
+			FS_OpenMode::FSOM_OPEN_APPEND
+		} else if self.create {
+			FS_OpenMode::FSOM_OPEN_ALWAYS
+		} else {
+			FS_OpenMode::FSOM_OPEN_EXISTING
+		};
+
+		Ok((access, mode))
+	}
+
+	/// Opens a file at `path` with the options specified by `self`.
+	pub fn open<P: AsRef<Path>>(&self, storage: &Storage, path: P) -> Result<File> {
+		let (access, mode) = self.modes()?;
+		let mut file = File::new(storage).ok_or(Error::Internal)?;
+		file.open_with(path, access, mode)?;
+
+		if self.truncate && !self.create && !self.create_new {
+			file.truncate()?;
+		}
+		Ok(file)
+	}
+}
+
+
+impl File {
+	/// Opens a file in read-only mode.
+	pub fn open<P: AsRef<Path>>(storage: &Storage, path: P) -> Result<File> { OpenOptions::new().read(true).open(storage, path) }
+
+	/// Opens a file in write-only mode, creates it if it does not exist, and truncates it if it does.
+	pub fn create<P: AsRef<Path>>(storage: &Storage, path: P) -> Result<File> {
+		OpenOptions::new().write(true).create(true).truncate(true).open(storage, path)
+	}
+
+	pub fn options() -> OpenOptions { OpenOptions::new() }
+}
+
+
+impl Storage {
+	/// Reads the entire contents of a file into a bytes vector.
+	pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+		let mut bytes = Vec::new();
+		File::open(self, path)?.read_to_end(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Reads the entire contents of a file into a string.
+	///
+	/// Returns [`io::Error::InvalidData`] if the contents is not valid UTF-8.
+	pub fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+		let mut s = String::new();
+		File::open(self, path)?.read_to_string(&mut s)?;
+		Ok(s)
+	}
+
+	/// Writes `contents` as the entire contents of a file,
+	/// creates it if it does not exist, and truncates it if it does.
+	pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+		File::create(self, path)?.write_all(contents.as_ref())
+	}
+}
@@ -1,6 +1,12 @@
+//! Record tag of the storage, kept for compatibility.
+//!
+//! Use [`crate::record`] for typed records of all services.
+#![allow(deprecated)]
+
 use super::ffi;
 
 
+#[deprecated(note = "use `crate::record::Service` instead")]
 #[const_trait]
 pub trait Record {
 	const LEN: usize;
@@ -8,6 +14,7 @@ pub trait Record {
 	fn tag(&self) -> &[u8; Self::LEN];
 }
 
+#[deprecated(note = "use `crate::record::Service` instead")]
 #[const_trait]
 pub trait RecordTagRef {
 	fn tag_ref(&self) -> &[u8];
@@ -17,6 +24,7 @@ impl<T: ~const Record> const RecordTagRef for T where [(); Self::LEN]: Sized {
 }
 
 
+#[deprecated(note = "use `crate::record::Record::exists` instead")]
 pub trait RecordExt: Record {
 	fn exists(&self) -> bool;
 }
@@ -32,6 +40,7 @@ impl<T: Record> RecordExt for T where [(); T::LEN]: Sized {
 }
 
 
+#[deprecated(note = "use `crate::record::Storage` instead")]
 pub struct Storage;
 
 impl const Record for Storage {
@@ -40,20 +49,3 @@ impl const Record for Storage {
 }
 
 impl RecordExt for Storage {}
-
-
-pub(crate) struct CloseOnDrop<T>(T)
-	where T: Record,
-	      [(); T::LEN]: Sized;
-
-impl<T: Record> Drop for CloseOnDrop<T> where [(); T::LEN]: Sized {
-	fn drop(&mut self) { unsafe { ffi::furi_record_close(self.0.tag().as_ptr() as _) } }
-}
-
-impl<T> CloseOnDrop<T>
-	where T: Record,
-	      [(); T::LEN]: Sized
-{
-	#[inline(always)]
-	pub fn new(rec: T) -> Self { Self(rec) }
-}
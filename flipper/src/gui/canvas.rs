@@ -0,0 +1,194 @@
+use core::ffi::CStr;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::string::with_c_str;
+
+pub use sys::ffi::Icon;
+
+
+/// Canvas borrowed for the time of the draw callback.
+///
+/// Coordinates are in pixels, `(0, 0)` is the top left corner.
+/// Strings are passed to the firmware as C strings, so `&str`s are cut at the first nul.
+pub struct Canvas<'a>(NonNull<ffi::Canvas>, PhantomData<&'a mut ffi::Canvas>);
+
+
+impl<'a> Canvas<'a> {
+	/// # Safety
+	/// `raw` must be a valid canvas for the lifetime `'a`, e.g. received in a draw callback.
+	pub unsafe fn from_raw(raw: *mut ffi::Canvas) -> Option<Self> { NonNull::new(raw).map(|p| Self(p, PhantomData)) }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Canvas { self.0.as_ptr() }
+
+
+	pub fn width(&self) -> u8 { unsafe { ffi::canvas_width(self.as_ptr()) } }
+	pub fn height(&self) -> u8 { unsafe { ffi::canvas_height(self.as_ptr()) } }
+
+	/// Clears the canvas, sets default color and font.
+	pub fn clear(&mut self) { unsafe { ffi::canvas_clear(self.as_ptr()) } }
+
+	pub fn set_color(&mut self, color: Color) { unsafe { ffi::canvas_set_color(self.as_ptr(), color.into()) } }
+	pub fn invert_color(&mut self) { unsafe { ffi::canvas_invert_color(self.as_ptr()) } }
+
+	/// Enables transparency for the white pixels of bitmaps.
+	pub fn set_bitmap_mode(&mut self, alpha: bool) { unsafe { ffi::canvas_set_bitmap_mode(self.as_ptr(), alpha) } }
+
+
+	pub fn set_font(&mut self, font: Font) { unsafe { ffi::canvas_set_font(self.as_ptr(), font.into()) } }
+	pub fn set_font_direction(&mut self, dir: Direction) { unsafe { ffi::canvas_set_font_direction(self.as_ptr(), dir.into()) } }
+	pub fn current_font_height(&self) -> u8 { unsafe { ffi::canvas_current_font_height(self.as_ptr()) } }
+
+	/// Width of the string in pixels with the current font.
+	pub fn string_width(&self, s: &str) -> u16 { with_c_str(s, |s| unsafe { ffi::canvas_string_width(self.as_ptr(), s.as_ptr()) }) }
+	pub fn glyph_width(&self, symbol: u8) -> u8 { unsafe { ffi::canvas_glyph_width(self.as_ptr(), symbol as _) } }
+
+
+	/// Draws string with the current font, `(x, y)` is the left end of the baseline.
+	///
+	/// Only the part of `s` before the first nul is drawn.
+	pub fn draw_str(&mut self, x: u8, y: u8, s: &str) { with_c_str(s, |s| self.draw_c_str(x, y, s)) }
+
+	pub fn draw_c_str(&mut self, x: u8, y: u8, s: &CStr) { unsafe { ffi::canvas_draw_str(self.as_ptr(), x, y, s.as_ptr()) } }
+
+	/// Draws string aligned relative to `(x, y)`.
+	pub fn draw_str_aligned(&mut self, x: u8, y: u8, horizontal: Align, vertical: Align, s: &str) {
+		with_c_str(s, |s| unsafe {
+			ffi::canvas_draw_str_aligned(self.as_ptr(), x, y, horizontal.into(), vertical.into(), s.as_ptr())
+		})
+	}
+
+	/// Draws unicode glyph with the current font.
+	pub fn draw_glyph(&mut self, x: u8, y: u8, ch: u16) { unsafe { ffi::canvas_draw_glyph(self.as_ptr(), x, y, ch) } }
+
+
+	pub fn draw_dot(&mut self, x: u8, y: u8) { unsafe { ffi::canvas_draw_dot(self.as_ptr(), x, y) } }
+	pub fn draw_line(&mut self, x1: u8, y1: u8, x2: u8, y2: u8) { unsafe { ffi::canvas_draw_line(self.as_ptr(), x1, y1, x2, y2) } }
+
+	/// Draws filled rectangle.
+	pub fn draw_box(&mut self, x: u8, y: u8, width: u8, height: u8) {
+		unsafe { ffi::canvas_draw_box(self.as_ptr(), x, y, width, height) }
+	}
+
+	/// Draws rectangle outline.
+	pub fn draw_frame(&mut self, x: u8, y: u8, width: u8, height: u8) {
+		unsafe { ffi::canvas_draw_frame(self.as_ptr(), x, y, width, height) }
+	}
+
+	/// Draws filled rectangle with rounded corners.
+	pub fn draw_rbox(&mut self, x: u8, y: u8, width: u8, height: u8, radius: u8) {
+		unsafe { ffi::canvas_draw_rbox(self.as_ptr(), x, y, width, height, radius) }
+	}
+
+	/// Draws rectangle outline with rounded corners.
+	pub fn draw_rframe(&mut self, x: u8, y: u8, width: u8, height: u8, radius: u8) {
+		unsafe { ffi::canvas_draw_rframe(self.as_ptr(), x, y, width, height, radius) }
+	}
+
+	/// Draws circle outline, `(x, y)` is the center.
+	pub fn draw_circle(&mut self, x: u8, y: u8, radius: u8) { unsafe { ffi::canvas_draw_circle(self.as_ptr(), x, y, radius) } }
+
+	/// Draws filled circle, `(x, y)` is the center.
+	pub fn draw_disc(&mut self, x: u8, y: u8, radius: u8) { unsafe { ffi::canvas_draw_disc(self.as_ptr(), x, y, radius) } }
+
+	/// Draws filled triangle, `(x, y)` is the middle of the base.
+	pub fn draw_triangle(&mut self, x: u8, y: u8, base: u8, height: u8, dir: Direction) {
+		unsafe { ffi::canvas_draw_triangle(self.as_ptr(), x, y, base, height, dir.into()) }
+	}
+
+
+	/// Draws XBM bitmap, rows are padded to whole bytes, least significant bit first.
+	///
+	/// # Panics
+	/// If `bitmap` is shorter than `(width + 7) / 8 * height` bytes.
+	pub fn draw_xbm(&mut self, x: u8, y: u8, width: u8, height: u8, bitmap: &[u8]) {
+		assert!(
+		        bitmap.len() >= (width as usize + 7) / 8 * height as usize,
+		        "XBM bitmap is too short"
+		);
+		unsafe { ffi::canvas_draw_xbm(self.as_ptr(), x, y, width, height, bitmap.as_ptr()) }
+	}
+
+	/// Draws the current frame of the `icon`.
+	pub fn draw_icon(&mut self, x: u8, y: u8, icon: &Icon) { unsafe { ffi::canvas_draw_icon(self.as_ptr(), x, y, icon) } }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+	White,
+	#[default]
+	Black,
+}
+
+impl From<Color> for ffi::Color {
+	fn from(color: Color) -> Self {
+		match color {
+			Color::White => ffi::Color::ColorWhite,
+			Color::Black => ffi::Color::ColorBlack,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+	Primary,
+	Secondary,
+	Keyboard,
+	BigNumbers,
+}
+
+impl From<Font> for ffi::Font {
+	fn from(font: Font) -> Self {
+		match font {
+			Font::Primary => ffi::Font::FontPrimary,
+			Font::Secondary => ffi::Font::FontSecondary,
+			Font::Keyboard => ffi::Font::FontKeyboard,
+			Font::BigNumbers => ffi::Font::FontBigNumbers,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+	Left,
+	Right,
+	Top,
+	Bottom,
+	Center,
+}
+
+impl From<Align> for ffi::Align {
+	fn from(align: Align) -> Self {
+		match align {
+			Align::Left => ffi::Align::AlignLeft,
+			Align::Right => ffi::Align::AlignRight,
+			Align::Top => ffi::Align::AlignTop,
+			Align::Bottom => ffi::Align::AlignBottom,
+			Align::Center => ffi::Align::AlignCenter,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	LeftToRight,
+	TopToBottom,
+	RightToLeft,
+	BottomToTop,
+}
+
+impl From<Direction> for ffi::CanvasDirection {
+	fn from(dir: Direction) -> Self {
+		match dir {
+			Direction::LeftToRight => ffi::CanvasDirection::CanvasDirectionLeftToRight,
+			Direction::TopToBottom => ffi::CanvasDirection::CanvasDirectionTopToBottom,
+			Direction::RightToLeft => ffi::CanvasDirection::CanvasDirectionRightToLeft,
+			Direction::BottomToTop => ffi::CanvasDirection::CanvasDirectionBottomToTop,
+		}
+	}
+}
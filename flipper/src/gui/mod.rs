@@ -0,0 +1,91 @@
+//! GUI: the `Gui` record, view ports, canvas, views, stock modules and scenes.
+//!
+//! ```ignore
+//! let mut view_port = ViewPort::new()?;
+//! view_port.on_draw(|canvas| {
+//!     canvas.set_font(Font::Primary);
+//!     canvas.draw_str(2, 12, "Hello, Flipper!");
+//! });
+//! view_port.attach(Layer::Fullscreen)?;
+//! ```
+
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::record::{self, Record};
+
+pub use canvas::{Canvas, Color, Font, Align, Direction, Icon};
+pub use view_port::{ViewPort, Orientation};
+pub use view::{View, AsView};
+pub use view_dispatcher::{ViewDispatcher, ViewDispatcherType};
+pub use scene_manager::{SceneManager, SceneManagerBuilder, Scene, SceneContext, SceneDispatcher, SceneEvent};
+pub use modules::{Submenu, Popup, DialogEx, DialogExResult, TextInput, ByteInput, Widget, ButtonType, VariableItemList, VariableItem};
+
+
+pub mod canvas;
+pub mod view_port;
+pub mod view;
+pub mod view_dispatcher;
+pub mod scene_manager;
+pub mod modules;
+
+
+/// Opened `Gui` record, closed on drop.
+pub struct Gui(Record<record::Gui>);
+
+
+impl Gui {
+	pub fn open() -> Result<Self, NullPointerError> { Record::<record::Gui>::open().map(Self) }
+
+	/// Adds `view_port` to the `layer`.
+	///
+	/// Prefer [`ViewPort::attach`] which removes it from the `Gui` on drop.
+	///
+	/// # Safety
+	/// `view_port` must be removed with [`Gui::remove_view_port`] before free.
+	pub unsafe fn add_view_port(&self, view_port: *mut ffi::ViewPort, layer: Layer) {
+		ffi::gui_add_view_port(self.as_ptr(), view_port, layer.into())
+	}
+
+	/// # Safety
+	/// `view_port` must be previously added to this `Gui`.
+	pub unsafe fn remove_view_port(&self, view_port: *mut ffi::ViewPort) { ffi::gui_remove_view_port(self.as_ptr(), view_port) }
+
+	/// Size of the framebuffer in bytes.
+	pub fn framebuffer_size(&self) -> usize { unsafe { ffi::gui_get_framebuffer_size(self.as_ptr()) } }
+
+	/// Lockdown mode, when enabled only the `Desktop` and `Fullscreen` layers are visible.
+	pub fn set_lockdown(&self, lockdown: bool) { unsafe { ffi::gui_set_lockdown(self.as_ptr(), lockdown) } }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Gui { self.0.as_ptr() }
+}
+
+unsafe impl Sync for Gui {}
+
+
+/// GUI layer of a [`ViewPort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+	/// Desktop layer for internal use. Like fullscreen but with status bar.
+	Desktop,
+	/// Window layer, status bar is shown.
+	Window,
+	/// Status bar left-side layer, auto-layout.
+	StatusBarLeft,
+	/// Status bar right-side layer, auto-layout.
+	StatusBarRight,
+	/// Fullscreen layer, no status bar.
+	Fullscreen,
+}
+
+impl From<Layer> for ffi::GuiLayer {
+	fn from(layer: Layer) -> Self {
+		match layer {
+			Layer::Desktop => ffi::GuiLayer::GuiLayerDesktop,
+			Layer::Window => ffi::GuiLayer::GuiLayerWindow,
+			Layer::StatusBarLeft => ffi::GuiLayer::GuiLayerStatusBarLeft,
+			Layer::StatusBarRight => ffi::GuiLayer::GuiLayerStatusBarRight,
+			Layer::Fullscreen => ffi::GuiLayer::GuiLayerFullscreen,
+		}
+	}
+}
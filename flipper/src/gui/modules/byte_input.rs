@@ -0,0 +1,132 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::gui::AsView;
+use crate::string::IntoCString;
+
+
+type BytesFn = dyn FnMut(&[u8]) + Send;
+
+#[derive(Default)]
+struct Callbacks {
+	bytes: Box<[u8]>,
+	result: Option<Box<BytesFn>>,
+	changed: Option<Box<BytesFn>>,
+}
+
+
+/// On-screen keyboard for hex input of bytes.
+pub struct ByteInput {
+	raw: NonNull<ffi::ByteInput>,
+	header: Option<CString>,
+	callbacks: Box<Callbacks>,
+}
+
+
+impl ByteInput {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::byte_input_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          callbacks: Default::default() })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::ByteInput { self.raw.as_ptr() }
+
+
+	/// Sets bytes to edit, up to 255.
+	///
+	/// # Panics
+	/// If there are more than 255 bytes.
+	pub fn set_bytes(&mut self, bytes: &[u8]) {
+		assert!(bytes.len() <= u8::MAX as usize, "too many bytes");
+		// previous bytes are dropped after replacement:
+		let _previous = core::mem::replace(&mut self.callbacks.bytes, bytes.into());
+		self.register();
+	}
+
+	/// Current bytes of the input.
+	pub fn bytes(&self) -> &[u8] { &self.callbacks.bytes }
+
+	/// Sets the callback called with the bytes when the input is done, replaces previous one.
+	pub fn on_result<F: FnMut(&[u8]) + Send + 'static>(&mut self, f: F) {
+		self.callbacks.result = Some(Box::new(f));
+		self.register();
+	}
+
+	/// Sets the callback called with the bytes on each change, replaces previous one.
+	pub fn on_change<F: FnMut(&[u8]) + Send + 'static>(&mut self, f: F) {
+		self.callbacks.changed = Some(Box::new(f));
+		self.register();
+	}
+
+	fn register(&mut self) {
+		let bytes = self.callbacks.bytes.as_mut_ptr();
+		let count = self.callbacks.bytes.len() as u8;
+		let context = self.callbacks.as_mut() as *mut Callbacks as *mut c_void;
+		unsafe { ffi::byte_input_set_result_callback(self.as_ptr(), Some(result_proxy), Some(changed_proxy), context, bytes, count) };
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::byte_input_set_header_text(self.as_ptr(), text.as_ptr()) };
+		self.header = Some(text);
+	}
+}
+
+unsafe impl AsView for ByteInput {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::byte_input_get_view(self.as_ptr()) } }
+}
+
+impl Drop for ByteInput {
+	fn drop(&mut self) { unsafe { ffi::byte_input_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for ByteInput {}
+
+
+unsafe extern "C" fn result_proxy(context: *mut c_void) {
+	let callbacks = &mut *(context as *mut Callbacks);
+	if let Some(f) = callbacks.result.as_mut() {
+		f(&callbacks.bytes)
+	}
+}
+
+unsafe extern "C" fn changed_proxy(context: *mut c_void) {
+	let callbacks = &mut *(context as *mut Callbacks);
+	if let Some(f) = callbacks.changed.as_mut() {
+		f(&callbacks.bytes)
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+	use std::vec::Vec;
+
+	#[test]
+	fn change_and_save() {
+		let changes = Arc::new(Mutex::new(Vec::new()));
+		let result = Arc::new(Mutex::new(Vec::new()));
+		let mut input = ByteInput::new().unwrap();
+		input.set_header("Key");
+		input.set_bytes(&[0x00, 0x11]);
+		let sink = changes.clone();
+		input.on_change(move |bytes| sink.lock().unwrap().push(bytes.to_vec()));
+		let sink = result.clone();
+		input.on_result(move |bytes| sink.lock().unwrap().extend_from_slice(bytes));
+
+		unsafe { sys::mock::gui::byte_input_set(input.as_ptr(), 1, 0xAB) };
+		unsafe { sys::mock::gui::byte_input_save(input.as_ptr()) };
+		assert_eq!([0x00, 0xAB], input.bytes());
+		drop(input);
+		assert_eq!(vec![vec![0x00, 0xAB]], *changes.lock().unwrap());
+		assert_eq!(vec![0x00, 0xAB], *result.lock().unwrap());
+	}
+}
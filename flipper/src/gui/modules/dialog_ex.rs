@@ -0,0 +1,190 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::gui::{AsView, Align, Icon};
+use crate::string::IntoCString;
+
+
+type ResultFn = dyn FnMut(DialogExResult) + Send;
+
+
+/// Dialog with header, text, icon and up to three buttons.
+pub struct DialogEx {
+	raw: NonNull<ffi::DialogEx>,
+	header: Option<CString>,
+	text: Option<CString>,
+	buttons: [Option<CString>; 3],
+	callback: Option<Box<Box<ResultFn>>>,
+}
+
+
+impl DialogEx {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::dialog_ex_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          text: None,
+		          buttons: Default::default(),
+		          callback: None })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::DialogEx { self.raw.as_ptr() }
+
+
+	/// Sets the result callback, replaces previous one.
+	///
+	/// `Press*` and `Release*` results are reported only with [`enable_extended_events`](DialogEx::enable_extended_events).
+	pub fn on_result<F: FnMut(DialogExResult) + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<ResultFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<ResultFn> as *mut c_void;
+		unsafe {
+			ffi::dialog_ex_set_context(self.as_ptr(), context);
+			ffi::dialog_ex_set_result_callback(self.as_ptr(), Some(result_proxy));
+		}
+		self.callback = Some(callback);
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_ex_set_header(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.header = Some(text);
+	}
+
+	pub fn set_text<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_ex_set_text(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.text = Some(text);
+	}
+
+	/// Sets the icon, `None` removes it.
+	pub fn set_icon(&self, x: u8, y: u8, icon: Option<&'static Icon>) {
+		let icon = icon.map_or(core::ptr::null(), |icon| icon as *const Icon);
+		unsafe { ffi::dialog_ex_set_icon(self.as_ptr(), x, y, icon) }
+	}
+
+	pub fn set_left_button_text<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_ex_set_left_button_text(self.as_ptr(), text.as_ptr()) };
+		self.buttons[0] = Some(text);
+	}
+
+	pub fn set_center_button_text<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_ex_set_center_button_text(self.as_ptr(), text.as_ptr()) };
+		self.buttons[1] = Some(text);
+	}
+
+	pub fn set_right_button_text<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::dialog_ex_set_right_button_text(self.as_ptr(), text.as_ptr()) };
+		self.buttons[2] = Some(text);
+	}
+
+	/// Enables `Press*` and `Release*` results.
+	pub fn enable_extended_events(&self) { unsafe { ffi::dialog_ex_enable_extended_events(self.as_ptr()) } }
+	pub fn disable_extended_events(&self) { unsafe { ffi::dialog_ex_disable_extended_events(self.as_ptr()) } }
+
+	/// Clears texts, icon, buttons and callback.
+	pub fn reset(&mut self) {
+		unsafe {
+			ffi::dialog_ex_reset(self.as_ptr());
+			ffi::dialog_ex_set_result_callback(self.as_ptr(), None);
+		}
+		self.header = None;
+		self.text = None;
+		self.buttons = Default::default();
+		self.callback = None;
+	}
+}
+
+unsafe impl AsView for DialogEx {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::dialog_ex_get_view(self.as_ptr()) } }
+}
+
+impl Drop for DialogEx {
+	fn drop(&mut self) { unsafe { ffi::dialog_ex_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for DialogEx {}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogExResult {
+	Left,
+	Center,
+	Right,
+	PressLeft,
+	PressCenter,
+	PressRight,
+	ReleaseLeft,
+	ReleaseCenter,
+	ReleaseRight,
+}
+
+impl TryFrom<&ffi::DialogExResult> for DialogExResult {
+	type Error = ();
+
+	fn try_from(result: &ffi::DialogExResult) -> Result<Self, Self::Error> {
+		match result {
+			ffi::DialogExResult::DialogExResultLeft => Ok(DialogExResult::Left),
+			ffi::DialogExResult::DialogExResultCenter => Ok(DialogExResult::Center),
+			ffi::DialogExResult::DialogExResultRight => Ok(DialogExResult::Right),
+			ffi::DialogExResult::DialogExPressLeft => Ok(DialogExResult::PressLeft),
+			ffi::DialogExResult::DialogExPressCenter => Ok(DialogExResult::PressCenter),
+			ffi::DialogExResult::DialogExPressRight => Ok(DialogExResult::PressRight),
+			ffi::DialogExResult::DialogExReleaseLeft => Ok(DialogExResult::ReleaseLeft),
+			ffi::DialogExResult::DialogExReleaseCenter => Ok(DialogExResult::ReleaseCenter),
+			ffi::DialogExResult::DialogExReleaseRight => Ok(DialogExResult::ReleaseRight),
+			_ => Err(()),
+		}
+	}
+}
+
+
+// `DialogExResult` is `#[non_exhaustive]` in bindings, but it is `repr(u8)`:
+#[allow(improper_ctypes_definitions)]
+unsafe extern "C" fn result_proxy(result: ffi::DialogExResult, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<ResultFn>);
+	if let Ok(result) = DialogExResult::try_from(&result) {
+		callback(result);
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+	use std::vec::Vec;
+
+	#[test]
+	fn buttons() {
+		let results = Arc::new(Mutex::new(Vec::new()));
+		let mut dialog = DialogEx::new().unwrap();
+		dialog.set_header("Delete?", 64, 0, Align::Center, Align::Top);
+		dialog.set_left_button_text("No");
+		dialog.set_right_button_text("Yes");
+		let buttons = unsafe { sys::mock::gui::dialog_ex_buttons(dialog.as_ptr()) };
+		assert_eq!(
+		           [Some(b"No".as_slice()), None, Some(b"Yes")],
+		           buttons.map(|s| s.map(|s| s.to_bytes()))
+		);
+
+		let sink = results.clone();
+		dialog.on_result(move |result| sink.lock().unwrap().push(result));
+		let press = |result| unsafe { sys::mock::gui::dialog_ex_press(dialog.as_ptr(), result) };
+		press(ffi::DialogExResult::DialogExPressRight);
+		press(ffi::DialogExResult::DialogExResultRight);
+		dialog.enable_extended_events();
+		press(ffi::DialogExResult::DialogExPressLeft);
+		drop(dialog);
+		assert_eq!(
+		           vec![DialogExResult::Right, DialogExResult::PressLeft],
+		           *results.lock().unwrap()
+		);
+	}
+}
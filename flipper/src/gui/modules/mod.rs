@@ -0,0 +1,30 @@
+//! Stock views of the firmware, each can be added to the [`ViewDispatcher`](super::ViewDispatcher).
+//!
+//! Texts are copied and owned by the module, so setters accept anything [`IntoCString`](crate::string::IntoCString),
+//! `&str` and `String` texts end at the first nul.
+//! Callbacks are called from the GUI thread.
+//!
+//! ```ignore
+//! let mut submenu = Submenu::new()?;
+//! submenu.set_header("Settings");
+//! submenu.add_item("Volume", 0, |index| { /* ... */ });
+//! submenu.add_item("About", 1, |index| { /* ... */ });
+//! view_dispatcher.add_view(Views::Menu as u32, submenu);
+//! ```
+
+pub use submenu::Submenu;
+pub use popup::Popup;
+pub use dialog_ex::{DialogEx, DialogExResult};
+pub use text_input::TextInput;
+pub use byte_input::ByteInput;
+pub use widget::{Widget, ButtonType};
+pub use variable_item_list::{VariableItemList, VariableItem};
+
+
+pub mod submenu;
+pub mod popup;
+pub mod dialog_ex;
+pub mod text_input;
+pub mod byte_input;
+pub mod widget;
+pub mod variable_item_list;
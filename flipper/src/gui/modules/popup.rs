@@ -0,0 +1,132 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::gui::{AsView, Align, Icon};
+use crate::string::IntoCString;
+
+
+type TimeoutFn = dyn FnMut() + Send;
+
+
+/// Popup with header, text and icon, optionally closed by timeout.
+pub struct Popup {
+	raw: NonNull<ffi::Popup>,
+	header: Option<CString>,
+	text: Option<CString>,
+	callback: Option<Box<Box<TimeoutFn>>>,
+}
+
+
+impl Popup {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::popup_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          text: None,
+		          callback: None })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Popup { self.raw.as_ptr() }
+
+
+	/// Sets the timeout callback, replaces previous one.
+	///
+	/// It is also called on `Back` key when timeout is enabled.
+	pub fn on_timeout<F: FnMut() + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<TimeoutFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<TimeoutFn> as *mut c_void;
+		unsafe {
+			ffi::popup_set_context(self.as_ptr(), context);
+			ffi::popup_set_callback(self.as_ptr(), Some(timeout_proxy));
+		}
+		self.callback = Some(callback);
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::popup_set_header(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.header = Some(text);
+	}
+
+	pub fn set_text<S: IntoCString>(&mut self, text: S, x: u8, y: u8, horizontal: Align, vertical: Align) {
+		let text = text.into_c_string();
+		unsafe { ffi::popup_set_text(self.as_ptr(), text.as_ptr(), x, y, horizontal.into(), vertical.into()) };
+		self.text = Some(text);
+	}
+
+	/// Sets the icon, `None` removes it.
+	pub fn set_icon(&self, x: u8, y: u8, icon: Option<&'static Icon>) {
+		let icon = icon.map_or(core::ptr::null(), |icon| icon as *const Icon);
+		unsafe { ffi::popup_set_icon(self.as_ptr(), x, y, icon) }
+	}
+
+	pub fn set_timeout(&self, timeout: Duration) {
+		unsafe { ffi::popup_set_timeout(self.as_ptr(), timeout.as_millis().min(u32::MAX as _) as u32) }
+	}
+
+	pub fn enable_timeout(&self) { unsafe { ffi::popup_enable_timeout(self.as_ptr()) } }
+	pub fn disable_timeout(&self) { unsafe { ffi::popup_disable_timeout(self.as_ptr()) } }
+
+	/// Clears texts, icon and callback.
+	pub fn reset(&mut self) {
+		unsafe {
+			ffi::popup_reset(self.as_ptr());
+			ffi::popup_set_callback(self.as_ptr(), None);
+		}
+		self.header = None;
+		self.text = None;
+		self.callback = None;
+	}
+}
+
+unsafe impl AsView for Popup {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::popup_get_view(self.as_ptr()) } }
+}
+
+impl Drop for Popup {
+	fn drop(&mut self) { unsafe { ffi::popup_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for Popup {}
+
+
+unsafe extern "C" fn timeout_proxy(context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<TimeoutFn>);
+	callback();
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::Arc;
+
+	#[test]
+	fn timeout() {
+		let expired = Arc::new(AtomicUsize::new(0));
+		let mut popup = Popup::new().unwrap();
+		popup.set_header("Header", 64, 0, Align::Center, Align::Top);
+		popup.set_text("text", 64, 32, Align::Center, Align::Center);
+		let (header, text) = unsafe { sys::mock::gui::popup_texts(popup.as_ptr()) };
+		assert_eq!(Some(b"Header".as_slice()), header.map(|s| s.to_bytes()));
+		assert_eq!(Some(b"text".as_slice()), text.map(|s| s.to_bytes()));
+
+		let counter = expired.clone();
+		popup.on_timeout(move || {
+			     counter.fetch_add(1, Ordering::SeqCst);
+		     });
+		popup.set_timeout(Duration::from_millis(500));
+		unsafe { sys::mock::gui::popup_expire(popup.as_ptr()) };
+		assert_eq!(0, expired.load(Ordering::SeqCst));
+		popup.enable_timeout();
+		unsafe { sys::mock::gui::popup_expire(popup.as_ptr()) };
+		drop(popup);
+		assert_eq!(1, expired.load(Ordering::SeqCst));
+	}
+}
@@ -0,0 +1,105 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::gui::AsView;
+use crate::string::IntoCString;
+
+
+type ItemFn = dyn FnMut(u32) + Send;
+
+
+/// Scrollable list of items with a header.
+pub struct Submenu {
+	raw: NonNull<ffi::Submenu>,
+	header: Option<CString>,
+	items: Vec<(CString, Box<Box<ItemFn>>)>,
+}
+
+
+impl Submenu {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::submenu_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          items: Vec::new() })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Submenu { self.raw.as_ptr() }
+
+
+	/// Adds item with the `label`, callback receives `index` of the item when it is selected.
+	pub fn add_item<S, F>(&mut self, label: S, index: u32, f: F)
+		where S: IntoCString,
+		      F: FnMut(u32) + Send + 'static {
+		let label = label.into_c_string();
+		let mut callback: Box<Box<ItemFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<ItemFn> as *mut c_void;
+		unsafe { ffi::submenu_add_item(self.as_ptr(), label.as_ptr(), index, Some(item_proxy), context) };
+		self.items.push((label, callback));
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, header: S) {
+		let header = header.into_c_string();
+		unsafe { ffi::submenu_set_header(self.as_ptr(), header.as_ptr()) };
+		self.header = Some(header);
+	}
+
+	/// Selects item with the `index`.
+	pub fn set_selected_item(&self, index: u32) { unsafe { ffi::submenu_set_selected_item(self.as_ptr(), index) } }
+
+	/// Removes all items and the header.
+	pub fn reset(&mut self) {
+		unsafe { ffi::submenu_reset(self.as_ptr()) };
+		self.header = None;
+		self.items.clear();
+	}
+}
+
+unsafe impl AsView for Submenu {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::submenu_get_view(self.as_ptr()) } }
+}
+
+impl Drop for Submenu {
+	fn drop(&mut self) { unsafe { ffi::submenu_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for Submenu {}
+
+
+unsafe extern "C" fn item_proxy(context: *mut c_void, index: u32) {
+	let callback = &mut *(context as *mut Box<ItemFn>);
+	callback(index);
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	#[test]
+	fn select_item() {
+		let selected = Arc::new(Mutex::new(Vec::new()));
+		let mut submenu = Submenu::new().unwrap();
+		submenu.set_header("Menu");
+		for (label, index) in [("first", 1), ("second", 2)] {
+			let selected = selected.clone();
+			submenu.add_item(label, index, move |index| selected.lock().unwrap().push(index));
+		}
+		let labels = unsafe { sys::mock::gui::submenu_labels(submenu.as_ptr()) };
+		assert_eq!(
+		           vec![b"first".as_slice(), b"second"],
+		           labels.iter().map(|s| s.to_bytes()).collect::<Vec<_>>()
+		);
+
+		unsafe { sys::mock::gui::submenu_select(submenu.as_ptr(), 2) };
+		unsafe { sys::mock::gui::submenu_select(submenu.as_ptr(), 1) };
+		drop(submenu);
+		assert_eq!(vec![2, 1], *selected.lock().unwrap());
+	}
+}
@@ -0,0 +1,157 @@
+use core::ffi::{c_char, c_void, CStr};
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec;
+use crate::error::NullPointerError;
+use crate::gui::AsView;
+use crate::string::IntoCString;
+
+
+type ResultFn = dyn FnMut(&CStr) + Send;
+type ValidatorFn = dyn FnMut(&CStr) -> Result<(), CString> + Send;
+
+struct Input {
+	buffer: Box<[u8]>,
+	callback: Box<ResultFn>,
+}
+
+
+/// On-screen keyboard for text input.
+pub struct TextInput {
+	raw: NonNull<ffi::TextInput>,
+	header: Option<CString>,
+	input: Option<Box<Input>>,
+	validator: Option<Box<Box<ValidatorFn>>>,
+}
+
+
+impl TextInput {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::text_input_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          header: None,
+		          input: None,
+		          validator: None })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::TextInput { self.raw.as_ptr() }
+
+
+	/// Starts input of up to `capacity - 1` bytes, `default` text is shown initially.
+	///
+	/// Callback receives entered text when the input is done.
+	/// If `clear_default_text` is `true` the default text is erased on first key press.
+	pub fn on_result<S, F>(&mut self, default: S, capacity: usize, clear_default_text: bool, f: F)
+		where S: IntoCString,
+		      F: FnMut(&CStr) + Send + 'static {
+		let mut buffer = vec![0u8; capacity.max(1)].into_boxed_slice();
+		let default = default.into_c_string();
+		let len = default.as_bytes().len().min(buffer.len() - 1);
+		buffer[..len].copy_from_slice(&default.as_bytes()[..len]);
+
+		let mut input = Box::new(Input { buffer,
+		                                 callback: Box::new(f) });
+		let text = input.buffer.as_mut_ptr() as *mut c_char;
+		let size = input.buffer.len();
+		let context = input.as_mut() as *mut Input as *mut c_void;
+		unsafe { ffi::text_input_set_result_callback(self.as_ptr(), Some(result_proxy), context, text, size, clear_default_text) };
+		self.input = Some(input);
+	}
+
+	/// Sets the validator called before the result callback.
+	///
+	/// Error message is shown to the user and the input continues.
+	pub fn set_validator<F: FnMut(&CStr) -> Result<(), CString> + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<ValidatorFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<ValidatorFn> as *mut c_void;
+		unsafe { ffi::text_input_set_validator(self.as_ptr(), Some(validator_proxy), context) };
+		self.validator = Some(callback);
+	}
+
+	/// Current text of the input, empty if it isn't started with [`on_result`](TextInput::on_result).
+	pub fn text(&self) -> &CStr {
+		self.input
+		    .as_ref()
+		    .and_then(|input| CStr::from_bytes_until_nul(&input.buffer).ok())
+		    .unwrap_or_default()
+	}
+
+	pub fn set_header<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::text_input_set_header_text(self.as_ptr(), text.as_ptr()) };
+		self.header = Some(text);
+	}
+
+	/// Clears the header, text, callback and validator.
+	pub fn reset(&mut self) {
+		unsafe { ffi::text_input_reset(self.as_ptr()) };
+		self.header = None;
+		self.input = None;
+		self.validator = None;
+	}
+}
+
+unsafe impl AsView for TextInput {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::text_input_get_view(self.as_ptr()) } }
+}
+
+impl Drop for TextInput {
+	fn drop(&mut self) { unsafe { ffi::text_input_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for TextInput {}
+
+
+unsafe extern "C" fn result_proxy(context: *mut c_void) {
+	let input = &mut *(context as *mut Input);
+	if let Ok(text) = CStr::from_bytes_until_nul(&input.buffer) {
+		(input.callback)(text);
+	}
+}
+
+unsafe extern "C" fn validator_proxy(text: *const c_char, error: *mut ffi::FuriString, context: *mut c_void) -> bool {
+	let callback = &mut *(context as *mut Box<ValidatorFn>);
+	match callback(CStr::from_ptr(text)) {
+		Ok(()) => true,
+		Err(message) => {
+			ffi::furi_string_set_str(error, message.as_ptr() as _);
+			false
+		},
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+	use std::vec::Vec;
+
+	#[test]
+	fn validate_and_enter() {
+		let entered = Arc::new(Mutex::new(Vec::new()));
+		let mut input = TextInput::new().unwrap();
+		input.set_header("Name");
+		let sink = entered.clone();
+		input.on_result("default", 6, true, move |text| {
+			     sink.lock().unwrap().push(text.to_bytes().to_vec())
+		     });
+		assert_eq!(b"defau", input.text().to_bytes());
+		input.set_validator(|text| {
+			     if text.to_bytes().is_empty() {
+				     return Err(CString::new("empty").unwrap());
+			     }
+			     Ok(())
+		     });
+
+		let enter = |text: &[u8]| unsafe { sys::mock::gui::text_input_enter(input.as_ptr(), text) };
+		assert_eq!(Err(b"empty".to_vec()), enter(b""));
+		assert_eq!(Ok(()), enter(b"flipper"));
+		assert_eq!(b"flipp", input.text().to_bytes());
+		drop(input);
+		assert_eq!(vec![b"flipp".to_vec()], *entered.lock().unwrap());
+	}
+}
@@ -0,0 +1,174 @@
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::gui::AsView;
+use crate::string::IntoCString;
+
+
+type ChangeFn = dyn FnMut(&mut VariableItem<'_>) + Send;
+type EnterFn = dyn FnMut(u32) + Send;
+
+struct Item {
+	raw: *mut ffi::VariableItem,
+	label: CString,
+	value_text: Option<CString>,
+	callback: Box<ChangeFn>,
+}
+
+
+/// List of items with switchable values, such as settings.
+pub struct VariableItemList {
+	raw: NonNull<ffi::VariableItemList>,
+	// boxed, so pointers passed as context are stable:
+	#[allow(clippy::vec_box)]
+	items: Vec<Box<Item>>,
+	enter: Option<Box<Box<EnterFn>>>,
+}
+
+
+impl VariableItemList {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::variable_item_list_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          items: Vec::new(),
+		          enter: None })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::VariableItemList { self.raw.as_ptr() }
+
+
+	/// Adds item with the `label` and `values_count` values.
+	///
+	/// Callback is called when current value of the item is changed by the user,
+	/// it should update the value text.
+	/// Returns the added item to set its initial value.
+	pub fn add<S, F>(&mut self, label: S, values_count: u8, f: F) -> VariableItem<'_>
+		where S: IntoCString,
+		      F: FnMut(&mut VariableItem<'_>) + Send + 'static {
+		let mut item = Box::new(Item { raw: core::ptr::null_mut(),
+		                               label: label.into_c_string(),
+		                               value_text: None,
+		                               callback: Box::new(f) });
+		let context = item.as_mut() as *mut Item as *mut c_void;
+		item.raw =
+			unsafe { ffi::variable_item_list_add(self.as_ptr(), item.label.as_ptr(), values_count, Some(change_proxy), context) };
+		self.items.push(item);
+
+		let item = self.items.last_mut().unwrap();
+		VariableItem::new(item.raw, &mut item.value_text)
+	}
+
+	/// Returns item by its index in order of addition.
+	pub fn item(&mut self, index: usize) -> Option<VariableItem<'_>> {
+		self.items
+		    .get_mut(index)
+		    .map(|item| VariableItem::new(item.raw, &mut item.value_text))
+	}
+
+	/// Sets the callback called with index of the item on `Ok` key, replaces previous one.
+	pub fn on_enter<F: FnMut(u32) + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<EnterFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<EnterFn> as *mut c_void;
+		unsafe { ffi::variable_item_list_set_enter_callback(self.as_ptr(), Some(enter_proxy), context) };
+		self.enter = Some(callback);
+	}
+
+	pub fn set_selected_item(&self, index: u8) { unsafe { ffi::variable_item_list_set_selected_item(self.as_ptr(), index) } }
+	pub fn selected_item(&self) -> u8 { unsafe { ffi::variable_item_list_get_selected_item_index(self.as_ptr()) } }
+
+	/// Removes all items.
+	pub fn reset(&mut self) {
+		unsafe { ffi::variable_item_list_reset(self.as_ptr()) };
+		self.items.clear();
+	}
+}
+
+unsafe impl AsView for VariableItemList {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::variable_item_list_get_view(self.as_ptr()) } }
+}
+
+impl Drop for VariableItemList {
+	fn drop(&mut self) { unsafe { ffi::variable_item_list_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for VariableItemList {}
+
+
+/// Item of the [`VariableItemList`].
+pub struct VariableItem<'a> {
+	raw: *mut ffi::VariableItem,
+	value_text: &'a mut Option<CString>,
+	_list: PhantomData<&'a mut VariableItemList>,
+}
+
+impl<'a> VariableItem<'a> {
+	fn new(raw: *mut ffi::VariableItem, value_text: &'a mut Option<CString>) -> Self {
+		Self { raw,
+		       value_text,
+		       _list: PhantomData }
+	}
+
+	pub fn current_value_index(&self) -> u8 { unsafe { ffi::variable_item_get_current_value_index(self.raw) } }
+
+	pub fn set_current_value_index(&mut self, index: u8) { unsafe { ffi::variable_item_set_current_value_index(self.raw, index) } }
+
+	pub fn set_values_count(&mut self, count: u8) { unsafe { ffi::variable_item_set_values_count(self.raw, count) } }
+
+	/// Sets text shown for the current value.
+	pub fn set_current_value_text<S: IntoCString>(&mut self, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::variable_item_set_current_value_text(self.raw, text.as_ptr()) };
+		*self.value_text = Some(text);
+	}
+}
+
+
+unsafe extern "C" fn change_proxy(item: *mut ffi::VariableItem) {
+	let context = ffi::variable_item_get_context(item) as *mut Item;
+	if let Some(Item { value_text, callback, .. }) = context.as_mut() {
+		callback(&mut VariableItem::new(item, value_text));
+	}
+}
+
+unsafe extern "C" fn enter_proxy(context: *mut c_void, index: u32) {
+	let callback = &mut *(context as *mut Box<EnterFn>);
+	callback(index);
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	const VALUES: [&str; 3] = ["low", "mid", "high"];
+
+	#[test]
+	fn change_and_enter() {
+		let entered = Arc::new(Mutex::new(Vec::new()));
+		let mut list = VariableItemList::new().unwrap();
+		let mut item = list.add("Level", VALUES.len() as u8, |item| {
+			                   let text = VALUES[item.current_value_index() as usize];
+			                   item.set_current_value_text(text);
+		                   });
+		item.set_current_value_index(2);
+		item.set_current_value_text(VALUES[2]);
+		let sink = entered.clone();
+		list.on_enter(move |index| sink.lock().unwrap().push(index));
+
+		unsafe { sys::mock::gui::variable_item_list_next_value(list.as_ptr(), 0) };
+		let (label, value) = unsafe { sys::mock::gui::variable_item_list_item(list.as_ptr(), 0) };
+		assert_eq!((b"Level".as_slice(), b"low".as_slice()), (label.to_bytes(), value));
+		assert_eq!(0, list.item(0).unwrap().current_value_index());
+		unsafe { sys::mock::gui::variable_item_list_enter(list.as_ptr(), 0) };
+		assert_eq!(0, list.selected_item());
+		drop(list);
+		assert_eq!(vec![0], *entered.lock().unwrap());
+	}
+}
@@ -0,0 +1,204 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::gui::{AsView, Align, Font, Icon};
+use crate::input::InputType;
+use crate::string::IntoCString;
+
+
+type ButtonFn = dyn FnMut(ButtonType, InputType) + Send;
+
+
+/// Container of simple elements: strings, text boxes, buttons, icons and frames.
+///
+/// Texts of elements are copied by the firmware.
+pub struct Widget {
+	raw: NonNull<ffi::Widget>,
+	// boxed, so pointers passed as context are stable:
+	#[allow(clippy::vec_box)]
+	buttons: Vec<Box<Box<ButtonFn>>>,
+}
+
+
+impl Widget {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::widget_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          buttons: Vec::new() })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::Widget { self.raw.as_ptr() }
+
+
+	/// Adds string, `\n` breaks lines.
+	pub fn add_string_multiline<S: IntoCString>(&mut self, x: u8, y: u8, horizontal: Align, vertical: Align, font: Font, text: S) {
+		let text = text.into_c_string();
+		unsafe {
+			ffi::widget_add_string_multiline_element(
+			                                         self.as_ptr(),
+			                                         x,
+			                                         y,
+			                                         horizontal.into(),
+			                                         vertical.into(),
+			                                         font.into(),
+			                                         text.as_ptr(),
+			)
+		}
+	}
+
+	pub fn add_string<S: IntoCString>(&mut self, x: u8, y: u8, horizontal: Align, vertical: Align, font: Font, text: S) {
+		let text = text.into_c_string();
+		unsafe {
+			ffi::widget_add_string_element(
+			                               self.as_ptr(),
+			                               x,
+			                               y,
+			                               horizontal.into(),
+			                               vertical.into(),
+			                               font.into(),
+			                               text.as_ptr(),
+			)
+		}
+	}
+
+	/// Adds text box with formatted text, see firmware docs of `widget_add_text_box_element` for the format.
+	///
+	/// If `strip_to_dots` is `true` the text not fitting into the box is ended with dots.
+	#[allow(clippy::too_many_arguments)]
+	pub fn add_text_box<S: IntoCString>(&mut self,
+	                                    x: u8,
+	                                    y: u8,
+	                                    width: u8,
+	                                    height: u8,
+	                                    horizontal: Align,
+	                                    vertical: Align,
+	                                    text: S,
+	                                    strip_to_dots: bool) {
+		let text = text.into_c_string();
+		unsafe {
+			ffi::widget_add_text_box_element(
+			                                 self.as_ptr(),
+			                                 x,
+			                                 y,
+			                                 width,
+			                                 height,
+			                                 horizontal.into(),
+			                                 vertical.into(),
+			                                 text.as_ptr(),
+			                                 strip_to_dots,
+			)
+		}
+	}
+
+	/// Adds scrollable text box.
+	pub fn add_text_scroll<S: IntoCString>(&mut self, x: u8, y: u8, width: u8, height: u8, text: S) {
+		let text = text.into_c_string();
+		unsafe { ffi::widget_add_text_scroll_element(self.as_ptr(), x, y, width, height, text.as_ptr()) }
+	}
+
+	/// Adds button to the bottom bar, callback receives the button and type of the input event.
+	pub fn add_button<S, F>(&mut self, button: ButtonType, text: S, f: F)
+		where S: IntoCString,
+		      F: FnMut(ButtonType, InputType) + Send + 'static {
+		let text = text.into_c_string();
+		let mut callback: Box<Box<ButtonFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<ButtonFn> as *mut c_void;
+		unsafe { ffi::widget_add_button_element(self.as_ptr(), button.into(), text.as_ptr(), Some(button_proxy), context) };
+		self.buttons.push(callback);
+	}
+
+	pub fn add_icon(&mut self, x: u8, y: u8, icon: &'static Icon) {
+		unsafe { ffi::widget_add_icon_element(self.as_ptr(), x, y, icon) }
+	}
+
+	pub fn add_frame(&mut self, x: u8, y: u8, width: u8, height: u8, radius: u8) {
+		unsafe { ffi::widget_add_frame_element(self.as_ptr(), x, y, width, height, radius) }
+	}
+
+	/// Removes all elements.
+	pub fn reset(&mut self) {
+		unsafe { ffi::widget_reset(self.as_ptr()) };
+		self.buttons.clear();
+	}
+}
+
+unsafe impl AsView for Widget {
+	fn as_view(&self) -> *mut ffi::View { unsafe { ffi::widget_get_view(self.as_ptr()) } }
+}
+
+impl Drop for Widget {
+	fn drop(&mut self) { unsafe { ffi::widget_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for Widget {}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonType {
+	Left,
+	Center,
+	Right,
+}
+
+impl From<ButtonType> for ffi::GuiButtonType {
+	fn from(button: ButtonType) -> Self {
+		match button {
+			ButtonType::Left => ffi::GuiButtonType::GuiButtonTypeLeft,
+			ButtonType::Center => ffi::GuiButtonType::GuiButtonTypeCenter,
+			ButtonType::Right => ffi::GuiButtonType::GuiButtonTypeRight,
+		}
+	}
+}
+
+impl TryFrom<&ffi::GuiButtonType> for ButtonType {
+	type Error = ();
+
+	fn try_from(button: &ffi::GuiButtonType) -> Result<Self, Self::Error> {
+		match button {
+			ffi::GuiButtonType::GuiButtonTypeLeft => Ok(ButtonType::Left),
+			ffi::GuiButtonType::GuiButtonTypeCenter => Ok(ButtonType::Center),
+			ffi::GuiButtonType::GuiButtonTypeRight => Ok(ButtonType::Right),
+			_ => Err(()),
+		}
+	}
+}
+
+
+// `GuiButtonType` and `InputType` are `#[non_exhaustive]` in bindings, but they are `repr(u8)`:
+#[allow(improper_ctypes_definitions)]
+unsafe extern "C" fn button_proxy(button: ffi::GuiButtonType, kind: ffi::InputType, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<ButtonFn>);
+	if let (Ok(button), Ok(kind)) = (ButtonType::try_from(&button), InputType::try_from(&kind)) {
+		callback(button, kind);
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	#[test]
+	fn press_button() {
+		let pressed = Arc::new(Mutex::new(Vec::new()));
+		let mut widget = Widget::new().unwrap();
+		widget.add_string(64, 0, Align::Center, Align::Top, Font::Primary, "Title");
+		widget.add_text_scroll(0, 16, 128, 32, "long text");
+		let sink = pressed.clone();
+		widget.add_button(ButtonType::Right, "Next", move |button, kind| {
+			      sink.lock().unwrap().push((button, kind))
+		      });
+		let texts = unsafe { sys::mock::gui::widget_texts(widget.as_ptr()) };
+		assert_eq!(vec![b"Title".to_vec(), b"long text".to_vec(), b"Next".to_vec()], texts);
+
+		let right = ffi::GuiButtonType::GuiButtonTypeRight;
+		unsafe { sys::mock::gui::widget_press(widget.as_ptr(), right, ffi::InputType::InputTypeShort) };
+		drop(widget);
+		assert_eq!(vec![(ButtonType::Right, InputType::Short)], *pressed.lock().unwrap());
+	}
+}
@@ -0,0 +1,342 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use super::{AsView, ViewDispatcher};
+
+
+/// Maximum number of scenes in the [`SceneManager`].
+pub const MAX_SCENES: usize = 32;
+
+
+/// Scene of the application `A`.
+///
+/// Navigation requested through the [`SceneContext`] is performed right after the handler returns.
+pub trait Scene<A>: 'static {
+	fn on_enter(&mut self, ctx: &mut SceneContext<'_, A>);
+
+	/// Returns `true` if the event is consumed.
+	///
+	/// Not consumed [`SceneEvent::Back`] switches to the previous scene,
+	/// or stops the [`ViewDispatcher`] if there is no previous scene.
+	fn on_event(&mut self, ctx: &mut SceneContext<'_, A>, event: SceneEvent) -> bool {
+		let _ = (ctx, event);
+		false
+	}
+
+	fn on_exit(&mut self, ctx: &mut SceneContext<'_, A>) { let _ = ctx; }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneEvent {
+	/// Custom event sent with [`ViewDispatcher::send_custom_event`].
+	Custom(u32),
+	/// Not consumed `Back` key.
+	Back,
+	/// Tick, see [`SceneManagerBuilder::tick_period`].
+	Tick,
+}
+
+impl TryFrom<&ffi::SceneManagerEvent> for SceneEvent {
+	type Error = ();
+
+	fn try_from(event: &ffi::SceneManagerEvent) -> Result<Self, Self::Error> {
+		match event.type_ {
+			ffi::SceneManagerEventType::SceneManagerEventTypeCustom => Ok(SceneEvent::Custom(event.event)),
+			ffi::SceneManagerEventType::SceneManagerEventTypeBack => Ok(SceneEvent::Back),
+			ffi::SceneManagerEventType::SceneManagerEventTypeTick => Ok(SceneEvent::Tick),
+			_ => Err(()),
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy)]
+enum Navigation {
+	Next(u32),
+	Previous,
+	SearchPrevious(u32),
+	SearchAnother(u32),
+}
+
+
+/// Application and dispatcher borrowed by a [`Scene`] handler.
+pub struct SceneContext<'a, A> {
+	pub app: &'a mut A,
+	pub view_dispatcher: SceneDispatcher<'a>,
+	raw: *mut ffi::SceneManager,
+	navigation: &'a mut Option<Navigation>,
+}
+
+impl<A> SceneContext<'_, A> {
+	/// Switches to the scene `id` after the handler returns.
+	pub fn next_scene<S: Into<u32>>(&mut self, id: S) { *self.navigation = Some(Navigation::Next(id.into())) }
+
+	/// Switches to the previous scene after the handler returns,
+	/// stops the [`ViewDispatcher`] if there is no previous scene.
+	pub fn previous_scene(&mut self) { *self.navigation = Some(Navigation::Previous) }
+
+	/// Goes back through the scenes stack to the scene `id` after the handler returns.
+	pub fn search_and_switch_to_previous_scene<S: Into<u32>>(&mut self, id: S) {
+		*self.navigation = Some(Navigation::SearchPrevious(id.into()))
+	}
+
+	/// Clears the scenes stack up to the first scene and switches to the scene `id` after the handler returns.
+	pub fn search_and_switch_to_another_scene<S: Into<u32>>(&mut self, id: S) {
+		*self.navigation = Some(Navigation::SearchAnother(id.into()))
+	}
+
+	pub fn has_previous_scene<S: Into<u32>>(&self, id: S) -> bool {
+		unsafe { ffi::scene_manager_has_previous_scene(self.raw, id.into()) }
+	}
+
+	/// Arbitrary state of the scene `id`, preserved while the manager exists.
+	pub fn scene_state<S: Into<u32>>(&self, id: S) -> u32 { unsafe { ffi::scene_manager_get_scene_state(self.raw, id.into()) } }
+
+	pub fn set_scene_state<S: Into<u32>>(&mut self, id: S, state: u32) {
+		unsafe { ffi::scene_manager_set_scene_state(self.raw, id.into(), state) }
+	}
+
+	/// Stops the [`ViewDispatcher`], so [`SceneManager::run`] returns.
+	pub fn stop(&mut self) { self.view_dispatcher.stop() }
+}
+
+
+/// [`ViewDispatcher`] owned by the [`SceneManager`].
+///
+/// Event callbacks of the dispatcher are used by the manager, so they can't be replaced through it.
+pub struct SceneDispatcher<'a>(&'a mut ViewDispatcher);
+
+impl SceneDispatcher<'_> {
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::ViewDispatcher { self.0.as_ptr() }
+
+	/// See [`ViewDispatcher::add_view`].
+	pub fn add_view<V: AsView>(&mut self, id: u32, view: V) -> Option<Box<dyn AsView>> { self.0.add_view(id, view) }
+	pub fn remove_view(&mut self, id: u32) -> Option<Box<dyn AsView>> { self.0.remove_view(id) }
+
+	pub fn view<V: AsView>(&self, id: u32) -> Option<&V> { self.0.view(id) }
+	pub fn view_mut<V: AsView>(&mut self, id: u32) -> Option<&mut V> { self.0.view_mut(id) }
+
+	pub fn switch_to_view(&self, id: u32) { self.0.switch_to_view(id) }
+
+	pub fn send_to_front(&self) { self.0.send_to_front() }
+	pub fn send_to_back(&self) { self.0.send_to_back() }
+
+	/// Sends [`SceneEvent::Custom`], can be called from any thread.
+	pub fn send_custom_event(&self, event: u32) { self.0.send_custom_event(event) }
+
+	/// Stops the dispatcher, so [`SceneManager::run`] returns.
+	pub fn stop(&self) { self.0.stop() }
+}
+
+
+/// Scene manager, owns the application state, scenes and the [`ViewDispatcher`].
+///
+/// ```ignore
+/// let mut scene_manager = SceneManager::builder().scene(MenuScene)
+///                                                .scene(SettingsScene::default())
+///                                                .build(App::default(), view_dispatcher)?;
+/// scene_manager.run(Scenes::Menu);
+/// ```
+pub struct SceneManager<A: 'static>(NonNull<Inner<A>>);
+
+struct Inner<A: 'static> {
+	raw: *mut ffi::SceneManager,
+	app: A,
+	view_dispatcher: ViewDispatcher,
+	scenes: Vec<Box<dyn Scene<A>>>,
+	navigation: Option<Navigation>,
+	on_enter: Vec<ffi::AppSceneOnEnterCallback>,
+	on_event: Vec<ffi::AppSceneOnEventCallback>,
+	on_exit: Vec<ffi::AppSceneOnExitCallback>,
+	handlers: ffi::SceneManagerHandlers,
+}
+
+
+/// Builder of the [`SceneManager`], id of a scene is its index in order of addition.
+pub struct SceneManagerBuilder<A> {
+	scenes: Vec<Box<dyn Scene<A>>>,
+	tick_period: Option<Duration>,
+}
+
+impl<A: 'static> SceneManagerBuilder<A> {
+	/// Adds the next scene.
+	///
+	/// # Panics
+	/// If there are already [`MAX_SCENES`] scenes.
+	pub fn scene<S: Scene<A>>(mut self, scene: S) -> Self {
+		assert!(self.scenes.len() < MAX_SCENES, "too many scenes");
+		self.scenes.push(Box::new(scene));
+		self
+	}
+
+	/// Enables [`SceneEvent::Tick`] with specified `period`.
+	pub fn tick_period(mut self, period: Duration) -> Self {
+		self.tick_period = Some(period);
+		self
+	}
+
+	pub fn build(self, app: A, view_dispatcher: ViewDispatcher) -> Result<SceneManager<A>, NullPointerError> {
+		let (on_enter, on_event, on_exit) = handlers::<A>();
+		let len = self.scenes.len();
+		let mut inner = Box::new(Inner { raw: core::ptr::null_mut(),
+		                                 app,
+		                                 view_dispatcher,
+		                                 scenes: self.scenes,
+		                                 navigation: None,
+		                                 on_enter: on_enter[..len].to_vec(),
+		                                 on_event: on_event[..len].to_vec(),
+		                                 on_exit: on_exit[..len].to_vec(),
+		                                 handlers: ffi::SceneManagerHandlers { on_enter_handlers: core::ptr::null(),
+		                                                                       on_event_handlers: core::ptr::null(),
+		                                                                       on_exit_handlers: core::ptr::null(),
+		                                                                       scene_num: len as u32 } });
+		inner.handlers.on_enter_handlers = inner.on_enter.as_ptr();
+		inner.handlers.on_event_handlers = inner.on_event.as_ptr();
+		inner.handlers.on_exit_handlers = inner.on_exit.as_ptr();
+
+		let context = inner.as_mut() as *mut Inner<A> as *mut c_void;
+		let raw = unsafe { ffi::scene_manager_alloc(&inner.handlers, context) };
+		if raw.is_null() {
+			return Err(NullPointerError);
+		}
+		inner.raw = raw;
+
+		inner.view_dispatcher
+		     .on_custom_event(move |event| unsafe { ffi::scene_manager_handle_custom_event(raw, event) });
+		inner.view_dispatcher
+		     .on_navigation_event(move || unsafe { ffi::scene_manager_handle_back_event(raw) });
+		if let Some(period) = self.tick_period {
+			inner.view_dispatcher
+			     .on_tick_event(period, move || unsafe { ffi::scene_manager_handle_tick_event(raw) });
+		}
+
+		Ok(SceneManager(NonNull::from(Box::leak(inner))))
+	}
+}
+
+
+impl<A: 'static> SceneManager<A> {
+	pub fn builder() -> SceneManagerBuilder<A> {
+		SceneManagerBuilder { scenes: Vec::new(),
+		                      tick_period: None }
+	}
+
+	/// Enters the scene `start` and runs the [`ViewDispatcher`] until it stops.
+	///
+	/// All entered scenes are exited before return.
+	pub fn run<S: Into<u32>>(&mut self, start: S) {
+		let inner = self.0.as_ptr();
+		unsafe {
+			ffi::scene_manager_next_scene((*inner).raw, start.into());
+			ffi::view_dispatcher_run((*inner).view_dispatcher.as_ptr());
+			ffi::scene_manager_stop((*inner).raw);
+		}
+	}
+
+	pub fn app(&self) -> &A { unsafe { &self.0.as_ref().app } }
+	pub fn app_mut(&mut self) -> &mut A { unsafe { &mut self.0.as_mut().app } }
+
+	pub fn view_dispatcher(&self) -> &ViewDispatcher { unsafe { &self.0.as_ref().view_dispatcher } }
+	pub fn view_dispatcher_mut(&mut self) -> SceneDispatcher<'_> { SceneDispatcher(unsafe { &mut self.0.as_mut().view_dispatcher }) }
+}
+
+impl<A: 'static> Drop for SceneManager<A> {
+	fn drop(&mut self) {
+		unsafe {
+			let inner = Box::from_raw(self.0.as_ptr());
+			ffi::scene_manager_free(inner.raw);
+		}
+	}
+}
+
+
+impl<A: 'static> Inner<A> {
+	/// Calls handler `f` of the scene `index`, then performs requested navigation.
+	///
+	/// Navigation re-enters handlers, so the borrow of `Inner` must be released before.
+	unsafe fn dispatch<F>(this: *mut Self, index: usize, f: F) -> bool
+		where F: FnOnce(&mut dyn Scene<A>, &mut SceneContext<'_, A>) -> bool {
+		let (consumed, navigation, raw) = {
+			let Inner { raw,
+			            app,
+			            view_dispatcher,
+			            scenes,
+			            navigation,
+			            .. } = &mut *this;
+			let consumed = match scenes.get_mut(index) {
+				Some(scene) => {
+					let mut ctx = SceneContext { app,
+					                             view_dispatcher: SceneDispatcher(view_dispatcher),
+					                             raw: *raw,
+					                             navigation };
+					f(scene.as_mut(), &mut ctx)
+				},
+				None => false,
+			};
+			(consumed, navigation.take(), *raw)
+		};
+
+		match navigation {
+			Some(Navigation::Next(id)) => ffi::scene_manager_next_scene(raw, id),
+			Some(Navigation::Previous) => {
+				if !ffi::scene_manager_previous_scene(raw) {
+					(*this).view_dispatcher.stop();
+				}
+			},
+			Some(Navigation::SearchPrevious(id)) => {
+				ffi::scene_manager_search_and_switch_to_previous_scene(raw, id);
+			},
+			Some(Navigation::SearchAnother(id)) => {
+				ffi::scene_manager_search_and_switch_to_another_scene(raw, id);
+			},
+			None => {},
+		}
+		consumed
+	}
+}
+
+
+unsafe extern "C" fn enter_proxy<A: 'static, const N: usize>(context: *mut c_void) {
+	Inner::<A>::dispatch(context as _, N, |scene, ctx| {
+		scene.on_enter(ctx);
+		true
+	});
+}
+
+// `SceneManagerEventType` is `#[non_exhaustive]` in bindings, but it is `repr(u8)`:
+#[allow(improper_ctypes_definitions)]
+unsafe extern "C" fn event_proxy<A: 'static, const N: usize>(context: *mut c_void, event: ffi::SceneManagerEvent) -> bool {
+	match SceneEvent::try_from(&event) {
+		Ok(event) => Inner::<A>::dispatch(context as _, N, |scene, ctx| scene.on_event(ctx, event)),
+		Err(_) => false,
+	}
+}
+
+unsafe extern "C" fn exit_proxy<A: 'static, const N: usize>(context: *mut c_void) {
+	Inner::<A>::dispatch(context as _, N, |scene, ctx| {
+		scene.on_exit(ctx);
+		true
+	});
+}
+
+
+type Handlers = ([ffi::AppSceneOnEnterCallback; MAX_SCENES],
+                 [ffi::AppSceneOnEventCallback; MAX_SCENES],
+                 [ffi::AppSceneOnExitCallback; MAX_SCENES]);
+
+/// Firmware passes only the context to the handlers, so there is a separate handler for each scene index.
+macro_rules! handlers {
+	($($n:literal)*) => {
+		fn handlers<A: 'static>() -> Handlers {
+			([$(Some(enter_proxy::<A, $n>)),*], [$(Some(event_proxy::<A, $n>)),*], [$(Some(exit_proxy::<A, $n>)),*])
+		}
+	};
+}
+
+handlers!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31);
@@ -0,0 +1,139 @@
+use core::any::Any;
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::input::InputEvent;
+use super::{Canvas, Orientation};
+
+
+/// Something that owns a firmware `View`, so it can be added to the [`ViewDispatcher`](super::ViewDispatcher).
+///
+/// # Safety
+/// Returned view must be valid while `self` is alive.
+pub unsafe trait AsView: Any {
+	fn as_view(&self) -> *mut ffi::View;
+}
+
+impl dyn AsView {
+	pub fn is<T: AsView>(&self) -> bool { Any::type_id(self) == core::any::TypeId::of::<T>() }
+
+	pub fn downcast_ref<T: AsView>(&self) -> Option<&T> {
+		if self.is::<T>() {
+			Some(unsafe { &*(self as *const dyn AsView as *const T) })
+		} else {
+			None
+		}
+	}
+
+	pub fn downcast_mut<T: AsView>(&mut self) -> Option<&mut T> {
+		if self.is::<T>() {
+			Some(unsafe { &mut *(self as *mut dyn AsView as *mut T) })
+		} else {
+			None
+		}
+	}
+}
+
+
+type DrawFn = dyn FnMut(&mut Canvas<'_>) + Send;
+type InputFn = dyn FnMut(InputEvent) -> bool + Send;
+type DrawSlot = Option<Box<DrawFn>>;
+type InputSlot = Option<Box<InputFn>>;
+
+/// Custom view with draw and input callbacks, like the [`ViewPort`](super::ViewPort) but for the [`ViewDispatcher`](super::ViewDispatcher).
+///
+/// Draw and input callbacks are called from different threads, so they are boxed separately.
+pub struct View {
+	raw: NonNull<ffi::View>,
+	draw: Box<DrawSlot>,
+	input: Box<InputSlot>,
+}
+
+
+impl View {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::view_alloc() }).ok_or(NullPointerError)?;
+		let mut draw = Box::<DrawSlot>::default();
+		let mut input = Box::<InputSlot>::default();
+
+		unsafe {
+			// Draw callback receives model instead of context, so the model is pointer to draw callback.
+			// Locking model also guards its replacement while drawing in the GUI thread.
+			ffi::view_allocate_model(
+			                         raw.as_ptr(),
+			                         ffi::ViewModelType::ViewModelTypeLocking,
+			                         core::mem::size_of::<*mut DrawSlot>(),
+			);
+			let model = ffi::view_get_model(raw.as_ptr()) as *mut *mut DrawSlot;
+			model.write(draw.as_mut());
+			ffi::view_commit_model(raw.as_ptr(), false);
+
+			ffi::view_set_context(raw.as_ptr(), input.as_mut() as *mut InputSlot as *mut c_void);
+			ffi::view_set_draw_callback(raw.as_ptr(), Some(draw_proxy));
+			ffi::view_set_input_callback(raw.as_ptr(), Some(input_proxy));
+		}
+
+		Ok(Self { raw, draw, input })
+	}
+
+	/// Sets the draw callback, replaces previous one.
+	pub fn on_draw<F: FnMut(&mut Canvas<'_>) + Send + 'static>(&mut self, f: F) {
+		unsafe {
+			ffi::view_get_model(self.raw.as_ptr());
+			*self.draw = Some(Box::new(f));
+			ffi::view_commit_model(self.raw.as_ptr(), false);
+		}
+	}
+
+	/// Sets the input callback, replaces previous one.
+	///
+	/// Callback returns `true` if the event is consumed.
+	/// Not consumed `Back` is passed to the [`ViewDispatcher`](super::ViewDispatcher) as navigation event.
+	pub fn on_input<F: FnMut(InputEvent) -> bool + Send + 'static>(&mut self, f: F) { *self.input = Some(Box::new(f)); }
+
+	/// Requests redraw.
+	pub fn update(&self) {
+		unsafe {
+			ffi::view_get_model(self.raw.as_ptr());
+			ffi::view_commit_model(self.raw.as_ptr(), true);
+		}
+	}
+
+	pub fn set_orientation(&self, orientation: Orientation) {
+		let orientation = match orientation {
+			Orientation::Horizontal => ffi::ViewOrientation::ViewOrientationHorizontal,
+			Orientation::HorizontalFlip => ffi::ViewOrientation::ViewOrientationHorizontalFlip,
+			Orientation::Vertical => ffi::ViewOrientation::ViewOrientationVertical,
+			Orientation::VerticalFlip => ffi::ViewOrientation::ViewOrientationVerticalFlip,
+		};
+		unsafe { ffi::view_set_orientation(self.raw.as_ptr(), orientation) }
+	}
+}
+
+unsafe impl AsView for View {
+	fn as_view(&self) -> *mut ffi::View { self.raw.as_ptr() }
+}
+
+impl Drop for View {
+	fn drop(&mut self) { unsafe { ffi::view_free(self.raw.as_ptr()) } }
+}
+
+unsafe impl Send for View {}
+
+
+unsafe extern "C" fn draw_proxy(canvas: *mut ffi::Canvas, model: *mut c_void) {
+	let draw = &mut **(model as *mut *mut DrawSlot);
+	if let (Some(draw), Some(mut canvas)) = (draw.as_mut(), Canvas::from_raw(canvas)) {
+		draw(&mut canvas);
+	}
+}
+
+unsafe extern "C" fn input_proxy(event: *mut ffi::InputEvent, context: *mut c_void) -> bool {
+	let input = &mut *(context as *mut InputSlot);
+	match (input.as_mut(), event.as_ref().map(InputEvent::try_from)) {
+		(Some(input), Some(Ok(event))) => input(event),
+		_ => false,
+	}
+}
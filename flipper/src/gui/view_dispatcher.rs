@@ -0,0 +1,183 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::alloc::vec::Vec;
+use crate::error::NullPointerError;
+use crate::time::ticks;
+use super::{AsView, Gui};
+
+
+type CustomFn = dyn FnMut(u32) -> bool;
+type NavigationFn = dyn FnMut() -> bool;
+type TickFn = dyn FnMut();
+
+#[derive(Default)]
+struct Callbacks {
+	custom: Option<Box<CustomFn>>,
+	navigation: Option<Box<NavigationFn>>,
+	tick: Option<Box<TickFn>>,
+}
+
+
+/// Owns views and switches between them, see also [`SceneManager`](super::SceneManager).
+///
+/// Event callbacks are called from the thread running [`ViewDispatcher::run`].
+pub struct ViewDispatcher {
+	raw: NonNull<ffi::ViewDispatcher>,
+	gui: Option<Gui>,
+	views: Vec<(u32, Box<dyn AsView>)>,
+	callbacks: Box<Callbacks>,
+}
+
+
+impl ViewDispatcher {
+	/// Allocates dispatcher with enabled event queue.
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::view_dispatcher_alloc() }).ok_or(NullPointerError)?;
+		let mut callbacks = Box::<Callbacks>::default();
+		unsafe {
+			ffi::view_dispatcher_enable_queue(raw.as_ptr());
+			let context = callbacks.as_mut() as *mut Callbacks as *mut c_void;
+			ffi::view_dispatcher_set_event_callback_context(raw.as_ptr(), context);
+		}
+
+		Ok(Self { raw,
+		          gui: None,
+		          views: Vec::new(),
+		          callbacks })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::ViewDispatcher { self.raw.as_ptr() }
+
+
+	/// Attaches the dispatcher to the [`Gui`].
+	pub fn attach(&mut self, kind: ViewDispatcherType) -> Result<(), NullPointerError> {
+		if self.gui.is_none() {
+			let gui = Gui::open()?;
+			unsafe { ffi::view_dispatcher_attach_to_gui(self.as_ptr(), gui.as_ptr(), kind.into()) };
+			self.gui = Some(gui);
+		}
+		Ok(())
+	}
+
+
+	/// Adds `view` with the `id`, takes ownership of it.
+	///
+	/// Previous view with the same `id` is removed and returned.
+	pub fn add_view<V: AsView>(&mut self, id: u32, view: V) -> Option<Box<dyn AsView>> {
+		let previous = self.remove_view(id);
+		unsafe { ffi::view_dispatcher_add_view(self.as_ptr(), id, view.as_view()) };
+		self.views.push((id, Box::new(view)));
+		previous
+	}
+
+	/// Removes view with the `id` and gives it back.
+	pub fn remove_view(&mut self, id: u32) -> Option<Box<dyn AsView>> {
+		let index = self.views.iter().position(|(view_id, _)| *view_id == id)?;
+		unsafe { ffi::view_dispatcher_remove_view(self.as_ptr(), id) };
+		Some(self.views.remove(index).1)
+	}
+
+	/// Returns view with the `id` if it is of type `V`.
+	pub fn view<V: AsView>(&self, id: u32) -> Option<&V> {
+		self.views
+		    .iter()
+		    .find(|(view_id, _)| *view_id == id)
+		    .and_then(|(_, view)| view.downcast_ref())
+	}
+
+	/// Returns view with the `id` if it is of type `V`.
+	pub fn view_mut<V: AsView>(&mut self, id: u32) -> Option<&mut V> {
+		self.views
+		    .iter_mut()
+		    .find(|(view_id, _)| *view_id == id)
+		    .and_then(|(_, view)| view.downcast_mut())
+	}
+
+	pub fn switch_to_view(&self, id: u32) { unsafe { ffi::view_dispatcher_switch_to_view(self.as_ptr(), id) } }
+
+	pub fn send_to_front(&self) { unsafe { ffi::view_dispatcher_send_to_front(self.as_ptr()) } }
+	pub fn send_to_back(&self) { unsafe { ffi::view_dispatcher_send_to_back(self.as_ptr()) } }
+
+
+	/// Sends custom event into the queue, can be called from any thread.
+	pub fn send_custom_event(&self, event: u32) { unsafe { ffi::view_dispatcher_send_custom_event(self.as_ptr(), event) } }
+
+	/// Sets handler of custom events, returns `true` if the event is consumed.
+	pub fn on_custom_event<F: FnMut(u32) -> bool + 'static>(&mut self, f: F) {
+		self.callbacks.custom = Some(Box::new(f));
+		unsafe { ffi::view_dispatcher_set_custom_event_callback(self.as_ptr(), Some(custom_proxy)) };
+	}
+
+	/// Sets handler of not consumed `Back`, returns `false` to stop the dispatcher.
+	pub fn on_navigation_event<F: FnMut() -> bool + 'static>(&mut self, f: F) {
+		self.callbacks.navigation = Some(Box::new(f));
+		unsafe { ffi::view_dispatcher_set_navigation_event_callback(self.as_ptr(), Some(navigation_proxy)) };
+	}
+
+	/// Sets handler called with specified `period` when there are no other events.
+	pub fn on_tick_event<F: FnMut() + 'static>(&mut self, period: Duration, f: F) {
+		self.callbacks.tick = Some(Box::new(f));
+		unsafe { ffi::view_dispatcher_set_tick_event_callback(self.as_ptr(), Some(tick_proxy), ticks(period)) };
+	}
+
+
+	/// Runs the event loop until [`stop`](ViewDispatcher::stop).
+	pub fn run(&mut self) { unsafe { ffi::view_dispatcher_run(self.as_ptr()) } }
+
+	/// Stops the event loop, can be called from event callbacks or from any thread.
+	pub fn stop(&self) { unsafe { ffi::view_dispatcher_stop(self.as_ptr()) } }
+}
+
+impl Drop for ViewDispatcher {
+	fn drop(&mut self) {
+		// all views must be removed before free:
+		for (id, _) in self.views.iter() {
+			unsafe { ffi::view_dispatcher_remove_view(self.as_ptr(), *id) };
+		}
+		unsafe { ffi::view_dispatcher_free(self.as_ptr()) };
+	}
+}
+
+
+/// Layer of the [`Gui`] for the [`ViewDispatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewDispatcherType {
+	/// Fullscreen with status bar on top of it, for internal use.
+	Desktop,
+	/// With status bar.
+	Window,
+	/// Without status bar.
+	Fullscreen,
+}
+
+impl From<ViewDispatcherType> for ffi::ViewDispatcherType {
+	fn from(kind: ViewDispatcherType) -> Self {
+		match kind {
+			ViewDispatcherType::Desktop => ffi::ViewDispatcherType::ViewDispatcherTypeDesktop,
+			ViewDispatcherType::Window => ffi::ViewDispatcherType::ViewDispatcherTypeWindow,
+			ViewDispatcherType::Fullscreen => ffi::ViewDispatcherType::ViewDispatcherTypeFullscreen,
+		}
+	}
+}
+
+
+unsafe extern "C" fn custom_proxy(context: *mut c_void, event: u32) -> bool {
+	let callbacks = &mut *(context as *mut Callbacks);
+	callbacks.custom.as_mut().map_or(false, |f| f(event))
+}
+
+unsafe extern "C" fn navigation_proxy(context: *mut c_void) -> bool {
+	let callbacks = &mut *(context as *mut Callbacks);
+	callbacks.navigation.as_mut().map_or(false, |f| f())
+}
+
+unsafe extern "C" fn tick_proxy(context: *mut c_void) {
+	let callbacks = &mut *(context as *mut Callbacks);
+	if let Some(f) = callbacks.tick.as_mut() {
+		f()
+	}
+}
@@ -0,0 +1,160 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::input::InputEvent;
+use super::{Canvas, Gui, Layer};
+
+
+type DrawFn = dyn FnMut(&mut Canvas<'_>) + Send;
+type InputFn = dyn FnMut(InputEvent) + Send;
+
+
+/// View port with draw and input callbacks.
+///
+/// Callbacks are called from the GUI thread.
+/// Attached view port is removed from the [`Gui`] on drop.
+pub struct ViewPort {
+	raw: NonNull<ffi::ViewPort>,
+	gui: Option<Gui>,
+	draw: Option<Box<Box<DrawFn>>>,
+	input: Option<Box<Box<InputFn>>>,
+}
+
+
+impl ViewPort {
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = unsafe { ffi::view_port_alloc() };
+		NonNull::new(raw).map(|raw| {
+			                 Self { raw,
+			                        gui: None,
+			                        draw: None,
+			                        input: None }
+		                 })
+		                 .ok_or(NullPointerError)
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::ViewPort { self.raw.as_ptr() }
+
+
+	/// Sets the draw callback, replaces previous one.
+	pub fn on_draw<F: FnMut(&mut Canvas<'_>) + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<DrawFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<DrawFn> as *mut c_void;
+		unsafe { ffi::view_port_draw_callback_set(self.as_ptr(), Some(draw_proxy), context) };
+		// previous callback is dropped after replacement, so it can't be called anymore:
+		self.draw = Some(callback);
+	}
+
+	/// Sets the input callback, replaces previous one.
+	pub fn on_input<F: FnMut(InputEvent) + Send + 'static>(&mut self, f: F) {
+		let mut callback: Box<Box<InputFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<InputFn> as *mut c_void;
+		unsafe { ffi::view_port_input_callback_set(self.as_ptr(), Some(input_proxy), context) };
+		self.input = Some(callback);
+	}
+
+
+	/// Adds the view port to the `layer` of the [`Gui`].
+	///
+	/// If it is already attached, moves it to the `layer`.
+	pub fn attach(&mut self, layer: Layer) -> Result<(), NullPointerError> {
+		self.detach();
+		let gui = Gui::open()?;
+		unsafe { gui.add_view_port(self.as_ptr(), layer) };
+		self.gui = Some(gui);
+		Ok(())
+	}
+
+	/// Removes the view port from the [`Gui`], does nothing if not attached.
+	pub fn detach(&mut self) {
+		if let Some(gui) = self.gui.take() {
+			unsafe { gui.remove_view_port(self.as_ptr()) }
+		}
+	}
+
+	pub fn is_attached(&self) -> bool { self.gui.is_some() }
+
+	/// Moves attached view port to the top of its layer.
+	pub fn send_to_front(&self) {
+		if let Some(gui) = &self.gui {
+			unsafe { ffi::gui_view_port_send_to_front(gui.as_ptr(), self.as_ptr()) }
+		}
+	}
+
+	/// Moves attached view port to the bottom of its layer.
+	pub fn send_to_back(&self) {
+		if let Some(gui) = &self.gui {
+			unsafe { ffi::gui_view_port_send_to_back(gui.as_ptr(), self.as_ptr()) }
+		}
+	}
+
+
+	/// Requests redraw, can be called from any thread.
+	pub fn update(&self) { unsafe { ffi::view_port_update(self.as_ptr()) } }
+
+	pub fn set_enabled(&self, enabled: bool) { unsafe { ffi::view_port_enabled_set(self.as_ptr(), enabled) } }
+	pub fn is_enabled(&self) -> bool { unsafe { ffi::view_port_is_enabled(self.as_ptr()) } }
+
+	/// Sets width, `0` means auto.
+	pub fn set_width(&self, width: u8) { unsafe { ffi::view_port_set_width(self.as_ptr(), width) } }
+	pub fn width(&self) -> u8 { unsafe { ffi::view_port_get_width(self.as_ptr()) } }
+
+	/// Sets height, `0` means auto.
+	pub fn set_height(&self, height: u8) { unsafe { ffi::view_port_set_height(self.as_ptr(), height) } }
+	pub fn height(&self) -> u8 { unsafe { ffi::view_port_get_height(self.as_ptr()) } }
+
+	pub fn set_orientation(&self, orientation: Orientation) {
+		unsafe { ffi::view_port_set_orientation(self.as_ptr(), orientation.into()) }
+	}
+}
+
+impl Drop for ViewPort {
+	fn drop(&mut self) {
+		self.detach();
+		unsafe {
+			ffi::view_port_enabled_set(self.as_ptr(), false);
+			ffi::view_port_free(self.as_ptr());
+		}
+	}
+}
+
+unsafe impl Send for ViewPort {}
+
+
+unsafe extern "C" fn draw_proxy(canvas: *mut ffi::Canvas, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<DrawFn>);
+	if let Some(mut canvas) = Canvas::from_raw(canvas) {
+		callback(&mut canvas);
+	}
+}
+
+unsafe extern "C" fn input_proxy(event: *mut ffi::InputEvent, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<InputFn>);
+	if let Some(Ok(event)) = event.as_ref().map(InputEvent::try_from) {
+		callback(event);
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+	#[default]
+	Horizontal,
+	HorizontalFlip,
+	Vertical,
+	VerticalFlip,
+}
+
+impl From<Orientation> for ffi::ViewPortOrientation {
+	fn from(orientation: Orientation) -> Self {
+		match orientation {
+			Orientation::Horizontal => ffi::ViewPortOrientation::ViewPortOrientationHorizontal,
+			Orientation::HorizontalFlip => ffi::ViewPortOrientation::ViewPortOrientationHorizontalFlip,
+			Orientation::Vertical => ffi::ViewPortOrientation::ViewPortOrientationVertical,
+			Orientation::VerticalFlip => ffi::ViewPortOrientation::ViewPortOrientationVerticalFlip,
+		}
+	}
+}
@@ -0,0 +1,132 @@
+use core::time::Duration;
+use crate::alloc::sync::Arc;
+use crate::error::NullPointerError;
+use crate::gui::ViewPort;
+use crate::sync::channel::Queue;
+use crate::time::{ticks, Timer, WAIT_FOREVER};
+use super::{InputEvent, Subscription};
+
+
+/// Event received by the [`EventLoop`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<T> {
+	Input(InputEvent),
+	/// Tick of the periodic timer, see [`EventLoop::set_tick`].
+	Tick,
+	/// Custom message sent with [`Sender`].
+	Message(T),
+}
+
+
+/// Application event loop, multiplexes input, timer ticks and custom messages into one queue.
+///
+/// ```ignore
+/// let mut events = EventLoop::<Msg>::new(8)?;
+/// events.input_from(&mut view_port);
+/// events.set_tick(Some(Duration::from_millis(500)))?;
+///
+/// for event in events.iter() {
+///     match event {
+///         Event::Input(input) if input.is(Key::Back, InputType::Short) => break,
+///         Event::Input(input) => { /* ... */ },
+///         Event::Tick => view_port.update(),
+///         Event::Message(msg) => { /* ... */ },
+///     }
+/// }
+/// ```
+pub struct EventLoop<T: Send + 'static = ()> {
+	queue: Arc<Queue<Event<T>>>,
+	timer: Option<Timer>,
+	subscription: Option<Subscription>,
+}
+
+
+impl<T: Send + 'static> EventLoop<T> {
+	/// Creates event loop with queue for `capacity` events.
+	pub fn new(capacity: u32) -> Result<Self, NullPointerError> {
+		Ok(Self { queue: Arc::new(Queue::new(capacity)?),
+		          timer: None,
+		          subscription: None })
+	}
+
+	/// Returns sender of custom messages.
+	pub fn sender(&self) -> Sender<T> { Sender(self.queue.clone()) }
+
+	/// Routes input events of the `view_port` into this loop.
+	///
+	/// Replaces input callback of the `view_port`.
+	pub fn input_from(&self, view_port: &mut ViewPort) {
+		let queue = self.queue.clone();
+		view_port.on_input(move |event| {
+			         queue.put(Event::Input(event), 0).ok();
+		         });
+	}
+
+	/// Routes all input events of the system into this loop, see [`Subscription`].
+	pub fn subscribe_input(&mut self) -> Result<(), NullPointerError> {
+		let queue = self.queue.clone();
+		let subscription = Subscription::new(move |event| {
+			queue.put(Event::Input(event), 0).ok();
+		})?;
+		self.subscription = Some(subscription);
+		Ok(())
+	}
+
+	/// Starts periodic [`Event::Tick`] with specified `period`, `None` stops it.
+	pub fn set_tick(&mut self, period: Option<Duration>) -> Result<(), NullPointerError> {
+		self.timer = None;
+		if let Some(period) = period {
+			let queue = self.queue.clone();
+			let timer = Timer::periodic(move || {
+				queue.put(Event::Tick, 0).ok();
+			})?;
+			// fails only if the timer service queue is full, then ticks just don't come:
+			timer.start(period).ok();
+			self.timer = Some(timer);
+		}
+		Ok(())
+	}
+
+
+	/// Waits for the next event.
+	pub fn recv(&self) -> Event<T> {
+		loop {
+			if let Some(event) = self.queue.get(WAIT_FOREVER) {
+				return event;
+			}
+		}
+	}
+
+	/// Waits for the next event, returns `None` on timeout.
+	pub fn recv_timeout(&self, timeout: Duration) -> Option<Event<T>> { self.queue.get(ticks(timeout)) }
+
+	/// Returns the next event if there is one.
+	pub fn try_recv(&self) -> Option<Event<T>> { self.queue.get(0) }
+
+	/// Infinite iterator over events, same as calling [`recv`](EventLoop::recv) in the loop.
+	pub fn iter(&self) -> impl Iterator<Item = Event<T>> + '_ { core::iter::repeat_with(|| self.recv()) }
+}
+
+
+/// Sender of custom messages into the [`EventLoop`].
+///
+/// Can be used from any thread, and from ISR.
+pub struct Sender<T: Send + 'static>(Arc<Queue<Event<T>>>);
+
+impl<T: Send + 'static> Sender<T> {
+	/// Sends message without waiting, returns it back if the queue is full.
+	pub fn send(&self, msg: T) -> Result<(), T> { self.send_timeout(msg, Duration::ZERO) }
+
+	/// Sends message, waits for a free space in the queue up to `timeout`.
+	pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), T> {
+		match self.0.put(Event::Message(msg), ticks(timeout)) {
+			Ok(()) => Ok(()),
+			Err(Event::Message(msg)) => Err(msg),
+			Err(_) => unreachable!(),
+		}
+	}
+}
+
+impl<T: Send + 'static> Clone for Sender<T> {
+	fn clone(&self) -> Self { Self(self.0.clone()) }
+}
@@ -0,0 +1,144 @@
+//! Typed input events, subscription to the input events record and an application event loop.
+
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::pubsub;
+
+pub use event_loop::{Event, EventLoop, Sender};
+
+
+pub mod event_loop;
+
+
+/// Name of the input events record, [`ffi::FuriPubSub`] of [`ffi::InputEvent`].
+pub const RECORD_INPUT_EVENTS: &[u8] = <crate::record::InputEvents as crate::record::Service>::NAME;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+	Up,
+	Down,
+	Right,
+	Left,
+	Ok,
+	Back,
+}
+
+impl TryFrom<&ffi::InputKey> for Key {
+	type Error = ();
+
+	fn try_from(key: &ffi::InputKey) -> Result<Self, Self::Error> {
+		match key {
+			ffi::InputKey::InputKeyUp => Ok(Key::Up),
+			ffi::InputKey::InputKeyDown => Ok(Key::Down),
+			ffi::InputKey::InputKeyRight => Ok(Key::Right),
+			ffi::InputKey::InputKeyLeft => Ok(Key::Left),
+			ffi::InputKey::InputKeyOk => Ok(Key::Ok),
+			ffi::InputKey::InputKeyBack => Ok(Key::Back),
+			_ => Err(()),
+		}
+	}
+}
+
+impl From<Key> for ffi::InputKey {
+	fn from(key: Key) -> Self {
+		match key {
+			Key::Up => ffi::InputKey::InputKeyUp,
+			Key::Down => ffi::InputKey::InputKeyDown,
+			Key::Right => ffi::InputKey::InputKeyRight,
+			Key::Left => ffi::InputKey::InputKeyLeft,
+			Key::Ok => ffi::InputKey::InputKeyOk,
+			Key::Back => ffi::InputKey::InputKeyBack,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputType {
+	/// Hardware event, key pressed.
+	Press,
+	/// Hardware event, key released.
+	Release,
+	/// Software event, key released within the long press timeout.
+	Short,
+	/// Software event, key held longer than the long press timeout.
+	Long,
+	/// Software event, key is still held after the long press.
+	Repeat,
+}
+
+impl TryFrom<&ffi::InputType> for InputType {
+	type Error = ();
+
+	fn try_from(kind: &ffi::InputType) -> Result<Self, Self::Error> {
+		match kind {
+			ffi::InputType::InputTypePress => Ok(InputType::Press),
+			ffi::InputType::InputTypeRelease => Ok(InputType::Release),
+			ffi::InputType::InputTypeShort => Ok(InputType::Short),
+			ffi::InputType::InputTypeLong => Ok(InputType::Long),
+			ffi::InputType::InputTypeRepeat => Ok(InputType::Repeat),
+			_ => Err(()),
+		}
+	}
+}
+
+impl From<InputType> for ffi::InputType {
+	fn from(kind: InputType) -> Self {
+		match kind {
+			InputType::Press => ffi::InputType::InputTypePress,
+			InputType::Release => ffi::InputType::InputTypeRelease,
+			InputType::Short => ffi::InputType::InputTypeShort,
+			InputType::Long => ffi::InputType::InputTypeLong,
+			InputType::Repeat => ffi::InputType::InputTypeRepeat,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputEvent {
+	pub key: Key,
+	pub kind: InputType,
+	/// Same for all events of the one press-release cycle.
+	pub sequence: u32,
+}
+
+impl InputEvent {
+	pub fn is(&self, key: Key, kind: InputType) -> bool { self.key == key && self.kind == kind }
+}
+
+impl TryFrom<&ffi::InputEvent> for InputEvent {
+	type Error = ();
+
+	fn try_from(event: &ffi::InputEvent) -> Result<Self, Self::Error> {
+		Ok(Self { key: (&event.key).try_into()?,
+		          kind: (&event.type_).try_into()?,
+		          sequence: event.sequence })
+	}
+}
+
+impl From<InputEvent> for ffi::InputEvent {
+	fn from(event: InputEvent) -> Self {
+		Self { sequence: event.sequence,
+		       key: event.key.into(),
+		       type_: event.kind.into() }
+	}
+}
+
+
+/// Subscription to all input events of the system, unsubscribes on drop.
+///
+/// Callback is called from the input service thread.
+pub struct Subscription(pubsub::Subscription<ffi::InputEvent>);
+
+impl Subscription {
+	pub fn new<F: FnMut(InputEvent) + Send + 'static>(mut f: F) -> Result<Self, NullPointerError> {
+		let subscription = pubsub::input()?.subscribe(move |event| {
+			                                   if let Ok(event) = InputEvent::try_from(event) {
+				                                   f(event);
+			                                   }
+		                                   })?;
+		Ok(Self(subscription))
+	}
+}
@@ -0,0 +1,223 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use crate::alloc::boxed::Box;
+use crate::alloc::string::String;
+use crate::alloc::vec;
+use crate::alloc::vec::Vec;
+use super::{BufRead, Read, Result, Seek, SeekFrom, Write};
+
+
+/// Default capacity of [`BufReader`] and [`BufWriter`].
+///
+/// Much less than in the std because of tiny heap.
+pub const DEFAULT_BUF_SIZE: usize = 256;
+
+
+/// Adds buffering to any reader.
+pub struct BufReader<R> {
+	inner: R,
+	buf: Box<[u8]>,
+	pos: usize,
+	filled: usize,
+}
+
+
+impl<R: Read> BufReader<R> {
+	pub fn new(inner: R) -> Self { Self::with_capacity(DEFAULT_BUF_SIZE, inner) }
+
+	pub fn with_capacity(capacity: usize, inner: R) -> Self {
+		Self { inner,
+		       buf: vec![0; capacity].into_boxed_slice(),
+		       pos: 0,
+		       filled: 0 }
+	}
+}
+
+impl<R> BufReader<R> {
+	pub fn get_ref(&self) -> &R { &self.inner }
+	pub fn get_mut(&mut self) -> &mut R { &mut self.inner }
+	pub fn into_inner(self) -> R { self.inner }
+
+	/// Returns currently buffered data.
+	pub fn buffer(&self) -> &[u8] { &self.buf[self.pos..self.filled] }
+	pub fn capacity(&self) -> usize { self.buf.len() }
+
+	fn discard_buffer(&mut self) {
+		self.pos = 0;
+		self.filled = 0;
+	}
+}
+
+impl<R: Read> Read for BufReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		// bypass the buffer for large reads:
+		if self.pos == self.filled && buf.len() >= self.capacity() {
+			self.discard_buffer();
+			return self.inner.read(buf);
+		}
+
+		let mut available = self.fill_buf()?;
+		let read = available.read(buf)?;
+		self.consume(read);
+		Ok(read)
+	}
+}
+
+impl<R: Read> BufRead for BufReader<R> {
+	fn fill_buf(&mut self) -> Result<&[u8]> {
+		if self.pos >= self.filled {
+			self.filled = self.inner.read(&mut self.buf)?;
+			self.pos = 0;
+		}
+		Ok(self.buffer())
+	}
+
+	fn consume(&mut self, amt: usize) { self.pos = (self.pos + amt).min(self.filled) }
+}
+
+impl<R: Seek> Seek for BufReader<R> {
+	/// Seeks the underlying reader and discards the buffer.
+	///
+	/// `SeekFrom::Current` is relative to the logical position, so buffered data is taken into account.
+	fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+		let pos = match pos {
+			SeekFrom::Current(n) => SeekFrom::Current(n - (self.filled - self.pos) as i64),
+			pos => pos,
+		};
+		let result = self.inner.seek(pos)?;
+		self.discard_buffer();
+		Ok(result)
+	}
+}
+
+impl<R: fmt::Debug> fmt::Debug for BufReader<R> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("BufReader")
+		 .field("reader", &self.inner)
+		 .field("buffer", &format_args!("{}/{}", self.filled - self.pos, self.capacity()))
+		 .finish()
+	}
+}
+
+
+/// Adds buffering to any writer.
+///
+/// Buffer is flushed on drop, but errors are ignored there.
+/// Use [`BufWriter::into_inner`] or [`Write::flush`] to handle them.
+pub struct BufWriter<W: Write> {
+	inner: W,
+	buf: Vec<u8>,
+}
+
+
+impl<W: Write> BufWriter<W> {
+	pub fn new(inner: W) -> Self { Self::with_capacity(DEFAULT_BUF_SIZE, inner) }
+
+	pub fn with_capacity(capacity: usize, inner: W) -> Self {
+		Self { inner,
+		       buf: Vec::with_capacity(capacity) }
+	}
+
+	pub fn get_ref(&self) -> &W { &self.inner }
+	pub fn get_mut(&mut self) -> &mut W { &mut self.inner }
+
+	/// Returns currently buffered data.
+	pub fn buffer(&self) -> &[u8] { &self.buf }
+	pub fn capacity(&self) -> usize { self.buf.capacity() }
+
+	/// Writes out the buffer and returns the underlying writer.
+	pub fn into_inner(mut self) -> Result<W> {
+		self.flush_buf()?;
+		let this = ManuallyDrop::new(self);
+		unsafe {
+			drop(core::ptr::read(&this.buf));
+			Ok(core::ptr::read(&this.inner))
+		}
+	}
+
+	fn flush_buf(&mut self) -> Result<()> {
+		let mut written = 0;
+		let result = loop {
+			if written >= self.buf.len() {
+				break Ok(());
+			}
+			match self.inner.write(&self.buf[written..]) {
+				Ok(0) => break Err(super::Error::WriteZero),
+				Ok(n) => written += n,
+				Err(err) => break Err(err),
+			}
+		};
+		self.buf.drain(..written);
+		result
+	}
+}
+
+impl<W: Write> Write for BufWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		if self.buf.len() + buf.len() > self.capacity() {
+			self.flush_buf()?;
+		}
+		if buf.len() >= self.capacity() {
+			self.inner.write(buf)
+		} else {
+			self.buf.extend_from_slice(buf);
+			Ok(buf.len())
+		}
+	}
+
+	fn flush(&mut self) -> Result<()> {
+		self.flush_buf()?;
+		self.inner.flush()
+	}
+}
+
+impl<W: Write + Seek> Seek for BufWriter<W> {
+	/// Writes out the buffer before seeking.
+	fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+		self.flush_buf()?;
+		self.inner.seek(pos)
+	}
+}
+
+impl<W: Write> Drop for BufWriter<W> {
+	fn drop(&mut self) { self.flush_buf().ok(); }
+}
+
+impl<W: Write + fmt::Debug> fmt::Debug for BufWriter<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("BufWriter")
+		 .field("writer", &self.inner)
+		 .field("buffer", &format_args!("{}/{}", self.buf.len(), self.capacity()))
+		 .finish()
+	}
+}
+
+
+/// Iterator over the lines of a [`BufRead`], created by [`BufRead::lines`].
+///
+/// Line endings `\n` and `\r\n` are not included.
+#[derive(Debug)]
+pub struct Lines<B> {
+	pub(super) buf: B,
+}
+
+impl<B: BufRead> Iterator for Lines<B> {
+	type Item = Result<String>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut line = String::new();
+		match self.buf.read_line(&mut line) {
+			Ok(0) => None,
+			Ok(_) => {
+				if line.ends_with('\n') {
+					line.pop();
+					if line.ends_with('\r') {
+						line.pop();
+					}
+				}
+				Some(Ok(line))
+			},
+			Err(err) => Some(Err(err)),
+		}
+	}
+}
@@ -1,41 +1,285 @@
-use core::fmt::Write;
+//! Basic I/O traits like in the `std::io` and their implementations for firmware things.
+//!
+//! Traits are simplified, so there are no vectored I/O, `Interrupted` retries or `bytes()`.
 
+use core::fmt;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
 
-pub mod stdout {}
+pub use stdout::{stdout, Stdout};
+pub use buffered::{BufReader, BufWriter, Lines, DEFAULT_BUF_SIZE};
+pub use stream::Stream;
+pub use stream_buffer::{StreamBuffer, StreamReader, StreamWriter};
 
 
-pub fn stdout() -> Stdout { Stdout }
-pub fn print(s: &str) { Stdout.write_str(s).unwrap(); }
+pub mod stdout;
+pub mod buffered;
+pub mod stream;
+pub mod stream_buffer;
 
 
-pub struct Stdout;
+pub fn print(s: &str) { Stdout.write_all(s.as_bytes()).unwrap(); }
 
-impl core::fmt::Write for Stdout {
-	fn write_str(&mut self, s: &str) -> core::fmt::Result {
-		use crate::ffi::furi_thread_stdout_write;
 
-		unsafe {
-			if furi_thread_stdout_write(s.as_ptr() as _, s.len()) != s.len() {
-				return Err(core::fmt::Error);
-			}
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+
+#[derive(Debug, Clone)]
+pub enum Error {
+	/// Error of the underlying storage.
+	Fs(crate::fs::Error),
+	/// End of data reached before the buffer has been filled.
+	UnexpectedEof,
+	/// Zero bytes written, so the data can't be written entirely.
+	WriteZero,
+	/// Data is not valid, e.g. not UTF-8 when string expected.
+	InvalidData,
+	/// Invalid parameter, e.g. seek to negative position.
+	InvalidInput,
+	/// Unspecified error of the underlying API.
+	Other,
+}
+
+impl core::error::Error for Error {}
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Fs(err) => write!(f, "storage error: {err}"),
+			Error::UnexpectedEof => write!(f, "unexpected end of file"),
+			Error::WriteZero => write!(f, "failed to write whole buffer"),
+			Error::InvalidData => write!(f, "invalid data"),
+			Error::InvalidInput => write!(f, "invalid input parameter"),
+			Error::Other => write!(f, "other error"),
 		}
+	}
+}
+
+impl From<crate::fs::Error> for Error {
+	fn from(err: crate::fs::Error) -> Self { Error::Fs(err) }
+}
 
+
+pub trait Read {
+	/// Pulls some bytes into `buf`, returns how many bytes were read.
+	///
+	/// `Ok(0)` means end of data or empty `buf`.
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+	/// Reads exactly `buf.len()` bytes, otherwise returns [`Error::UnexpectedEof`].
+	fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+		while !buf.is_empty() {
+			match self.read(buf)? {
+				0 => return Err(Error::UnexpectedEof),
+				n => buf = &mut buf[n..],
+			}
+		}
 		Ok(())
 	}
+
+	/// Reads all bytes until the end, appending them to `buf`.
+	///
+	/// Reads into the spare capacity of `buf`, up to `u16::MAX` bytes at once.
+	fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+		let start = buf.len();
+		loop {
+			if buf.len() == buf.capacity() {
+				buf.reserve(32);
+			}
+			let len = buf.len();
+			let spare = (buf.capacity() - len).min(u16::MAX as usize);
+			buf.resize(len + spare, 0);
+			match self.read(&mut buf[len..]) {
+				Ok(0) => {
+					buf.truncate(len);
+					return Ok(len - start);
+				},
+				Ok(n) => buf.truncate(len + n),
+				Err(err) => {
+					buf.truncate(len);
+					return Err(err);
+				},
+			}
+		}
+	}
+
+	/// Reads all bytes until the end, appending them to `buf`.
+	///
+	/// Returns [`Error::InvalidData`] if the data is not valid UTF-8, then `buf` is unchanged.
+	fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+		let mut bytes = Vec::new();
+		let read = self.read_to_end(&mut bytes)?;
+		buf.push_str(core::str::from_utf8(&bytes).map_err(|_| Error::InvalidData)?);
+		Ok(read)
+	}
+
+	fn by_ref(&mut self) -> &mut Self
+		where Self: Sized {
+		self
+	}
 }
 
-impl Stdout {
-	pub fn flush(&mut self) -> core::fmt::Result {
-		use crate::ffi::furi_thread_stdout_flush;
 
-		unsafe {
-			if furi_thread_stdout_flush() != 0 {
-				return Err(core::fmt::Error);
+pub trait Write {
+	/// Writes some bytes from `buf`, returns how many bytes were written.
+	fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+	fn flush(&mut self) -> Result<()>;
+
+	/// Writes entire `buf`, otherwise returns [`Error::WriteZero`].
+	fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+		while !buf.is_empty() {
+			match self.write(buf)? {
+				0 => return Err(Error::WriteZero),
+				n => buf = &buf[n..],
 			}
 		}
 		Ok(())
 	}
+
+	/// Writes formatted string, so `write!` can be used.
+	fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+		struct Adapter<'a, W: ?Sized> {
+			inner: &'a mut W,
+			error: Result<()>,
+		}
+
+		impl<W: Write + ?Sized> fmt::Write for Adapter<'_, W> {
+			fn write_str(&mut self, s: &str) -> fmt::Result {
+				self.inner.write_all(s.as_bytes()).map_err(|err| {
+					                                  self.error = Err(err);
+					                                  fmt::Error
+				                                  })
+			}
+		}
+
+		let mut output = Adapter { inner: self,
+		                           error: Ok(()) };
+		match fmt::write(&mut output, args) {
+			Ok(()) => Ok(()),
+			Err(_) => output.error.and(Err(Error::Other)),
+		}
+	}
+
+	fn by_ref(&mut self) -> &mut Self
+		where Self: Sized {
+		self
+	}
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekFrom {
+	Start(u64),
+	End(i64),
+	Current(i64),
+}
+
+pub trait Seek {
+	/// Seeks to `pos`, returns new position from the start.
+	fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+
+	fn rewind(&mut self) -> Result<()> { self.seek(SeekFrom::Start(0)).map(drop) }
+
+	fn stream_position(&mut self) -> Result<u64> { self.seek(SeekFrom::Current(0)) }
+}
+
+
+pub trait BufRead: Read {
+	/// Returns buffered data, fills the buffer if it is empty.
+	///
+	/// Empty result means end of data.
+	fn fill_buf(&mut self) -> Result<&[u8]>;
+
+	/// Marks `amt` bytes of the buffer as read.
+	fn consume(&mut self, amt: usize);
+
+	/// Reads all bytes until `byte` inclusive or end of data, appending them to `buf`.
+	fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+		let mut read = 0;
+		loop {
+			let (done, used) = {
+				let available = self.fill_buf()?;
+				match available.iter().position(|b| *b == byte) {
+					Some(i) => {
+						buf.extend_from_slice(&available[..=i]);
+						(true, i + 1)
+					},
+					None => {
+						buf.extend_from_slice(available);
+						(available.is_empty(), available.len())
+					},
+				}
+			};
+			self.consume(used);
+			read += used;
+			if done {
+				return Ok(read);
+			}
+		}
+	}
+
+	/// Reads all bytes until newline inclusive or end of data, appending them to `buf`.
+	///
+	/// Returns [`Error::InvalidData`] if the line is not valid UTF-8, then `buf` is unchanged.
+	fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+		let mut bytes = Vec::new();
+		let read = self.read_until(b'\n', &mut bytes)?;
+		buf.push_str(core::str::from_utf8(&bytes).map_err(|_| Error::InvalidData)?);
+		Ok(read)
+	}
+
+	fn lines(self) -> Lines<Self>
+		where Self: Sized {
+		Lines { buf: self }
+	}
 }
 
 
-// TODO: impl Buffers on furi_stream_buffer
+impl<R: Read + ?Sized> Read for &mut R {
+	#[inline]
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> { (**self).read(buf) }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+	#[inline]
+	fn write(&mut self, buf: &[u8]) -> Result<usize> { (**self).write(buf) }
+	#[inline]
+	fn flush(&mut self) -> Result<()> { (**self).flush() }
+}
+
+impl<S: Seek + ?Sized> Seek for &mut S {
+	#[inline]
+	fn seek(&mut self, pos: SeekFrom) -> Result<u64> { (**self).seek(pos) }
+}
+
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+	#[inline]
+	fn fill_buf(&mut self) -> Result<&[u8]> { (**self).fill_buf() }
+	#[inline]
+	fn consume(&mut self, amt: usize) { (**self).consume(amt) }
+}
+
+
+impl Read for &[u8] {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		let len = buf.len().min(self.len());
+		let (head, tail) = self.split_at(len);
+		buf[..len].copy_from_slice(head);
+		*self = tail;
+		Ok(len)
+	}
+}
+
+impl BufRead for &[u8] {
+	fn fill_buf(&mut self) -> Result<&[u8]> { Ok(self) }
+	fn consume(&mut self, amt: usize) { *self = &self[amt.min(self.len())..] }
+}
+
+impl Write for Vec<u8> {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		self.extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> Result<()> { Ok(()) }
+}
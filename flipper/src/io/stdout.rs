@@ -0,0 +1,27 @@
+use core::fmt;
+use crate::ffi::{furi_thread_stdout_write, furi_thread_stdout_flush};
+use super::{Error, Result, Write};
+
+
+pub fn stdout() -> Stdout { Stdout }
+
+
+/// Stdout of the current thread.
+pub struct Stdout;
+
+impl Write for Stdout {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> { Ok(unsafe { furi_thread_stdout_write(buf.as_ptr() as _, buf.len()) }) }
+
+	fn flush(&mut self) -> Result<()> {
+		unsafe {
+			if furi_thread_stdout_flush() != 0 {
+				return Err(Error::Other);
+			}
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Write for Stdout {
+	fn write_str(&mut self, s: &str) -> fmt::Result { self.write_all(s.as_bytes()).map_err(|_| fmt::Error) }
+}
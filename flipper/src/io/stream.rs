@@ -0,0 +1,277 @@
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr::NonNull;
+use sys::ffi;
+use sys::ffi::{FS_AccessMode, FS_OpenMode, StreamOffset};
+use crate::error::NullPointerError;
+use crate::path::Path;
+use crate::record::{self, Record};
+use crate::string::OsString;
+use crate::AsPtr;
+use super::{Error, Read, Result, Seek, SeekFrom, Write};
+
+
+enum Backend {
+	String,
+	File(Record<record::Storage>),
+	BufferedFile(Record<record::Storage>),
+}
+
+
+/// Firmware stream backed by a string in memory, a file or a file with buffered reading.
+///
+/// Unlike [`File`](crate::fs::File) it can insert and delete data at the current position.
+///
+/// ```ignore
+/// let mut stream = Stream::open(&path)?;
+/// let mut line = OsString::new()?;
+/// while stream.read_line(&mut line) {
+///     println!("{line:?}");
+/// }
+/// ```
+pub struct Stream {
+	raw: NonNull<ffi::Stream>,
+	backend: Backend,
+}
+
+impl Stream {
+	/// Allocates stream backed by the string in memory.
+	pub fn string() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::string_stream_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::String })
+	}
+
+	/// Allocates stream backed by a file, it must be opened before use.
+	pub fn file() -> Result<Self, NullPointerError> {
+		let storage = Record::<record::Storage>::open()?;
+		let raw = NonNull::new(unsafe { ffi::file_stream_alloc(storage.as_ptr()) }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::File(storage) })
+	}
+
+	/// Allocates stream backed by a file with buffered reading, it must be opened before use.
+	pub fn buffered_file() -> Result<Self, NullPointerError> {
+		let storage = Record::<record::Storage>::open()?;
+		let raw = NonNull::new(unsafe { ffi::buffered_file_stream_alloc(storage.as_ptr()) }).ok_or(NullPointerError)?;
+		Ok(Self { raw,
+		          backend: Backend::BufferedFile(storage) })
+	}
+
+	/// Opens existing file at `path` for buffered reading.
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let mut stream = Self::buffered_file().map_err(|_| Error::Other)?;
+		stream.open_with(path, FS_AccessMode::FSAM_READ, FS_OpenMode::FSOM_OPEN_EXISTING)?;
+		Ok(stream)
+	}
+
+	/// Opens file at `path` for reading and writing, creates it if it does not exist, and truncates it if it does.
+	pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let mut stream = Self::file().map_err(|_| Error::Other)?;
+		stream.open_with(path, FS_AccessMode::FSAM_READ_WRITE, FS_OpenMode::FSOM_CREATE_ALWAYS)?;
+		Ok(stream)
+	}
+
+
+	/// Opens the file with raw firmware modes.
+	///
+	/// Returns [`Error::InvalidInput`] if the stream isn't backed by a file.
+	pub fn open_with<P: AsRef<Path>>(&mut self, path: P, access_mode: FS_AccessMode, open_mode: FS_OpenMode) -> Result<()> {
+		let path = path.as_ref().as_ptr();
+		let opened = match self.backend {
+			Backend::File(_) => unsafe { ffi::file_stream_open(self.as_ptr(), path, access_mode, open_mode) },
+			Backend::BufferedFile(_) => unsafe { ffi::buffered_file_stream_open(self.as_ptr(), path, access_mode, open_mode) },
+			Backend::String => return Err(Error::InvalidInput),
+		};
+		self.result(opened)
+	}
+
+	/// Closes the file, it is also closed on drop.
+	pub fn close(&mut self) -> Result<()> {
+		let closed = match self.backend {
+			Backend::File(_) => unsafe { ffi::file_stream_close(self.as_ptr()) },
+			Backend::BufferedFile(_) => unsafe { ffi::buffered_file_stream_close(self.as_ptr()) },
+			Backend::String => return Err(Error::InvalidInput),
+		};
+		self.result(closed)
+	}
+
+
+	/// Size of the whole stream in bytes.
+	pub fn size(&self) -> usize { unsafe { ffi::stream_size(self.as_ptr()) } }
+
+	/// Current position from the start.
+	pub fn tell(&self) -> usize { unsafe { ffi::stream_tell(self.as_ptr()) } }
+
+	/// Checks whether the current position is at the end.
+	pub fn eof(&self) -> bool { unsafe { ffi::stream_eof(self.as_ptr()) } }
+
+	/// Removes all data.
+	pub fn clean(&mut self) { unsafe { ffi::stream_clean(self.as_ptr()) } }
+
+
+	/// Reads the next line with its line ending into `line`, replacing its content.
+	///
+	/// Both LF and CRLF line endings are supported. Returns `false` at the end of the stream.
+	pub fn read_line(&mut self, line: &mut OsString) -> bool { unsafe { ffi::stream_read_line(self.as_ptr(), line.as_ptr()) } }
+
+	/// Inserts `data` at the current position, moving the rest forward.
+	/// Position is moved after the inserted data.
+	pub fn insert(&mut self, data: &[u8]) -> Result<()> {
+		let ok = unsafe { ffi::stream_insert(self.as_ptr(), data.as_ptr(), data.len()) };
+		self.result(ok)
+	}
+
+	/// Removes `len` bytes at the current position or all bytes up to the end if there are fewer.
+	pub fn delete(&mut self, len: usize) -> Result<()> {
+		let ok = unsafe { ffi::stream_delete(self.as_ptr(), len) };
+		self.result(ok)
+	}
+
+	/// Replaces `len` bytes at the current position with `data`.
+	pub fn delete_and_insert(&mut self, len: usize, data: &[u8]) -> Result<()> {
+		unsafe extern "C" fn write(stream: *mut ffi::Stream, context: *const c_void) -> bool {
+			let data = &*(context as *const &[u8]);
+			ffi::stream_write(stream, data.as_ptr(), data.len()) == data.len()
+		}
+
+		let ok = unsafe { ffi::stream_delete_and_insert(self.as_ptr(), len, Some(write), &data as *const &[u8] as _) };
+		self.result(ok)
+	}
+
+
+	/// Converts result of the firmware call to the error of the underlying file if any.
+	fn result(&self, ok: bool) -> Result<()> {
+		if !ok {
+			self.last_error()?;
+			return Err(Error::Other);
+		}
+		Ok(())
+	}
+
+	fn last_error(&self) -> Result<()> {
+		let status = match self.backend {
+			Backend::File(_) => unsafe { ffi::file_stream_get_error(self.as_ptr()) },
+			Backend::BufferedFile(_) => unsafe { ffi::buffered_file_stream_get_error(self.as_ptr()) },
+			Backend::String => return Ok(()),
+		};
+		status?;
+		Ok(())
+	}
+}
+
+impl const AsPtr<ffi::Stream> for Stream {
+	#[inline]
+	fn as_ptr(&self) -> *mut ffi::Stream { self.raw.as_ptr() }
+}
+
+impl Read for Stream {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		let read = unsafe { ffi::stream_read(self.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+		if read < buf.len() {
+			self.last_error()?;
+		}
+		Ok(read)
+	}
+}
+
+impl Write for Stream {
+	/// Overwrites data at the current position, the stream grows if needed.
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		let written = unsafe { ffi::stream_write(self.as_ptr(), buf.as_ptr(), buf.len()) };
+		if written < buf.len() {
+			self.last_error()?;
+		}
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> Result<()> {
+		match self.backend {
+			Backend::BufferedFile(_) => {
+				let ok = unsafe { ffi::buffered_file_stream_sync(self.as_ptr()) };
+				self.result(ok)
+			},
+			_ => Ok(()),
+		}
+	}
+}
+
+impl Seek for Stream {
+	fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+		let (offset, from) = match pos {
+			SeekFrom::Start(offset) => (i32::try_from(offset).ok(), StreamOffset::StreamOffsetFromStart),
+			SeekFrom::End(offset) => (i32::try_from(offset).ok(), StreamOffset::StreamOffsetFromEnd),
+			SeekFrom::Current(offset) => (i32::try_from(offset).ok(), StreamOffset::StreamOffsetFromCurrent),
+		};
+		let offset = offset.ok_or(Error::InvalidInput)?;
+
+		if !unsafe { ffi::stream_seek(self.as_ptr(), offset, from) } {
+			self.last_error()?;
+			return Err(Error::InvalidInput);
+		}
+		Ok(self.tell() as u64)
+	}
+}
+
+impl fmt::Write for Stream {
+	fn write_str(&mut self, s: &str) -> fmt::Result { self.write_all(s.as_bytes()).map_err(|_| fmt::Error) }
+}
+
+/// Closes the file if it is opened and frees the stream.
+impl Drop for Stream {
+	fn drop(&mut self) {
+		let _ = self.close();
+		unsafe { ffi::stream_free(self.as_ptr()) }
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use crate::path::PathBuf;
+
+	#[test]
+	fn string() {
+		let mut stream = Stream::string().unwrap();
+		stream.write_all(b"first\nthird\n").unwrap();
+		stream.seek(SeekFrom::Start(6)).unwrap();
+		stream.insert(b"second\n").unwrap();
+		assert_eq!(13, stream.tell());
+		stream.delete_and_insert(5, b"THIRD").unwrap();
+		assert_eq!(19, stream.size());
+
+		stream.rewind().unwrap();
+		let mut line = OsString::new().unwrap();
+		assert!(stream.read_line(&mut line));
+		assert_eq!(b"first\n", line.as_c_str().to_bytes());
+		stream.delete(7).unwrap();
+		assert!(stream.read_line(&mut line));
+		assert_eq!(b"THIRD\n", line.as_c_str().to_bytes());
+		assert!(!stream.read_line(&mut line));
+		assert!(stream.eof());
+
+		let mut buf = [0; 4];
+		stream.seek(SeekFrom::End(-6)).unwrap();
+		stream.read_exact(&mut buf).unwrap();
+		assert_eq!(b"THIR", &buf);
+		assert!(matches!(stream.seek(SeekFrom::Start(100)), Err(Error::InvalidInput)));
+		assert!(matches!(stream.close(), Err(Error::InvalidInput)));
+	}
+
+	#[test]
+	fn file() {
+		let path = Path::ext().join(PathBuf::from("stream_test.txt"));
+		let mut stream = Stream::create(&path).unwrap();
+		writeln!(stream, "key: {}", 42).unwrap();
+		stream.close().unwrap();
+
+		let mut stream = Stream::open(&path).unwrap();
+		let mut content = crate::alloc::string::String::new();
+		stream.read_to_string(&mut content).unwrap();
+		assert_eq!("key: 42\n", content);
+
+		let missing = Stream::open(Path::ext().join(PathBuf::from("stream_missing.txt")));
+		assert!(matches!(missing, Err(Error::Fs(crate::fs::Error::NotExist))));
+	}
+}
@@ -0,0 +1,183 @@
+use core::ffi::c_void;
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::alloc::sync::Arc;
+use crate::error::NullPointerError;
+use crate::time::{ticks, WAIT_FOREVER};
+use super::{Read, Result, Write};
+
+
+/// Byte pipe over `FuriStreamBuffer` for a single writer and a single reader,
+/// e.g. to feed UART or radio data from ISR to a worker thread.
+///
+/// Blocked reader is woken when there are at least [trigger level](StreamBuffer::set_trigger_level) bytes.
+/// Non-blocking [`try_send`](StreamBuffer::try_send) and [`try_receive`](StreamBuffer::try_receive) can be called from ISR.
+///
+/// ```ignore
+/// let (mut tx, mut rx) = StreamBuffer::new(256, 1)?.split();
+/// let worker = thread::spawn(move || {
+///     let mut buf = [0; 64];
+///     let len = rx.receive(&mut buf);
+///     process(&buf[..len]);
+/// });
+/// // in the ISR:
+/// tx.try_send(&[byte]);
+/// ```
+pub struct StreamBuffer(Raw);
+
+impl StreamBuffer {
+	/// Allocates buffer of `size` bytes.
+	pub fn new(size: usize, trigger_level: usize) -> Result<Self, NullPointerError> {
+		let raw = unsafe { ffi::furi_stream_buffer_alloc(size, trigger_level) };
+		NonNull::new(raw).map(|raw| Self(Raw(raw))).ok_or(NullPointerError)
+	}
+
+	/// Splits into writing and reading halves which can be moved to different threads or ISR context.
+	pub fn split(self) -> (StreamWriter, StreamReader) {
+		let raw = Arc::new(self.0);
+		(StreamWriter(raw.clone()), StreamReader(raw))
+	}
+
+	/// Discards all data, fails if there is a thread blocked on sending or receiving.
+	pub fn reset(&mut self) -> crate::sync::Result<()> {
+		unsafe { ffi::furi_stream_buffer_reset(self.0.as_ptr()) }?;
+		Ok(())
+	}
+}
+
+
+/// Writing half of the [`StreamBuffer`].
+pub struct StreamWriter(Arc<Raw>);
+
+/// Reading half of the [`StreamBuffer`].
+pub struct StreamReader(Arc<Raw>);
+
+
+macro_rules! writer {
+	($t:ty) => {
+		impl $t {
+			/// Sends bytes, waits for a free space, returns number of sent bytes.
+			pub fn send(&mut self, data: &[u8]) -> usize { self.0.send(data, WAIT_FOREVER) }
+
+			/// Sends bytes, waits for a free space up to `timeout`, returns number of sent bytes.
+			pub fn send_timeout(&mut self, data: &[u8], timeout: Duration) -> usize { self.0.send(data, ticks(timeout)) }
+
+			/// Sends as many bytes as fit without waiting, returns number of sent bytes.
+			///
+			/// Can be called from ISR.
+			pub fn try_send(&mut self, data: &[u8]) -> usize { self.0.send(data, 0) }
+
+			/// Number of bytes that can be sent without waiting.
+			pub fn space(&self) -> usize { unsafe { ffi::furi_stream_buffer_spaces_available(self.0.as_ptr()) } }
+
+			pub fn is_full(&self) -> bool { unsafe { ffi::furi_stream_buffer_is_full(self.0.as_ptr()) } }
+		}
+
+		impl Write for $t {
+			/// Sends bytes, waits for a free space.
+			fn write(&mut self, buf: &[u8]) -> Result<usize> { Ok(self.send(buf)) }
+
+			fn flush(&mut self) -> Result<()> { Ok(()) }
+		}
+	};
+}
+
+macro_rules! reader {
+	($t:ty) => {
+		impl $t {
+			/// Receives bytes into `buf`, returns number of received bytes.
+			///
+			/// If the buffer is empty, waits until there are at least trigger level bytes.
+			pub fn receive(&mut self, buf: &mut [u8]) -> usize { self.0.receive(buf, WAIT_FOREVER) }
+
+			/// Same as [`receive`](Self::receive), but waits up to `timeout`, returns zero if nothing is received.
+			pub fn receive_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> usize { self.0.receive(buf, ticks(timeout)) }
+
+			/// Receives available bytes without waiting.
+			///
+			/// Can be called from ISR.
+			pub fn try_receive(&mut self, buf: &mut [u8]) -> usize { self.0.receive(buf, 0) }
+
+			/// Sets number of bytes needed to wake up blocked reader,
+			/// returns `false` if it is greater than the size of the buffer.
+			pub fn set_trigger_level(&mut self, trigger_level: usize) -> bool {
+				unsafe { ffi::furi_stream_set_trigger_level(self.0.as_ptr(), trigger_level) }
+			}
+
+			/// Number of bytes available to receive.
+			pub fn len(&self) -> usize { unsafe { ffi::furi_stream_buffer_bytes_available(self.0.as_ptr()) } }
+
+			pub fn is_empty(&self) -> bool { unsafe { ffi::furi_stream_buffer_is_empty(self.0.as_ptr()) } }
+		}
+
+		impl Read for $t {
+			/// Receives bytes, waits for trigger level bytes if the buffer is empty.
+			fn read(&mut self, buf: &mut [u8]) -> Result<usize> { Ok(self.receive(buf)) }
+		}
+	};
+}
+
+writer!(StreamBuffer);
+writer!(StreamWriter);
+reader!(StreamBuffer);
+reader!(StreamReader);
+
+
+/// Owned `FuriStreamBuffer`.
+struct Raw(NonNull<c_void>);
+
+impl Raw {
+	fn as_ptr(&self) -> *mut ffi::FuriStreamBuffer { self.0.as_ptr() }
+
+	fn send(&self, data: &[u8], timeout: u32) -> usize {
+		unsafe { ffi::furi_stream_buffer_send(self.as_ptr(), data.as_ptr() as _, data.len(), timeout) }
+	}
+
+	fn receive(&self, buf: &mut [u8], timeout: u32) -> usize {
+		unsafe { ffi::furi_stream_buffer_receive(self.as_ptr(), buf.as_mut_ptr() as _, buf.len(), timeout) }
+	}
+}
+
+impl Drop for Raw {
+	fn drop(&mut self) { unsafe { ffi::furi_stream_buffer_free(self.as_ptr()) } }
+}
+
+// Sending and receiving require `&mut` of the single writer and the single reader.
+unsafe impl Send for Raw {}
+unsafe impl Sync for Raw {}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use crate::thread;
+
+	#[test]
+	fn split() {
+		let (mut tx, mut rx) = StreamBuffer::new(8, 1).unwrap().split();
+		assert_eq!(8, tx.space());
+
+		let writer = thread::spawn(move || {
+			tx.write_all(b"hello world").unwrap();
+			tx
+		});
+		let mut buf = [0; 16];
+		let mut received = crate::alloc::vec::Vec::new();
+		while received.len() < 11 {
+			let len = rx.receive(&mut buf);
+			received.extend_from_slice(&buf[..len]);
+		}
+		assert_eq!(b"hello world", &received[..]);
+
+		let mut tx = writer.join();
+		assert!(rx.is_empty());
+		assert_eq!(0, rx.receive_timeout(&mut buf, Duration::from_millis(5)));
+		assert_eq!(8, tx.try_send(b"0123456789"));
+		assert!(tx.is_full());
+		assert_eq!(0, tx.send_timeout(b"x", Duration::from_millis(5)));
+		assert_eq!(4, rx.try_receive(&mut buf[..4]));
+		assert_eq!(4, rx.len());
+		assert!(!rx.set_trigger_level(9));
+	}
+}
@@ -22,8 +22,20 @@ pub mod macros;
 pub mod io;
 pub mod fs;
 pub mod path;
+pub mod gui;
+pub mod input;
+pub mod pubsub;
+pub mod record;
+pub mod notification;
+pub mod cli;
+pub mod dialogs;
+pub mod flipper_format;
+pub mod saved_struct;
 pub mod string;
 pub mod peripheral;
+pub mod thread;
+pub mod sync;
+pub mod time;
 
 
 pub mod ffi {
@@ -40,10 +52,109 @@ trait AsPtr<T> {
 
 
 pub mod error {
+	use core::fmt;
+
+
 	#[derive(Debug)]
 	pub struct NullPointerError;
 	impl core::error::Error for NullPointerError {}
-	impl core::fmt::Display for NullPointerError {
-		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { write!(f, "NullPointerError") }
+	impl fmt::Display for NullPointerError {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "NullPointerError") }
+	}
+
+
+	pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+
+	/// Any error of the firmware API, so different calls can be chained with `?`.
+	///
+	/// ```ignore
+	/// fn run() -> flipper0::error::Result<()> {
+	///     let storage = Record::<record::Storage>::open()?;
+	///     unsafe { ffi::storage_common_mkdir(storage.as_ptr(), path.as_ptr()) }?;
+	///     unsafe { ffi::furi_mutex_acquire(mutex, WAIT_FOREVER) }?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[derive(Debug)]
+	pub enum Error {
+		Fs(sys::error::fs::Error),
+		Furi(sys::error::furi::Error),
+		Gpio(sys::error::gpio::Error),
+		NullPointer(NullPointerError),
+	}
+
+	impl core::error::Error for Error {
+		fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+			match self {
+				Error::Fs(err) => Some(err),
+				Error::Furi(err) => Some(err),
+				Error::Gpio(err) => Some(err),
+				Error::NullPointer(err) => Some(err),
+			}
+		}
+	}
+
+	/// Only the kind of the error, the inner error is the [`source`](core::error::Error::source).
+	impl fmt::Display for Error {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			match self {
+				Error::Fs(_) => write!(f, "storage error"),
+				Error::Furi(_) => write!(f, "furi error"),
+				Error::Gpio(_) => write!(f, "gpio error"),
+				Error::NullPointer(_) => write!(f, "null pointer error"),
+			}
+		}
+	}
+
+	impl From<sys::error::fs::Error> for Error {
+		fn from(err: sys::error::fs::Error) -> Self { Error::Fs(err) }
+	}
+
+	impl From<sys::error::furi::Error> for Error {
+		fn from(err: sys::error::furi::Error) -> Self { Error::Furi(err) }
+	}
+
+	impl From<sys::error::gpio::Error> for Error {
+		fn from(err: sys::error::gpio::Error) -> Self { Error::Gpio(err) }
+	}
+
+	impl From<NullPointerError> for Error {
+		fn from(err: NullPointerError) -> Self { Error::NullPointer(err) }
+	}
+
+
+	#[cfg(all(test, feature = "mock"))]
+	mod tests {
+		use super::*;
+		use sys::alloc::string::ToString;
+		use sys::ffi::{FS_Error, FuriStatus};
+
+		fn chain(fs: FS_Error, furi: FuriStatus) -> Result<()> {
+			fs?;
+			furi?;
+			Err(NullPointerError)?
+		}
+
+		#[test]
+		fn chain_and_display() {
+			use core::error::Error as _;
+
+			let err = chain(FS_Error::FSE_NOT_EXIST, FuriStatus::FuriStatusOk).unwrap_err();
+			assert_eq!("storage error", err.to_string());
+			assert_eq!("file/dir not exist", err.source().unwrap().to_string());
+			let err = chain(FS_Error::FSE_OK, FuriStatus::FuriStatusErrorTimeout).unwrap_err();
+			assert_eq!("furi error", err.to_string());
+			assert_eq!(
+			           "operation not completed within the timeout period",
+			           err.source().unwrap().to_string()
+			);
+			let err = chain(FS_Error::FSE_OK, FuriStatus::FuriStatusOk).unwrap_err();
+			assert_eq!("null pointer error", err.to_string());
+			assert_eq!("NullPointerError", err.source().unwrap().to_string());
+			let err = Error::from(sys::error::gpio::Error);
+			assert_eq!("gpio error", err.to_string());
+			assert_eq!("operation failed", err.source().unwrap().to_string());
+		}
 	}
 }
@@ -1,8 +1,7 @@
 #[macro_export]
 macro_rules! print {
 	($($arg:tt)*) => {{
-		use core::fmt::Write;
-		core::write!($crate::io::Stdout, "{}", format_args!($($arg)*)).unwrap()
+		core::fmt::Write::write_fmt(&mut $crate::io::Stdout, format_args!($($arg)*)).unwrap()
 	}};
 }
 
@@ -16,3 +15,15 @@ macro_rules! println {
 		$crate::print!("{}\n", format_args!($($arg)*));
 	}};
 }
+
+/// Creates [`OsString`](crate::string::OsString) like the `format!`, writing straight into the `FuriString`.
+///
+/// Panics if the string can't be allocated.
+#[macro_export]
+macro_rules! os_format {
+	($($arg:tt)*) => {{
+		let mut s = $crate::string::OsString::new().expect("NullPointer");
+		core::fmt::Write::write_fmt(&mut s, format_args!($($arg)*)).unwrap();
+		s
+	}};
+}
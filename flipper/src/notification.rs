@@ -0,0 +1,369 @@
+//! Notifications: LED, vibro, speaker and display backlight sequences.
+//!
+//! ```ignore
+//! notification_sequence! {
+//!     static BEEP = [Message::red(255), Message::note(523.25), Message::delay(100), Message::sound_off()];
+//! }
+//!
+//! let notifications = Notifications::open()?;
+//! notifications.notify(&BEEP);
+//! notifications.notify_blocking(&Stock::Success);
+//! ```
+
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::record::{self, Record};
+
+
+/// Declares statics of [`Sequence`] from the list of [`Message`]s.
+#[macro_export]
+macro_rules! notification_sequence {
+	($($(#[$meta:meta])* $vis:vis static $name:ident = [$($message:expr),* $(,)?];)*) => {
+		$(
+			$(#[$meta])*
+			$vis static $name: $crate::notification::Sequence<{ <[()]>::len(&[$($crate::notification_sequence!(@unit $message)),*]) + 1 }> =
+				$crate::notification::Sequence::new(&[$($message),*]);
+		)*
+	};
+	(@unit $message:expr) => { () };
+}
+
+
+/// Opened notification service.
+pub struct Notifications(Record<record::Notification>);
+
+impl Notifications {
+	pub fn open() -> Result<Self, NullPointerError> { Record::<record::Notification>::open().map(Self) }
+
+	/// Starts the sequence, returns immediately.
+	///
+	/// The firmware plays it after the return, so it must be `'static`,
+	/// local sequences can only be played by [`notify_blocking`](Notifications::notify_blocking):
+	///
+	/// ```compile_fail
+	/// # use flipper0::notification::*;
+	/// static MESSAGES: [Message; 1] = [Message::delay(100)];
+	///
+	/// fn play(notifications: &Notifications) {
+	///     let local = Sequence::<2>::new(&MESSAGES);
+	///     notifications.notify(&local);
+	/// }
+	/// ```
+	pub fn notify<S: AsSequence + ?Sized>(&self, sequence: &'static S) {
+		unsafe { ffi::notification_message(self.as_ptr(), sequence.as_sequence_ptr()) }
+	}
+
+	/// Starts the sequence and waits for its end, so the sequence can be local.
+	pub fn notify_blocking<S: AsSequence + ?Sized>(&self, sequence: &S) {
+		unsafe { ffi::notification_message_block(self.as_ptr(), sequence.as_sequence_ptr()) }
+	}
+
+	/// Applies the sequence to the permanent layer, which is restored after other notifications.
+	pub fn notify_internal<S: AsSequence + ?Sized>(&self, sequence: &'static S) {
+		unsafe { ffi::notification_internal_message(self.as_ptr(), sequence.as_sequence_ptr()) }
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::NotificationApp { self.0.as_ptr() }
+}
+
+unsafe impl Sync for Notifications {}
+
+
+/// Null-terminated `NotificationSequence`.
+pub trait AsSequence {
+	/// Pointer must stay valid while the sequence is played, so it is `'static` for non-blocking calls.
+	fn as_sequence_ptr(&self) -> *const ffi::NotificationSequence;
+}
+
+
+/// `NotificationSequence` of `N - 1` messages, see [`notification_sequence!`].
+///
+/// Only statics can be played without blocking, because the firmware reads messages in its own thread.
+#[repr(transparent)]
+pub struct Sequence<const N: usize>([*const Message; N]);
+
+impl<const N: usize> Sequence<N> {
+	/// # Panics
+	/// At compile time, if `N` is not `messages.len() + 1`.
+	pub const fn new(messages: &'static [Message]) -> Self {
+		assert!(messages.len() + 1 == N, "sequence length must be number of messages plus one");
+		let mut pointers = [core::ptr::null(); N];
+		let mut i = 0;
+		while i < messages.len() {
+			pointers[i] = &messages[i] as *const Message;
+			i += 1;
+		}
+		Self(pointers)
+	}
+}
+
+impl<const N: usize> AsSequence for Sequence<N> {
+	fn as_sequence_ptr(&self) -> *const ffi::NotificationSequence { self.0.as_ptr() as _ }
+}
+
+unsafe impl<const N: usize> Sync for Sequence<N> {}
+unsafe impl<const N: usize> Send for Sequence<N> {}
+
+
+/// `NotificationMessage` which can be created in const context.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Message {
+	kind: MessageType,
+	data: MessageData,
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageType {
+	Vibro = 0,
+	SoundOn = 1,
+	SoundOff = 2,
+	LedRed = 3,
+	LedGreen = 4,
+	LedBlue = 5,
+	LedBlinkStart = 6,
+	LedBlinkStop = 7,
+	LedBlinkColor = 8,
+	Delay = 9,
+	LedDisplayBacklight = 10,
+	LedDisplayBacklightEnforceOn = 11,
+	LedDisplayBacklightEnforceAuto = 12,
+	DoNotReset = 13,
+	ForceSpeakerVolumeSetting = 14,
+	ForceVibroSetting = 15,
+	ForceDisplayBrightnessSetting = 16,
+}
+
+/// `NotificationMessageData`
+#[repr(C)]
+#[derive(Clone, Copy)]
+union MessageData {
+	sound: Sound,
+	led: u8,
+	led_blink: LedBlink,
+	vibro: bool,
+	delay: u32,
+	forced_settings: ForcedSettings,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Sound {
+	frequency: f32,
+	volume: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LedBlink {
+	on_time: u16,
+	period: u16,
+	color: Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ForcedSettings {
+	speaker_volume: f32,
+	vibro: bool,
+	display_brightness: f32,
+}
+
+impl Message {
+	const fn new(kind: MessageType, data: MessageData) -> Self { Self { kind, data } }
+
+	pub const fn vibro(on: bool) -> Self { Self::new(MessageType::Vibro, MessageData { vibro: on }) }
+
+	/// Starts sound of `frequency` in Hz with `volume` from `0.0` to `1.0`.
+	pub const fn sound(frequency: f32, volume: f32) -> Self {
+		Self::new(MessageType::SoundOn, MessageData { sound: Sound { frequency, volume } })
+	}
+
+	/// Starts sound of `frequency` with full volume, like stock `message_note_*`.
+	pub const fn note(frequency: f32) -> Self { Self::sound(frequency, 1.0) }
+
+	pub const fn sound_off() -> Self { Self::new(MessageType::SoundOff, MessageData { led: 0 }) }
+
+	pub const fn red(value: u8) -> Self { Self::new(MessageType::LedRed, MessageData { led: value }) }
+
+	pub const fn green(value: u8) -> Self { Self::new(MessageType::LedGreen, MessageData { led: value }) }
+
+	pub const fn blue(value: u8) -> Self { Self::new(MessageType::LedBlue, MessageData { led: value }) }
+
+	/// Starts blinking with `color`, LED is on for `on_time` of each `period` in milliseconds.
+	pub const fn blink_start(on_time: u16, period: u16, color: Color) -> Self {
+		let led_blink = LedBlink { on_time, period, color };
+		Self::new(MessageType::LedBlinkStart, MessageData { led_blink })
+	}
+
+	pub const fn blink_stop() -> Self { Self::new(MessageType::LedBlinkStop, MessageData { led: 0 }) }
+
+	/// Changes color of the current blinking.
+	pub const fn blink_color(color: Color) -> Self {
+		let led_blink = LedBlink { on_time: 0,
+		                           period: 0,
+		                           color };
+		Self::new(MessageType::LedBlinkColor, MessageData { led_blink })
+	}
+
+	/// Pause of the sequence in milliseconds.
+	pub const fn delay(ms: u32) -> Self { Self::new(MessageType::Delay, MessageData { delay: ms }) }
+
+	/// Display backlight brightness, `0` turns it off.
+	pub const fn display_backlight(value: u8) -> Self { Self::new(MessageType::LedDisplayBacklight, MessageData { led: value }) }
+
+	/// Keeps display backlight on until [`display_backlight_enforce_auto`](Message::display_backlight_enforce_auto).
+	pub const fn display_backlight_enforce_on() -> Self {
+		Self::new(MessageType::LedDisplayBacklightEnforceOn, MessageData { led: 0xFF })
+	}
+
+	pub const fn display_backlight_enforce_auto() -> Self {
+		Self::new(MessageType::LedDisplayBacklightEnforceAuto, MessageData { led: 0 })
+	}
+
+	/// Keeps state of LEDs after the end of the sequence.
+	pub const fn do_not_reset() -> Self { Self::new(MessageType::DoNotReset, MessageData { led: 0 }) }
+
+	/// Overrides user's volume setting for the rest of the sequence.
+	pub const fn force_speaker_volume(volume: f32) -> Self {
+		Self::new(MessageType::ForceSpeakerVolumeSetting, Self::forced(volume, false, 0.0))
+	}
+
+	/// Overrides user's vibro setting for the rest of the sequence.
+	pub const fn force_vibro(on: bool) -> Self { Self::new(MessageType::ForceVibroSetting, Self::forced(0.0, on, 0.0)) }
+
+	/// Overrides user's display brightness setting for the rest of the sequence.
+	pub const fn force_display_brightness(brightness: f32) -> Self {
+		Self::new(
+		          MessageType::ForceDisplayBrightnessSetting,
+		          Self::forced(0.0, false, brightness),
+		)
+	}
+
+	const fn forced(speaker_volume: f32, vibro: bool, display_brightness: f32) -> MessageData {
+		MessageData { forced_settings: ForcedSettings { speaker_volume,
+		                                                vibro,
+		                                                display_brightness } }
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *const ffi::NotificationMessage { self as *const Self as _ }
+}
+
+
+/// Color of the RGB LED, mix of `Light` bits.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(u8);
+
+impl Color {
+	pub const RED: Self = Self(1 << 0);
+	pub const GREEN: Self = Self(1 << 1);
+	pub const BLUE: Self = Self(1 << 2);
+	pub const CYAN: Self = Self(Self::GREEN.0 | Self::BLUE.0);
+	pub const MAGENTA: Self = Self(Self::RED.0 | Self::BLUE.0);
+	pub const YELLOW: Self = Self(Self::RED.0 | Self::GREEN.0);
+	pub const WHITE: Self = Self(Self::RED.0 | Self::GREEN.0 | Self::BLUE.0);
+}
+
+
+macro_rules! stock {
+	($($variant:ident => $sequence:ident,)*) => {
+		/// Stock sequences of the firmware.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum Stock {
+			$($variant,)*
+		}
+
+		impl AsSequence for Stock {
+			fn as_sequence_ptr(&self) -> *const ffi::NotificationSequence {
+				unsafe {
+					match self {
+						$(Stock::$variant => &ffi::$sequence,)*
+					}
+				}
+			}
+		}
+	};
+}
+
+stock! {
+	ResetRed => sequence_reset_red,
+	ResetGreen => sequence_reset_green,
+	ResetBlue => sequence_reset_blue,
+	ResetRgb => sequence_reset_rgb,
+	ResetDisplay => sequence_reset_display,
+	ResetSound => sequence_reset_sound,
+	ResetVibro => sequence_reset_vibro,
+	SetVibroOn => sequence_set_vibro_on,
+	DisplayBacklightOn => sequence_display_backlight_on,
+	DisplayBacklightOff => sequence_display_backlight_off,
+	DisplayBacklightOffDelay1000 => sequence_display_backlight_off_delay_1000,
+	DisplayBacklightEnforceOn => sequence_display_backlight_enforce_on,
+	DisplayBacklightEnforceAuto => sequence_display_backlight_enforce_auto,
+	Charging => sequence_charging,
+	Charged => sequence_charged,
+	NotCharging => sequence_not_charging,
+	SetOnlyRed255 => sequence_set_only_red_255,
+	SetOnlyGreen255 => sequence_set_only_green_255,
+	SetOnlyBlue255 => sequence_set_only_blue_255,
+	SetRed255 => sequence_set_red_255,
+	SetGreen255 => sequence_set_green_255,
+	SetBlue255 => sequence_set_blue_255,
+	SolidYellow => sequence_solid_yellow,
+	BlinkBlue10 => sequence_blink_blue_10,
+	BlinkRed10 => sequence_blink_red_10,
+	BlinkGreen10 => sequence_blink_green_10,
+	BlinkYellow10 => sequence_blink_yellow_10,
+	BlinkCyan10 => sequence_blink_cyan_10,
+	BlinkMagenta10 => sequence_blink_magenta_10,
+	BlinkRed100 => sequence_blink_red_100,
+	BlinkGreen100 => sequence_blink_green_100,
+	BlinkBlue100 => sequence_blink_blue_100,
+	BlinkYellow100 => sequence_blink_yellow_100,
+	BlinkCyan100 => sequence_blink_cyan_100,
+	BlinkMagenta100 => sequence_blink_magenta_100,
+	BlinkWhite100 => sequence_blink_white_100,
+	BlinkStartBlue => sequence_blink_start_blue,
+	BlinkStartRed => sequence_blink_start_red,
+	BlinkStartGreen => sequence_blink_start_green,
+	BlinkStartYellow => sequence_blink_start_yellow,
+	BlinkStartCyan => sequence_blink_start_cyan,
+	BlinkStartMagenta => sequence_blink_start_magenta,
+	BlinkStop => sequence_blink_stop,
+	SingleVibro => sequence_single_vibro,
+	DoubleVibro => sequence_double_vibro,
+	Success => sequence_success,
+	Error => sequence_error,
+	AudiovisualAlert => sequence_audiovisual_alert,
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	notification_sequence! {
+		static BEEP = [Message::red(255), Message::note(523.25), Message::delay(100), Message::sound_off()];
+	}
+
+	#[test]
+	fn sequence_layout() {
+		assert_eq!(
+		           core::mem::size_of::<ffi::NotificationMessage>(),
+		           core::mem::size_of::<Message>()
+		);
+		assert_eq!(
+		           core::mem::align_of::<ffi::NotificationMessage>(),
+		           core::mem::align_of::<Message>()
+		);
+
+		assert_eq!(5, BEEP.0.len());
+		assert!(BEEP.0[4].is_null());
+		let delay = unsafe { &*(BEEP.0[2] as *const ffi::NotificationMessage) };
+		assert_eq!(ffi::NotificationMessageType::NotificationMessageTypeDelay, delay.type_);
+		assert_eq!(100, unsafe { delay.data.delay.as_ref().length });
+	}
+}
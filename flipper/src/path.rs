@@ -1,4 +1,449 @@
-/// Temporarily aliased CString -> PathBuf
-pub type PathBuf = sys::alloc::ffi::CString;
-pub type Path = core::ffi::CStr;
-// TODO: impl PathBuf with `ffi::path_append` and etc..
+//! Owned and borrowed paths for the storage API.
+//!
+//! [`Path`] is a borrowed null-terminated path string, [`PathBuf`] is an owned one based on the [`OsString`].
+//! Both of them are looks like same things in the std,
+//! but they are works with firmware's path API (`path_append`, `path_extract_dirname`, etc..)
+//! and so there are some differences:
+//!
+//! - [`PathBuf`] is always normalized - trailing slashes are removed
+//! - [`PathBuf`] created from `&str` or `String` ends at the first nul
+//! - [`Path::join`] and [`PathBuf::push`] always append the suffix, even if it starts with slash
+//! - [`Path::parent`] returns owned [`PathBuf`]
+
+use core::fmt;
+use core::ops::Deref;
+use core::hash::Hash;
+use core::borrow::Borrow;
+use core::ffi::{CStr, c_char, FromBytesWithNulError};
+use core::str::Utf8Error;
+use sys::ffi;
+use crate::alloc::borrow::{Cow, ToOwned};
+use crate::alloc::ffi::CString;
+use crate::alloc::string::String;
+use crate::alloc::vec::Vec;
+use crate::string::OsString;
+use crate::AsPtr;
+
+
+/// Path delimiter.
+pub const SEPARATOR: u8 = b'/';
+
+/// Root of the applications data, `/ext/apps_data`.
+const APPS_DATA_PATH: &[u8] = b"/ext/apps_data\0";
+
+
+/// Borrowed null-terminated path.
+///
+/// This is an *unsized* type, so it must always be used behind a pointer like `&`.
+#[repr(transparent)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path(CStr);
+
+
+impl Path {
+	/// Wraps a c-string slice as a `Path` slice.
+	pub fn new<S: AsRef<CStr> + ?Sized>(s: &S) -> &Path { unsafe { &*(s.as_ref() as *const CStr as *const Path) } }
+
+	pub fn from_bytes_with_nul(bytes: &[u8]) -> Result<&Path, FromBytesWithNulError> {
+		CStr::from_bytes_with_nul(bytes).map(Path::new)
+	}
+
+	/// Unsafely creates a path from a byte slice.
+	///
+	/// # Safety
+	/// The slice must be null-terminated and must not contain any interior nul bytes.
+	pub unsafe fn from_bytes_with_nul_unchecked(bytes: &[u8]) -> &Path { Path::new(CStr::from_bytes_with_nul_unchecked(bytes)) }
+
+
+	/// Root of the external storage (SD card), `/ext`.
+	pub fn ext() -> &'static Path { unsafe { Path::from_bytes_with_nul_unchecked(ffi::STORAGE_EXT_PATH_PREFIX) } }
+
+	/// Root of the internal storage, `/int`.
+	pub fn int() -> &'static Path { unsafe { Path::from_bytes_with_nul_unchecked(ffi::STORAGE_INT_PATH_PREFIX) } }
+
+	/// Root of any available storage, `/any`.
+	pub fn any() -> &'static Path { unsafe { Path::from_bytes_with_nul_unchecked(ffi::STORAGE_ANY_PATH_PREFIX) } }
+
+	/// Root of the applications data, `/ext/apps_data`.
+	///
+	/// See also [`PathBuf::app_data`].
+	pub fn apps_data() -> &'static Path { unsafe { Path::from_bytes_with_nul_unchecked(APPS_DATA_PATH) } }
+
+
+	#[inline]
+	pub fn as_c_str(&self) -> &CStr { &self.0 }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *const c_char { self.0.as_ptr() }
+
+	/// Bytes without terminating null.
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] { self.0.to_bytes() }
+
+	pub fn to_str(&self) -> Result<&str, Utf8Error> { self.0.to_str() }
+
+	pub fn to_string_lossy(&self) -> Cow<'_, str> { self.0.to_string_lossy() }
+
+	pub fn to_path_buf(&self) -> PathBuf { PathBuf::from(self) }
+
+
+	pub fn is_empty(&self) -> bool { self.as_bytes().is_empty() }
+
+	pub fn is_absolute(&self) -> bool { self.as_bytes().first() == Some(&SEPARATOR) }
+
+	pub fn has_trailing_slash(&self) -> bool { self.as_bytes().len() > 1 && self.as_bytes().ends_with(&[SEPARATOR]) }
+
+	/// Returns the path without trailing slashes.
+	///
+	/// Borrows if there is nothing to remove.
+	pub fn normalize(&self) -> Cow<'_, Path> {
+		if self.has_trailing_slash() {
+			Cow::Owned(self.to_path_buf())
+		} else {
+			Cow::Borrowed(self)
+		}
+	}
+
+
+	/// Returns the final component of the path.
+	///
+	/// Returns `None` if the path is empty or ends with a slash.
+	pub fn file_name(&self) -> Option<&CStr> {
+		let bytes = self.0.to_bytes_with_nul();
+		let start = bytes.iter().rposition(|b| *b == SEPARATOR).map_or(0, |i| i + 1);
+		if start + 1 >= bytes.len() {
+			None
+		} else {
+			Some(unsafe { CStr::from_bytes_with_nul_unchecked(&bytes[start..]) })
+		}
+	}
+
+	/// Returns extension of the [`file_name`](Path::file_name) without leading dot.
+	///
+	/// Returns `None` if there is no file name, no dot or the only dot is the first char of the file name.
+	pub fn extension(&self) -> Option<&CStr> {
+		let name = self.file_name()?.to_bytes_with_nul();
+		match name.iter().rposition(|b| *b == b'.') {
+			Some(0) | None => None,
+			Some(i) => Some(unsafe { CStr::from_bytes_with_nul_unchecked(&name[i + 1..]) }),
+		}
+	}
+
+	/// Returns the path without its final component.
+	///
+	/// Returns `None` if the path has no parent such as `/ext`, `file.txt` or empty.
+	pub fn parent(&self) -> Option<PathBuf> {
+		let bytes = trim_trailing_slashes(self.as_bytes());
+		match bytes.iter().rposition(|b| *b == SEPARATOR) {
+			Some(0) | None => None,
+			Some(_) => {
+				let result = OsString::new().ok()?;
+				unsafe { ffi::path_extract_dirname(self.as_ptr(), result.as_ptr()) };
+				Some(PathBuf(result))
+			},
+		}
+	}
+
+	/// Creates an owned [`PathBuf`] with `path` adjoined to `self`.
+	///
+	/// Unlike std, `path` is always appended, even if it is absolute.
+	/// Result is normalized, empty `path` gives normalized `self`.
+	pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+		let mut result = PathBuf::from(self);
+		result.push(path);
+		result
+	}
+
+	/// Returns `true` if the final component of the path contains only printable ascii characters
+	/// and no characters forbidden in FAT file names.
+	pub fn contains_only_ascii(&self) -> bool { unsafe { ffi::path_contains_only_ascii(self.as_ptr()) } }
+
+
+	fn trim_leading_slashes(&self) -> &CStr {
+		let bytes = self.0.to_bytes_with_nul();
+		let start = bytes.iter().position(|b| *b != SEPARATOR).unwrap_or(bytes.len() - 1);
+		unsafe { CStr::from_bytes_with_nul_unchecked(&bytes[start..]) }
+	}
+}
+
+
+/// Removes trailing slashes, but keeps the root `/`.
+fn trim_trailing_slashes(bytes: &[u8]) -> &[u8] {
+	let mut bytes = bytes;
+	while bytes.len() > 1 && bytes.ends_with(&[SEPARATOR]) {
+		bytes = &bytes[..bytes.len() - 1];
+	}
+	bytes
+}
+
+
+/// Owned normalized path.
+///
+/// Trailing slashes are removed on creation,
+/// so it can be passed to [`crate::fs::Storage::read_dir`] and [`crate::fs::Storage::walk_dir`] as is.
+#[derive(Clone)]
+pub struct PathBuf(OsString);
+
+
+impl PathBuf {
+	/// Path of the data directory for application with specified `appid`, `/ext/apps_data/{appid}`.
+	pub fn app_data<S: AsRef<str>>(appid: S) -> PathBuf { Path::apps_data().join(PathBuf::from(appid.as_ref())) }
+
+
+	#[inline]
+	pub fn as_path(&self) -> &Path { Path::new(self.0.as_c_str()) }
+
+	#[inline]
+	pub fn into_os_string(self) -> OsString { self.0 }
+
+	/// Extends `self` with `path`, adding path delimiter.
+	///
+	/// Unlike std, `path` is always appended, even if it is absolute.
+	/// Does nothing if `path` consists only of slashes or is empty.
+	pub fn push<P: AsRef<Path>>(&mut self, path: P) {
+		let suffix = path.as_ref().trim_leading_slashes();
+		if suffix.to_bytes().is_empty() {
+			return;
+		}
+		unsafe { ffi::path_append(self.0.as_ptr(), suffix.as_ptr()) }
+
+		let len = trim_trailing_slashes(self.0.as_bytes()).len();
+		self.0.left(len);
+	}
+
+	/// Truncates `self` to [`parent`](Path::parent).
+	///
+	/// Returns `false` and does nothing if there is no parent.
+	pub fn pop(&mut self) -> bool {
+		match self.parent() {
+			Some(parent) => {
+				*self = parent;
+				true
+			},
+			None => false,
+		}
+	}
+
+
+	/// Creates path from bytes, trailing slashes are removed.
+	///
+	/// Bytes after the first nul are ignored.
+	fn from_bytes(bytes: &[u8]) -> Self {
+		let bytes = bytes.split(|b| *b == 0).next().unwrap_or_default();
+		let bytes = trim_trailing_slashes(bytes);
+
+		let mut buf = Vec::with_capacity(bytes.len() + 1);
+		buf.extend_from_slice(bytes);
+		buf.push(0);
+
+		let path = unsafe { CStr::from_bytes_with_nul_unchecked(&buf) };
+		Self(OsString::try_from(path).expect("NullPointer"))
+	}
+}
+
+
+impl Deref for PathBuf {
+	type Target = Path;
+	#[inline]
+	fn deref(&self) -> &Path { self.as_path() }
+}
+
+impl Borrow<Path> for PathBuf {
+	#[inline]
+	fn borrow(&self) -> &Path { self.as_path() }
+}
+
+impl ToOwned for Path {
+	type Owned = PathBuf;
+	fn to_owned(&self) -> PathBuf { self.to_path_buf() }
+}
+
+
+impl From<&str> for PathBuf {
+	/// Panics if the string can't be allocated.
+	fn from(s: &str) -> Self { Self::from_bytes(s.as_bytes()) }
+}
+
+impl From<String> for PathBuf {
+	fn from(s: String) -> Self { Self::from_bytes(s.as_bytes()) }
+}
+
+impl From<&Path> for PathBuf {
+	fn from(path: &Path) -> Self { Self::from_bytes(path.as_bytes()) }
+}
+
+impl From<&CStr> for PathBuf {
+	fn from(s: &CStr) -> Self { Self::from_bytes(s.to_bytes()) }
+}
+
+impl From<CString> for PathBuf {
+	fn from(s: CString) -> Self { Self::from_bytes(s.as_bytes()) }
+}
+
+impl From<OsString> for PathBuf {
+	fn from(s: OsString) -> Self {
+		if Path::new(s.as_c_str()).has_trailing_slash() {
+			Self::from_bytes(s.as_c_str().to_bytes())
+		} else {
+			Self(s)
+		}
+	}
+}
+
+impl From<Cow<'_, Path>> for PathBuf {
+	fn from(path: Cow<'_, Path>) -> Self { path.into_owned() }
+}
+
+
+impl AsRef<Path> for Path {
+	#[inline]
+	fn as_ref(&self) -> &Path { self }
+}
+
+impl AsRef<Path> for PathBuf {
+	#[inline]
+	fn as_ref(&self) -> &Path { self.as_path() }
+}
+
+impl AsRef<Path> for CStr {
+	#[inline]
+	fn as_ref(&self) -> &Path { Path::new(self) }
+}
+
+impl AsRef<Path> for CString {
+	#[inline]
+	fn as_ref(&self) -> &Path { Path::new(self.as_c_str()) }
+}
+
+impl AsRef<Path> for OsString {
+	#[inline]
+	fn as_ref(&self) -> &Path { Path::new(self.as_c_str()) }
+}
+
+impl AsRef<CStr> for Path {
+	#[inline]
+	fn as_ref(&self) -> &CStr { &self.0 }
+}
+
+impl AsRef<CStr> for PathBuf {
+	#[inline]
+	fn as_ref(&self) -> &CStr { self.as_c_str() }
+}
+
+
+impl PartialEq for PathBuf {
+	fn eq(&self, other: &Self) -> bool { self.as_path() == other.as_path() }
+}
+impl Eq for PathBuf {}
+
+impl PartialEq<Path> for PathBuf {
+	fn eq(&self, other: &Path) -> bool { self.as_path() == other }
+}
+
+impl PartialEq<PathBuf> for Path {
+	fn eq(&self, other: &PathBuf) -> bool { self == other.as_path() }
+}
+
+impl PartialEq<&Path> for PathBuf {
+	fn eq(&self, other: &&Path) -> bool { self.as_path() == *other }
+}
+
+impl PartialEq<PathBuf> for &Path {
+	fn eq(&self, other: &PathBuf) -> bool { *self == other.as_path() }
+}
+
+impl PartialOrd for PathBuf {
+	fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> { self.as_path().partial_cmp(other.as_path()) }
+}
+
+impl Ord for PathBuf {
+	fn cmp(&self, other: &Self) -> core::cmp::Ordering { self.as_path().cmp(other.as_path()) }
+}
+
+impl Hash for PathBuf {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) { self.as_path().hash(state) }
+}
+
+
+impl fmt::Debug for Path {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{:?}", &self.0) }
+}
+
+impl fmt::Display for Path {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.to_string_lossy().fmt(f) }
+}
+
+impl fmt::Debug for PathBuf {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Debug::fmt(self.as_path(), f) }
+}
+
+impl fmt::Display for PathBuf {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Display::fmt(self.as_path(), f) }
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	fn path(bytes: &[u8]) -> &Path { Path::from_bytes_with_nul(bytes).unwrap() }
+
+
+	#[test]
+	fn normalize() {
+		assert_eq!(path(b"/ext/apps\0"), PathBuf::from("/ext/apps/"));
+		assert_eq!(path(b"/ext/apps\0"), PathBuf::from("/ext/apps//"));
+		assert_eq!(path(b"/\0"), PathBuf::from("/"));
+		assert!(matches!(path(b"/ext\0").normalize(), Cow::Borrowed(_)));
+		assert!(matches!(path(b"/ext/\0").normalize(), Cow::Owned(_)));
+	}
+
+	#[test]
+	fn join() {
+		assert_eq!(
+		           path(b"/ext/apps/Misc\0"),
+		           Path::ext().join(PathBuf::from("apps")).join(path(b"Misc\0"))
+		);
+		assert_eq!(path(b"/ext/apps\0"), Path::ext().join(PathBuf::from("/apps/")));
+		assert_eq!(path(b"/ext/apps_data/app\0"), PathBuf::app_data("app"));
+
+		let mut buf = PathBuf::from("/int/");
+		buf.push(path(b"file.txt\0"));
+		assert_eq!(path(b"/int/file.txt\0"), buf);
+		buf.push(path(b"//\0"));
+		assert_eq!(path(b"/int/file.txt\0"), buf);
+	}
+
+	#[test]
+	fn join_empty_and_trailing_slash() {
+		assert_eq!(Path::ext(), Path::ext().join(path(b"\0")));
+		assert_eq!(Path::ext(), path(b"/ext/\0").join(path(b"\0")));
+		assert_eq!(path(b"/ext/apps\0"), path(b"/ext/\0").join(path(b"apps\0")));
+		assert_eq!(path(b"/ext/apps\0"), path(b"/ext//\0").join(path(b"apps/\0")));
+		assert_eq!(path(b"/\0"), path(b"/\0").join(path(b"/\0")));
+	}
+
+	#[test]
+	fn parent() {
+		assert_eq!(Some(PathBuf::from("/ext/apps")), path(b"/ext/apps/Misc\0").parent());
+		assert_eq!(Some(PathBuf::from("/ext")), path(b"/ext/apps/\0").parent());
+		assert_eq!(None, path(b"/ext\0").parent());
+		assert_eq!(None, path(b"file\0").parent());
+
+		let mut buf = PathBuf::from("/ext/apps");
+		assert!(buf.pop());
+		assert_eq!(Path::ext(), buf);
+		assert!(!buf.pop());
+	}
+
+	#[test]
+	fn file_name_extension() {
+		let file = path(b"/ext/apps/file.tar.gz\0");
+		assert_eq!(Some(CStr::from_bytes_with_nul(b"file.tar.gz\0").unwrap()), file.file_name());
+		assert_eq!(Some(CStr::from_bytes_with_nul(b"gz\0").unwrap()), file.extension());
+
+		assert_eq!(None, path(b"/ext/apps/\0").file_name());
+		assert_eq!(None, path(b"/ext/.hidden\0").extension());
+		assert_eq!(None, path(b"/ext/a.b/file\0").extension());
+	}
+}
@@ -0,0 +1,221 @@
+//! Typed publish-subscribe over `FuriPubSub` and event streams of the system records.
+//!
+//! ```ignore
+//! let input = pubsub::input()?;
+//! let _subscription = input.subscribe(|event| println!("{:?}", event.key))?;
+//! input.publish(&InputEvent { key: Key::Ok, kind: InputType::Short, sequence: 0 }.into());
+//! ```
+
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::alloc::sync::Arc;
+use crate::error::NullPointerError;
+use crate::record::{self, Record, Service};
+
+
+/// What is freed when the last handle of [`PubSub`] is dropped.
+enum Owner {
+	/// Allocated by [`PubSub::new`].
+	Alloc,
+	/// Belongs to the opened record with this name.
+	Record(&'static [u8]),
+}
+
+struct Inner {
+	raw: NonNull<ffi::FuriPubSub>,
+	owner: Owner,
+}
+
+impl Drop for Inner {
+	fn drop(&mut self) {
+		match self.owner {
+			Owner::Alloc => unsafe { ffi::furi_pubsub_free(self.raw.as_ptr()) },
+			Owner::Record(name) => unsafe { ffi::furi_record_close(name.as_ptr() as _) },
+		}
+	}
+}
+
+
+/// Shared handle of the `FuriPubSub` with messages of type `T`.
+///
+/// Subscribers are called in the thread of the publisher, one after another.
+pub struct PubSub<T>(Arc<Inner>, PhantomData<fn(&T)>);
+
+impl<T> PubSub<T> {
+	/// Allocates new pubsub.
+	pub fn new() -> Result<Self, NullPointerError> {
+		let raw = NonNull::new(unsafe { ffi::furi_pubsub_alloc() }).ok_or(NullPointerError)?;
+		Ok(Self::from_inner(Inner { raw,
+		                            owner: Owner::Alloc }))
+	}
+
+	/// Opens the record of the service `S` which is a pubsub itself, it is closed with the last handle.
+	///
+	/// # Safety
+	/// Messages of the record must be of type `T`.
+	pub unsafe fn open_record<S: Service<Raw = ffi::FuriPubSub>>() -> Result<Self, NullPointerError> {
+		Self::open_record_with::<S, _>(|pubsub| pubsub)
+	}
+
+	/// Opens the record of the service `S` and gets the pubsub from it with `get`,
+	/// the record is closed with the last handle.
+	///
+	/// # Safety
+	/// Messages of the pubsub must be of type `T`.
+	pub unsafe fn open_record_with<S, F>(get: F) -> Result<Self, NullPointerError>
+		where S: Service,
+		      F: FnOnce(*mut S::Raw) -> *mut ffi::FuriPubSub {
+		let record = Record::<S>::open()?;
+		let raw = NonNull::new(get(record.as_ptr())).ok_or(NullPointerError)?;
+		// closed by `Inner`:
+		core::mem::forget(record);
+		Ok(Self::from_inner(Inner { raw,
+		                            owner: Owner::Record(S::NAME) }))
+	}
+
+	fn from_inner(inner: Inner) -> Self { Self(Arc::new(inner), PhantomData) }
+
+	/// Calls all subscribers with `message`, returns when they are done.
+	pub fn publish(&self, message: &T) { unsafe { ffi::furi_pubsub_publish(self.as_ptr(), message as *const T as *mut c_void) } }
+
+	/// Subscribes `f` to messages, it is called until the subscription is dropped.
+	///
+	/// Dropping the subscription from the callback deadlocks.
+	pub fn subscribe<F>(&self, f: F) -> Result<Subscription<T>, NullPointerError>
+		where F: FnMut(&T) + Send + 'static {
+		let mut callback: Box<Box<SubscriberFn<T>>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<SubscriberFn<T>> as *mut c_void;
+		let raw = unsafe { ffi::furi_pubsub_subscribe(self.as_ptr(), Some(pubsub_proxy::<T>), context) };
+		NonNull::new(raw).map(|raw| {
+			                 Subscription { pubsub: self.clone(),
+			                                raw,
+			                                _callback: callback }
+		                 })
+		                 .ok_or(NullPointerError)
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriPubSub { self.0.raw.as_ptr() }
+}
+
+impl<T> Clone for PubSub<T> {
+	fn clone(&self) -> Self { Self(self.0.clone(), PhantomData) }
+}
+
+unsafe impl<T: Send> Send for PubSub<T> {}
+unsafe impl<T: Send> Sync for PubSub<T> {}
+
+
+type SubscriberFn<T> = dyn FnMut(&T) + Send;
+
+/// Subscription to the [`PubSub`], unsubscribes on drop.
+///
+/// Keeps the pubsub alive.
+pub struct Subscription<T> {
+	pubsub: PubSub<T>,
+	raw: NonNull<ffi::FuriPubSubSubscription>,
+	_callback: Box<Box<SubscriberFn<T>>>,
+}
+
+impl<T> Subscription<T> {
+	pub fn pubsub(&self) -> &PubSub<T> { &self.pubsub }
+}
+
+impl<T> Drop for Subscription<T> {
+	fn drop(&mut self) {
+		// firmware unsubscribes under the lock of publishing, so the callback can be dropped after:
+		unsafe { ffi::furi_pubsub_unsubscribe(self.pubsub.as_ptr(), self.raw.as_ptr()) }
+	}
+}
+
+unsafe impl<T: Send> Send for Subscription<T> {}
+
+unsafe extern "C" fn pubsub_proxy<T>(message: *const c_void, context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<SubscriberFn<T>>);
+	if let Some(message) = (message as *const T).as_ref() {
+		callback(message);
+	}
+}
+
+
+/// Events of the storage service: SD card mount and unmount, closing of files and directories.
+pub fn storage() -> Result<PubSub<ffi::StorageEvent>, NullPointerError> {
+	unsafe { PubSub::open_record_with::<record::Storage, _>(|storage| ffi::storage_get_pubsub(storage)) }
+}
+
+/// Events of the power service: charging and battery level.
+pub fn power() -> Result<PubSub<PowerEvent>, NullPointerError> {
+	unsafe { PubSub::open_record_with::<record::Power, _>(|power| ffi::power_get_pubsub(power)) }
+}
+
+/// Events of the loader: start and stop of applications.
+pub fn loader() -> Result<PubSub<LoaderEvent>, NullPointerError> {
+	unsafe { PubSub::open_record_with::<record::Loader, _>(|loader| ffi::loader_get_pubsub(loader)) }
+}
+
+/// Raw input events of all keys, see also [`input::Subscription`](crate::input::Subscription).
+pub fn input() -> Result<PubSub<ffi::InputEvent>, NullPointerError> { unsafe { PubSub::open_record::<record::InputEvents>() } }
+
+
+/// `PowerEventType`, missing in bindings.
+#[repr(u8)]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerEventType {
+	StopCharging,
+	StartCharging,
+	FullyCharged,
+	BatteryLevelChanged,
+}
+
+/// `PowerEvent`, missing in bindings.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PowerEvent {
+	pub kind: PowerEventType,
+	/// Battery charge in percents, valid only for [`PowerEventType::BatteryLevelChanged`].
+	pub battery_level: u8,
+}
+
+/// `LoaderEventType`, missing in bindings.
+#[repr(u8)]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoaderEventType {
+	ApplicationStarted,
+	ApplicationStopped,
+}
+
+/// `LoaderEvent`, missing in bindings.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LoaderEvent {
+	pub kind: LoaderEventType,
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use crate::sync::channel;
+
+	#[test]
+	fn publish_subscribe() {
+		let pubsub = PubSub::<u32>::new().unwrap();
+		let (tx, rx) = channel::<u32>(4).unwrap();
+		let subscription = pubsub.subscribe(move |msg| tx.try_send(*msg).unwrap()).unwrap();
+		pubsub.publish(&1);
+		pubsub.publish(&2);
+		assert_eq!(Some(1), rx.try_recv());
+		assert_eq!(Some(2), rx.try_recv());
+
+		drop(pubsub);
+		subscription.pubsub().publish(&3);
+		assert_eq!(Some(3), rx.try_recv());
+		drop(subscription);
+		assert_eq!(None, rx.try_recv());
+	}
+}
@@ -0,0 +1,88 @@
+//! Typed records of the firmware services.
+//!
+//! ```ignore
+//! let gui = Record::<record::Gui>::open()?;
+//! let size = unsafe { ffi::gui_get_framebuffer_size(gui.as_ptr()) };
+//! ```
+
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use sys::ffi;
+use crate::error::NullPointerError;
+
+
+/// Service published as a record.
+pub trait Service {
+	/// Name of the record, nul-terminated.
+	const NAME: &'static [u8];
+	/// Type of the record data.
+	type Raw;
+}
+
+
+macro_rules! services {
+	($($(#[$meta:meta])* $name:ident($tag:expr) => $raw:ty;)*) => {
+		$(
+			$(#[$meta])*
+			#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+			pub struct $name;
+
+			impl Service for $name {
+				const NAME: &'static [u8] = $tag;
+				type Raw = $raw;
+			}
+		)*
+	};
+}
+
+services! {
+	Gui(ffi::RECORD_GUI) => ffi::Gui;
+	Notification(b"notification\0") => ffi::NotificationApp;
+	Dialogs(ffi::RECORD_DIALOGS) => ffi::DialogsApp;
+	Cli(b"cli\0") => ffi::Cli;
+	Loader(b"loader\0") => ffi::Loader;
+	Power(b"power\0") => ffi::Power;
+	Bt(b"bt\0") => ffi::Bt;
+	Dolphin(b"dolphin\0") => ffi::Dolphin;
+	/// `FuriPubSub` of `InputEvent`, see [`pubsub::input`](crate::pubsub::input).
+	InputEvents(b"input_events\0") => ffi::FuriPubSub;
+	Storage(ffi::RECORD_STORAGE) => ffi::Storage;
+}
+
+
+/// Opened record of the service `T`, closed on drop.
+pub struct Record<T: Service>(NonNull<T::Raw>, PhantomData<T>);
+
+impl<T: Service> Record<T> {
+	/// Opens the record of the service `T`, waits for its creation.
+	pub fn open() -> Result<Self, NullPointerError> {
+		let raw = unsafe { ffi::furi_record_open(T::NAME.as_ptr() as _) };
+		NonNull::new(raw as *mut T::Raw).map(|raw| Record(raw, PhantomData))
+		                                .ok_or(NullPointerError)
+	}
+
+	/// Checks whether the record of the service `T` is created.
+	pub fn exists() -> bool { unsafe { ffi::furi_record_exists(T::NAME.as_ptr() as _) } }
+
+	#[inline]
+	pub const fn as_ptr(&self) -> *mut T::Raw { self.0.as_ptr() }
+}
+
+impl<T: Service> Drop for Record<T> {
+	fn drop(&mut self) { unsafe { ffi::furi_record_close(T::NAME.as_ptr() as _) } }
+}
+
+unsafe impl<T: Service> Send for Record<T> {}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn open_storage() {
+		let storage = Record::<Storage>::open().unwrap();
+		assert!(!storage.as_ptr().is_null());
+		assert!(Record::<Storage>::exists());
+	}
+}
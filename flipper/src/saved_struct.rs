@@ -0,0 +1,240 @@
+//! Versioned binary settings stored by the firmware's `saved_struct`.
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, Default)]
+//! #[repr(C)]
+//! struct Config {
+//!     speed: u32,
+//!     volume: u8,
+//!     _reserved: [u8; 3],
+//! }
+//! unsafe impl Pod for Config {}
+//!
+//! let path = PathBuf::app_data("my_app").join(PathBuf::from("config.bin"));
+//! let mut config = Settings::<Config>::load_with(&path, MAGIC, 2, |version, bytes| {
+//!     // version 1 had only `speed`:
+//!     let speed = u32::from_le_bytes(bytes.try_into().ok()?);
+//!     Some(Config { speed, ..Default::default() })
+//! }).unwrap_or_else(|_| Settings::new(&path, MAGIC, 2, Default::default()));
+//! config.volume = 7;
+//! config.save()?;
+//! ```
+//!
+//! File is a header with magic, version and checksum followed by the raw bytes of the value,
+//! so its size is fixed by the version.
+
+use core::fmt;
+use core::mem::size_of;
+use core::ops::{Deref, DerefMut};
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::fs::Storage;
+use crate::path::{Path, PathBuf};
+
+
+/// Size of the header written by the firmware before the value:
+/// magic, version, checksum, flags and timestamp.
+const HEADER_LEN: usize = 8;
+
+
+/// Type that can be stored as raw bytes and restored from any bytes of its size.
+///
+/// # Safety
+///
+/// Any bit pattern must be a valid value, so the type must not contain pointers, references, `bool`s or enums,
+/// e.g. `#[repr(C)]` struct of integers or arrays of them without implicit padding.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! pod {
+	($($t:ty),*) => { $(unsafe impl Pod for $t {})* };
+}
+pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+	/// Storage record can't be opened.
+	NullPointer,
+	/// File is missing or corrupted, or has another magic, version or size.
+	Load,
+	/// File is of the older `version`, but migration failed.
+	Migrate { version: u8 },
+	/// File can't be written.
+	Save,
+}
+
+impl core::error::Error for Error {}
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::NullPointer => write!(f, "unable to open storage"),
+			Error::Load => write!(f, "unable to load saved struct"),
+			Error::Migrate { version } => write!(f, "unable to migrate saved struct from version {version}"),
+			Error::Save => write!(f, "unable to save struct"),
+		}
+	}
+}
+
+impl From<NullPointerError> for Error {
+	fn from(_: NullPointerError) -> Self { Error::NullPointer }
+}
+
+
+/// Value of `T` bound to the file at `path` with `magic` and `version`.
+///
+/// Derefs to the value, changes are written by [`save`](Settings::save).
+pub struct Settings<T: Pod> {
+	path: PathBuf,
+	magic: u8,
+	version: u8,
+	value: T,
+}
+
+impl<T: Pod> Settings<T> {
+	/// New settings with `value`, nothing is written until [`save`](Settings::save).
+	pub fn new<P: AsRef<Path>>(path: P, magic: u8, version: u8, value: T) -> Self {
+		Self { path: path.as_ref().to_path_buf(),
+		       magic,
+		       version,
+		       value }
+	}
+
+	/// Loads value from the file at `path` with exactly `magic` and `version`.
+	pub fn load<P: AsRef<Path>>(path: P, magic: u8, version: u8) -> Result<Self> {
+		let path = path.as_ref();
+		// any bytes are valid value of `T`:
+		let mut value: T = unsafe { core::mem::zeroed() };
+		let loaded = unsafe { ffi::saved_struct_load(path.as_ptr(), &mut value as *mut T as _, size_of::<T>(), magic, version) };
+		if loaded {
+			Ok(Self::new(path, magic, version, value))
+		} else {
+			Err(Error::Load)
+		}
+	}
+
+	/// Same as [`load`](Settings::load), but if the file has an older version,
+	/// calls `migrate` with that version and the stored bytes to convert them to the current value.
+	///
+	/// Migrated value isn't written, call [`save`](Settings::save) to store it with the current version.
+	pub fn load_with<P, F>(path: P, magic: u8, version: u8, migrate: F) -> Result<Self>
+		where P: AsRef<Path>,
+		      F: FnOnce(u8, &[u8]) -> Option<T> {
+		let path = path.as_ref();
+		if let Ok(settings) = Self::load(path, magic, version) {
+			return Ok(settings);
+		}
+
+		let bytes = Storage::open_default()?.read(path).map_err(|_| Error::Load)?;
+		let (header, data) = bytes.split_at(HEADER_LEN.min(bytes.len()));
+		match *header {
+			[m, stored, checksum, ..] if m == magic && stored < version && checksum == self::checksum(data) => {
+				let value = migrate(stored, data).ok_or(Error::Migrate { version: stored })?;
+				Ok(Self::new(path, magic, version, value))
+			},
+			_ => Err(Error::Load),
+		}
+	}
+
+	/// Writes the value to the file, truncates it if exists.
+	pub fn save(&self) -> Result<()> {
+		let path = self.path.as_ptr();
+		// the value is only read by the firmware:
+		let data = &self.value as *const T as *mut T as _;
+		let saved = unsafe { ffi::saved_struct_save(path, data, size_of::<T>(), self.magic, self.version) };
+		if saved {
+			Ok(())
+		} else {
+			Err(Error::Save)
+		}
+	}
+
+
+	pub fn path(&self) -> &Path { &self.path }
+	pub fn magic(&self) -> u8 { self.magic }
+	pub fn version(&self) -> u8 { self.version }
+
+	pub fn get(&self) -> &T { &self.value }
+	pub fn get_mut(&mut self) -> &mut T { &mut self.value }
+	pub fn into_inner(self) -> T { self.value }
+}
+
+impl<T: Pod> Deref for Settings<T> {
+	type Target = T;
+	fn deref(&self) -> &T { &self.value }
+}
+
+impl<T: Pod> DerefMut for Settings<T> {
+	fn deref_mut(&mut self) -> &mut T { &mut self.value }
+}
+
+impl<T: Pod + fmt::Debug> fmt::Debug for Settings<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Settings")
+		 .field("path", &self.path.as_c_str())
+		 .field("magic", &self.magic)
+		 .field("version", &self.version)
+		 .field("value", &self.value)
+		 .finish()
+	}
+}
+
+
+/// Checksum of the stored value, same as in the firmware.
+fn checksum(data: &[u8]) -> u8 { data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte)) }
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	#[derive(Debug, Clone, Copy, PartialEq, Default)]
+	#[repr(C)]
+	struct Config {
+		speed: u32,
+		ratio: f32,
+		key: [u8; 4],
+	}
+	unsafe impl Pod for Config {}
+
+	#[test]
+	fn save_load_migrate() {
+		let path = Path::ext().join(PathBuf::from("saved_struct_test.bin"));
+		let mut config = Settings::new(&path, 0x42, 1, 3_u32);
+		*config += 1;
+		config.save().unwrap();
+		assert_eq!(4, *Settings::<u32>::load(&path, 0x42, 1).unwrap());
+		assert_eq!(Err(Error::Load), Settings::<u32>::load(&path, 0x43, 1).map(drop));
+		assert_eq!(Err(Error::Load), Settings::<Config>::load(&path, 0x42, 1).map(drop));
+
+		let migrate = |version, bytes: &[u8]| {
+			assert_eq!(1, version);
+			let speed = u32::from_ne_bytes(bytes.try_into().ok()?);
+			Some(Config { speed,
+			              ratio: 0.5,
+			              ..Default::default() })
+		};
+		let mut config = Settings::<Config>::load_with(&path, 0x42, 2, migrate).unwrap();
+		assert_eq!(4, config.speed);
+		config.key = [0xDE, 0xAD, 0xBE, 0xEF];
+		config.save().unwrap();
+
+		let expected = Config { speed: 4,
+		                        ratio: 0.5,
+		                        key: [0xDE, 0xAD, 0xBE, 0xEF] };
+		let config = Settings::<Config>::load_with(&path, 0x42, 2, |_, _| unreachable!()).unwrap();
+		assert_eq!(expected, config.into_inner());
+		// newer version isn't migrated:
+		assert_eq!(
+		           Err(Error::Load),
+		           Settings::<u32>::load_with(&path, 0x42, 1, |_, _| Some(0)).map(drop)
+		);
+
+		Settings::new(&path, 0x42, 1, 1_u32).save().unwrap();
+		let failed = Settings::<Config>::load_with(&path, 0x42, 2, |_, _| None);
+		assert_eq!(Err(Error::Migrate { version: 1 }), failed.map(drop));
+	}
+}
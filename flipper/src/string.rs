@@ -3,24 +3,66 @@ use core::ffi::CStr;
 use core::ffi::c_char;
 use core::hash::Hash;
 use core::cmp::Ordering;
-use core::borrow::Borrow;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt::Write;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
 use sys::ffi;
+use sys::alloc::borrow::ToOwned;
 use sys::alloc::ffi::CString;
+use sys::alloc::string::String;
 use crate::error::NullPointerError;
 use crate::AsPtr;
 
 
+/// Owned `FuriString`, derefs to the borrowed [`FuriStr`].
 pub struct OsString(NonNull<ffi::FuriString>);
 
+/// Borrowed `FuriString` owned by someone else, e.g. by the [`OsString`] or by the firmware.
+///
+/// It is to the [`OsString`] what `str` is to the `String`, so it is only used behind a reference.
+#[repr(transparent)]
+pub struct FuriStr(ffi::FuriString);
+
 
 impl OsString {
 	pub fn new() -> Result<Self, NullPointerError> { unsafe { Self::from_raw(ffi::furi_string_alloc()) } }
 
-	unsafe fn from_raw(ptr: *mut ffi::FuriString) -> Result<Self, NullPointerError> {
+	/// Takes ownership of the string allocated by the firmware or released by [`into_raw`](OsString::into_raw).
+	///
+	/// # Safety
+	///
+	/// `ptr` must be null or valid `FuriString` not owned by anyone else, it is freed on drop.
+	pub unsafe fn from_raw(ptr: *mut ffi::FuriString) -> Result<Self, NullPointerError> {
 		NonNull::new(ptr).ok_or(NullPointerError).map(Self)
 	}
 
-	/// Length in `char`s.
+	/// Releases ownership, e.g. to hand the string over to the firmware.
+	///
+	/// The string isn't freed, use [`from_raw`](OsString::from_raw) to take it back.
+	pub fn into_raw(self) -> *mut ffi::FuriString { ManuallyDrop::new(self).0.as_ptr() }
+
+	pub fn as_furi_str(&self) -> &FuriStr { unsafe { FuriStr::from_ptr(self.as_ptr()) } }
+	pub fn as_mut_furi_str(&mut self) -> &mut FuriStr { unsafe { FuriStr::from_mut_ptr(self.as_ptr()) } }
+}
+
+
+impl FuriStr {
+	/// Borrows the string for `'a`.
+	///
+	/// # Safety
+	///
+	/// `ptr` must be non-null valid `FuriString` which outlives `'a` and isn't changed or freed while borrowed.
+	pub unsafe fn from_ptr<'a>(ptr: *const ffi::FuriString) -> &'a Self { &*(ptr as *const Self) }
+
+	/// Mutably borrows the string for `'a`.
+	///
+	/// # Safety
+	///
+	/// Same as for [`from_ptr`](FuriStr::from_ptr), and the string must not be accessed by anyone else while borrowed.
+	pub unsafe fn from_mut_ptr<'a>(ptr: *mut ffi::FuriString) -> &'a mut Self { &mut *(ptr as *mut Self) }
+
+	/// Length in bytes, without the trailing nul.
 	pub fn len(&self) -> usize { unsafe { ffi::furi_string_size(self.as_ptr()) } }
 
 	/// Length in utf8 characters.
@@ -30,18 +72,145 @@ impl OsString {
 
 
 	pub fn get(&self, index: usize) -> c_char { unsafe { ffi::furi_string_get_char(self.as_ptr(), index) } }
-	pub fn set(&self, index: usize, char: c_char) { unsafe { ffi::furi_string_set_char(self.as_ptr(), index, char) } }
+	pub fn set(&mut self, index: usize, char: c_char) { unsafe { ffi::furi_string_set_char(self.as_ptr(), index, char) } }
+
+
+	/// Borrows the content, valid until the string is changed.
+	pub fn as_c_str(&self) -> &CStr { unsafe { CStr::from_ptr(ffi::furi_string_get_cstr(self.as_ptr())) } }
+
+	/// Copies the content into a new `CString` independent of this string.
+	pub fn to_c_string(&self) -> CString { self.as_c_str().into() }
 }
 
-impl Clone for OsString {
-	fn clone(&self) -> Self {
-		unsafe {
-			let ptr = ffi::furi_string_alloc_set(self.as_ptr());
-			Self::from_raw(ptr).expect("NullPointer")
+
+/// Editing like the `String`.
+///
+/// Indices and lengths are in bytes. Added `&str`s are truncated at the first nul if there is one.
+impl FuriStr {
+	/// Bytes without the trailing nul.
+	pub fn as_bytes(&self) -> &[u8] { self.as_c_str().to_bytes() }
+
+	pub fn to_str(&self) -> Result<&str, core::str::Utf8Error> { self.as_c_str().to_str() }
+
+	/// Iterator over UTF-8 decoded chars, invalid sequences are replaced with [`char::REPLACEMENT_CHARACTER`].
+	pub fn chars(&self) -> Chars<'_> { Chars(self.as_bytes().iter()) }
+
+	/// Reserves memory for at least `size` bytes in total.
+	pub fn reserve(&mut self, size: usize) { unsafe { ffi::furi_string_reserve(self.as_ptr(), size) } }
+
+	pub fn clear(&mut self) { unsafe { ffi::furi_string_reset(self.as_ptr()) } }
+
+
+	pub fn push(&mut self, c: char) { unsafe { ffi::furi_string_utf8_push(self.as_ptr(), c as _) } }
+
+	pub fn push_str(&mut self, s: &str) { with_c_str(s, |s| unsafe { ffi::furi_string_cat_str(self.as_ptr(), s.as_ptr() as _) }) }
+
+	pub fn insert(&mut self, index: usize, s: &str) { self.replace_range(index, 0, s) }
+
+	/// Replaces `len` bytes starting at `index` with `s`.
+	pub fn replace_range(&mut self, index: usize, len: usize, s: &str) {
+		with_c_str(s, |s| unsafe {
+			ffi::furi_string_replace_at(self.as_ptr(), index, len, s.as_ptr() as _)
+		})
+	}
+
+	/// Replaces all occurrences of `from` with `to`.
+	pub fn replace(&mut self, from: &str, to: &str) {
+		with_c_str(from, |from| {
+			with_c_str(to, |to| unsafe {
+				ffi::furi_string_replace_all_str(self.as_ptr(), from.as_ptr() as _, to.as_ptr() as _)
+			})
+		})
+	}
+
+	/// Removes leading and trailing whitespaces: spaces, tabs and line endings.
+	pub fn trim(&mut self) { self.trim_matches(" \n\r\t") }
+
+	/// Removes leading and trailing bytes contained in `chars`.
+	pub fn trim_matches(&mut self, chars: &str) {
+		with_c_str(chars, |chars| unsafe {
+			ffi::furi_string_trim(self.as_ptr(), chars.as_ptr() as _)
+		})
+	}
+
+	/// Keeps first `len` bytes.
+	pub fn left(&mut self, len: usize) { unsafe { ffi::furi_string_left(self.as_ptr(), len) } }
+
+	/// Keeps bytes starting at `index`.
+	pub fn right(&mut self, index: usize) { unsafe { ffi::furi_string_right(self.as_ptr(), index) } }
+
+	/// Keeps `len` bytes starting at `index`.
+	pub fn mid(&mut self, index: usize, len: usize) { unsafe { ffi::furi_string_mid(self.as_ptr(), index, len) } }
+
+
+	/// Byte index of the first `needle` starting from `start`.
+	pub fn search(&self, needle: &str, start: usize) -> Option<usize> {
+		let index = with_c_str(needle, |needle| unsafe {
+			ffi::furi_string_search_str(self.as_ptr(), needle.as_ptr() as _, start)
+		});
+		found(index)
+	}
+
+	/// Byte index of the first `c` starting from `start`.
+	pub fn search_char(&self, c: u8, start: usize) -> Option<usize> {
+		found(unsafe { ffi::furi_string_search_char(self.as_ptr(), c as _, start) })
+	}
+
+	/// Byte index of the last `c` starting from `start`.
+	pub fn rsearch_char(&self, c: u8, start: usize) -> Option<usize> {
+		found(unsafe { ffi::furi_string_search_rchar(self.as_ptr(), c as _, start) })
+	}
+
+	pub fn starts_with(&self, s: &str) -> bool {
+		with_c_str(s, |s| unsafe {
+			ffi::furi_string_start_with_str(self.as_ptr(), s.as_ptr() as _)
+		})
+	}
+
+	pub fn ends_with(&self, s: &str) -> bool {
+		with_c_str(s, |s| unsafe {
+			ffi::furi_string_end_with_str(self.as_ptr(), s.as_ptr() as _)
+		})
+	}
+}
+
+/// Converts `STRING_FAILURE` of the search functions to `None`.
+fn found(index: usize) -> Option<usize> { (index != usize::MAX).then_some(index) }
+
+
+/// Iterator over chars of the [`FuriStr`], see [`FuriStr::chars`].
+#[derive(Clone)]
+pub struct Chars<'a>(core::slice::Iter<'a, u8>);
+
+impl Iterator for Chars<'_> {
+	type Item = char;
+
+	fn next(&mut self) -> Option<char> {
+		use ffi::FuriStringUTF8State::*;
+
+		let mut state = FuriStringUTF8StateStarting;
+		let mut unicode = 0;
+		let mut byte = *self.0.next()?;
+		loop {
+			unsafe { ffi::furi_string_utf8_decode(byte as _, &mut state, &mut unicode) };
+			match state {
+				FuriStringUTF8StateStarting => return Some(char::from_u32(unicode as _).unwrap_or(char::REPLACEMENT_CHARACTER)),
+				FuriStringUTF8StateError => return Some(char::REPLACEMENT_CHARACTER),
+				_ => {
+					byte = match self.0.next() {
+						Some(byte) => *byte,
+						None => return Some(char::REPLACEMENT_CHARACTER),
+					}
+				},
+			}
 		}
 	}
 }
 
+impl Clone for OsString {
+	fn clone(&self) -> Self { self.as_furi_str().to_owned() }
+}
+
 impl Drop for OsString {
 	fn drop(&mut self) {
 		unsafe {
@@ -50,40 +219,52 @@ impl Drop for OsString {
 	}
 }
 
-impl AsRef<CStr> for OsString {
-	fn as_ref(&self) -> &CStr {
-		unsafe {
-			let ptr = ffi::furi_string_get_cstr(self.as_ptr());
-			CStr::from_ptr(ptr)
-		}
-	}
+impl Deref for OsString {
+	type Target = FuriStr;
+	fn deref(&self) -> &FuriStr { self.as_furi_str() }
 }
 
-impl OsString {
-	pub fn to_c_string(&self) -> Result<CString, NullPointerError> {
+impl DerefMut for OsString {
+	fn deref_mut(&mut self) -> &mut FuriStr { self.as_mut_furi_str() }
+}
+
+impl Borrow<FuriStr> for OsString {
+	fn borrow(&self) -> &FuriStr { self }
+}
+
+impl BorrowMut<FuriStr> for OsString {
+	fn borrow_mut(&mut self) -> &mut FuriStr { self }
+}
+
+impl ToOwned for FuriStr {
+	type Owned = OsString;
+
+	/// Panics if the string can't be allocated.
+	fn to_owned(&self) -> OsString {
 		unsafe {
-			let ptr = ffi::furi_string_get_cstr(self.as_ptr());
-			if ptr.is_null() {
-				Err(NullPointerError)
-			} else {
-				Ok(CString::from_raw(ptr as _))
-			}
+			let ptr = ffi::furi_string_alloc_set(self.as_ptr());
+			OsString::from_raw(ptr).expect("NullPointer")
 		}
 	}
+}
+
+impl AsRef<FuriStr> for OsString {
+	fn as_ref(&self) -> &FuriStr { self }
+}
+
+impl AsRef<CStr> for OsString {
+	fn as_ref(&self) -> &CStr { self.as_c_str() }
+}
 
-	pub fn as_c_str(&self) -> &CStr { self.as_ref() }
+impl AsRef<CStr> for FuriStr {
+	fn as_ref(&self) -> &CStr { self.as_c_str() }
 }
 
 
 impl TryFrom<CString> for OsString {
 	type Error = NullPointerError;
 
-	fn try_from(value: CString) -> Result<Self, Self::Error> {
-		unsafe {
-			let ptr = ffi::furi_string_alloc_set_str(value.into_raw() as _);
-			Self::from_raw(ptr)
-		}
-	}
+	fn try_from(value: CString) -> Result<Self, Self::Error> { Self::try_from(value.as_c_str()) }
 }
 
 impl TryFrom<&'_ CStr> for OsString {
@@ -98,15 +279,81 @@ impl TryFrom<&'_ CStr> for OsString {
 }
 
 
+impl From<&str> for OsString {
+	/// `s` is truncated at the first nul if there is one.
+	///
+	/// Panics if the string can't be allocated, use [`OsString::new`] and [`push_str`](FuriStr::push_str) to handle it.
+	fn from(s: &str) -> Self {
+		let mut string = Self::new().expect("NullPointer");
+		string.push_str(s);
+		string
+	}
+}
+
+impl From<&FuriStr> for OsString {
+	/// Panics if the string can't be allocated.
+	fn from(s: &FuriStr) -> Self { s.to_owned() }
+}
+
+impl From<&FuriStr> for String {
+	/// Invalid UTF-8 sequences are replaced with [`char::REPLACEMENT_CHARACTER`].
+	fn from(s: &FuriStr) -> Self { s.chars().collect() }
+}
+
+impl From<&OsString> for String {
+	/// Invalid UTF-8 sequences are replaced with [`char::REPLACEMENT_CHARACTER`].
+	fn from(s: &OsString) -> Self { String::from(s.as_furi_str()) }
+}
+
+impl From<OsString> for String {
+	/// Invalid UTF-8 sequences are replaced with [`char::REPLACEMENT_CHARACTER`].
+	fn from(s: OsString) -> Self { String::from(s.as_furi_str()) }
+}
+
+
 impl AsPtr<ffi::FuriString> for OsString {
 	fn as_ptr(&self) -> *mut ffi::FuriString { self.0.as_ptr() }
 }
 
+impl AsPtr<ffi::FuriString> for FuriStr {
+	fn as_ptr(&self) -> *mut ffi::FuriString { &self.0 as *const _ as *mut _ }
+}
+
 
 impl core::fmt::Debug for OsString {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { self.as_furi_str().fmt(f) }
+}
+
+impl core::fmt::Debug for FuriStr {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { write!(f, "{:?}", self.as_c_str()) }
 }
 
+impl core::fmt::Display for OsString {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { self.as_furi_str().fmt(f) }
+}
+
+impl core::fmt::Display for FuriStr {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { self.chars().try_for_each(|c| f.write_char(c)) }
+}
+
+impl core::fmt::Write for OsString {
+	fn write_str(&mut self, s: &str) -> core::fmt::Result { self.as_mut_furi_str().write_str(s) }
+
+	fn write_char(&mut self, c: char) -> core::fmt::Result { self.as_mut_furi_str().write_char(c) }
+}
+
+impl core::fmt::Write for FuriStr {
+	fn write_str(&mut self, s: &str) -> core::fmt::Result {
+		self.push_str(s);
+		Ok(())
+	}
+
+	fn write_char(&mut self, c: char) -> core::fmt::Result {
+		self.push(c);
+		Ok(())
+	}
+}
+
 
 impl Borrow<CStr> for OsString {
 	#[inline]
@@ -114,65 +361,208 @@ impl Borrow<CStr> for OsString {
 }
 
 impl Hash for OsString {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) { self.as_furi_str().hash(state) }
+}
+
+impl Hash for FuriStr {
 	fn hash<H: core::hash::Hasher>(&self, state: &mut H) { self.as_c_str().hash(state); }
 }
 
 impl PartialEq<OsString> for OsString {
-	fn eq(&self, other: &Self) -> bool { unsafe { ffi::furi_string_equal(self.as_ptr(), other.as_ptr()) } }
+	fn eq(&self, other: &Self) -> bool { self.as_furi_str().eq(other.as_furi_str()) }
 }
 
 impl PartialEq<CString> for OsString {
-	fn eq(&self, other: &CString) -> bool { self.eq(other.as_c_str()) }
+	fn eq(&self, other: &CString) -> bool { self.as_furi_str().eq(other.as_c_str()) }
 }
 
 impl PartialEq<CStr> for OsString {
+	fn eq(&self, other: &CStr) -> bool { self.as_furi_str().eq(other) }
+}
+
+impl PartialEq<FuriStr> for FuriStr {
+	fn eq(&self, other: &Self) -> bool { unsafe { ffi::furi_string_equal(self.as_ptr(), other.as_ptr()) } }
+}
+
+impl PartialEq<CStr> for FuriStr {
 	fn eq(&self, other: &CStr) -> bool { unsafe { ffi::furi_string_equal_str(self.as_ptr(), other.as_ptr() as _) } }
 }
 
 impl PartialOrd<OsString> for OsString {
-	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		match unsafe { ffi::furi_string_cmp(self.as_ptr(), other.as_ptr()) } {
-			0 => Some(Ordering::Equal),
-			1 => Some(Ordering::Greater),
-			-1 => Some(Ordering::Less),
-			_ => None,
-		}
-	}
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Eq for OsString {}
+impl Ord for OsString {
+	fn cmp(&self, other: &Self) -> Ordering { self.as_furi_str().cmp(other.as_furi_str()) }
 }
 
 impl PartialOrd<CString> for OsString {
-	fn partial_cmp(&self, other: &CString) -> Option<Ordering> { self.partial_cmp(other.as_c_str()) }
+	fn partial_cmp(&self, other: &CString) -> Option<Ordering> { self.as_furi_str().partial_cmp(other.as_c_str()) }
 }
 
 impl PartialOrd<CStr> for OsString {
+	fn partial_cmp(&self, other: &CStr) -> Option<Ordering> { self.as_furi_str().partial_cmp(other) }
+}
+
+impl PartialOrd<FuriStr> for FuriStr {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Eq for FuriStr {}
+impl Ord for FuriStr {
+	/// Compares bytes like `strcmp`.
+	fn cmp(&self, other: &Self) -> Ordering { unsafe { ffi::furi_string_cmp(self.as_ptr(), other.as_ptr()) }.cmp(&0) }
+}
+
+impl PartialOrd<CStr> for FuriStr {
 	fn partial_cmp(&self, other: &CStr) -> Option<Ordering> {
-		match unsafe { ffi::furi_string_cmp_str(self.as_ptr(), other.as_ptr() as _) } {
-			0 => Some(Ordering::Equal),
-			1 => Some(Ordering::Greater),
-			-1 => Some(Ordering::Less),
-			_ => None,
-		}
+		Some(unsafe { ffi::furi_string_cmp_str(self.as_ptr(), other.as_ptr() as _) }.cmp(&0))
+	}
+}
+
+
+/// Text accepted by setters of GUI modules, converted into owned null-terminated string.
+///
+/// `&str` and `String` are truncated at the first nul if there is one.
+pub trait IntoCString {
+	fn into_c_string(self) -> CString;
+}
+
+impl IntoCString for &str {
+	fn into_c_string(self) -> CString {
+		let bytes = self.as_bytes();
+		let bytes = &bytes[..bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len())];
+		unsafe { CString::from_vec_unchecked(bytes.to_vec()) }
 	}
 }
 
+impl IntoCString for String {
+	fn into_c_string(self) -> CString { self.as_str().into_c_string() }
+}
+
+impl IntoCString for &CStr {
+	fn into_c_string(self) -> CString { self.into() }
+}
+
+impl IntoCString for CString {
+	fn into_c_string(self) -> CString { self }
+}
+
+impl IntoCString for &FuriStr {
+	fn into_c_string(self) -> CString { self.to_c_string() }
+}
+
+impl IntoCString for &OsString {
+	fn into_c_string(self) -> CString { self.to_c_string() }
+}
+
+impl IntoCString for OsString {
+	fn into_c_string(self) -> CString { self.to_c_string() }
+}
 
-#[cfg(test)]
+
+/// Calls `f` with null-terminated copy of `s`.
+///
+/// Short strings are copied on the stack, so it is cheap enough to use it in draw callbacks.
+/// String is truncated at the first nul if there is one.
+pub(crate) fn with_c_str<R, F: FnOnce(&CStr) -> R>(s: &str, f: F) -> R {
+	const STACK_BUF_LEN: usize = 64;
+
+	let bytes = s.as_bytes();
+	let bytes = &bytes[..bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len())];
+	if bytes.len() < STACK_BUF_LEN {
+		let mut buf = [0u8; STACK_BUF_LEN];
+		buf[..bytes.len()].copy_from_slice(bytes);
+		f(unsafe { CStr::from_bytes_with_nul_unchecked(&buf[..=bytes.len()]) })
+	} else {
+		f(unsafe { CString::from_vec_unchecked(bytes.to_vec()) }.as_c_str())
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
 mod tests {
 	use super::*;
-	use std::ffi::CString;
 
 	#[test]
-	#[cfg(target = "thumbv7em-none-eabihf")]
-	unsafe fn cmp_os_string() {
-		let a = OsString::try_from(CStr::from_ptr(b"a\0".as_ptr() as _)).unwrap();
-		let b = OsString::try_from(CStr::from_ptr(b"b\0".as_ptr() as _)).unwrap();
+	fn cmp_os_string() {
+		let a = OsString::try_from(CStr::from_bytes_with_nul(b"a\0").unwrap()).unwrap();
+		let b = OsString::try_from(CStr::from_bytes_with_nul(b"b\0").unwrap()).unwrap();
 		let result = a.partial_cmp(&b);
 
-		use alloc::string::String;
 		let a = String::from("a");
 		let b = String::from("b");
 		let expected = a.partial_cmp(&b);
 
 		assert_eq!(result, expected);
 	}
+	#[test]
+	fn cmp_not_adjacent() {
+		assert!(OsString::from("a") < OsString::from("c"));
+		assert!(OsString::from("abd") > OsString::from("abb"));
+		assert_eq!(
+		           Some(Ordering::Less),
+		           OsString::from("a").partial_cmp(CStr::from_bytes_with_nul(b"z\0").unwrap())
+		);
+	}
+
+	#[test]
+	fn edit() {
+		let mut s = OsString::from("  hello ");
+		s.push_str("world\t");
+		s.trim();
+		assert_eq!("hello world", s.to_str().unwrap());
+		s.insert(5, ",");
+		s.push('!');
+		s.replace("o", "0");
+		assert_eq!("hell0, w0rld!", s.to_string());
+		assert!(s.starts_with("hell0") && s.ends_with("!"));
+		assert_eq!(Some(4), s.search("0", 0));
+		assert_eq!(Some(8), s.search("0", 5));
+		assert_eq!(None, s.search("o", 0));
+		assert_eq!(Some(8), s.rsearch_char(b'0', 0));
+		s.mid(7, 5);
+		assert_eq!("w0rld", String::from(&s));
+		s.left(2);
+		assert_eq!("w0", String::from(s));
+
+		let s = crate::os_format!("{}: {:.1}, ??{}", "key", 0.5, 'ß');
+		assert_eq!(
+		           vec!['k', 'e', 'y', ':', ' ', '0', '.', '5', ',', ' ', '?', '?', 'ß'],
+		           s.chars().collect::<Vec<_>>()
+		);
+		let invalid = OsString::try_from(CStr::from_bytes_with_nul(b"a\xC3\0").unwrap()).unwrap();
+		assert_eq!("a\u{FFFD}", invalid.to_string());
+	}
+
+	#[test]
+	fn to_c_string_copies() {
+		let mut s = OsString::from("abc");
+		let copy = s.to_c_string();
+		s.push_str("def");
+		drop(s);
+		assert_eq!(b"abc", copy.to_bytes());
+	}
+
+	#[test]
+	fn raw_round_trip() {
+		let raw = OsString::from("owned by C").into_raw();
+		let view = unsafe { FuriStr::from_ptr(raw) };
+		assert_eq!("owned by C", view.to_str().unwrap());
+		assert_eq!(OsString::from("owned by C"), view.to_owned());
+
+		let mut s = unsafe { OsString::from_raw(raw) }.unwrap();
+		let view: &mut FuriStr = &mut s;
+		view.push('!');
+		assert_eq!("owned by C!", String::from(s));
+		assert!(unsafe { OsString::from_raw(core::ptr::null_mut()) }.is_err());
+	}
+
+	#[test]
+	fn truncated_at_nul() {
+		let s = OsString::from("abc\0def");
+		assert_eq!(3, s.len());
+		assert_eq!(b"abc", "abc\0def".into_c_string().to_bytes());
+	}
 }
@@ -0,0 +1,203 @@
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::mem::{size_of, ManuallyDrop, MaybeUninit};
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use sys::ffi;
+use crate::alloc::boxed::Box;
+use crate::alloc::sync::Arc;
+use crate::error::NullPointerError;
+use crate::time::{ticks, WAIT_FOREVER};
+
+
+/// Creates bounded channel for `capacity` messages, messages are copied into the queue.
+///
+/// Like `std::sync::mpsc`, the channel is disconnected when all [`Sender`]s are dropped,
+/// then the [`Receiver`] gets remaining messages and `None` after them.
+///
+/// ```ignore
+/// let (tx, rx) = sync::channel::<u32>(8)?;
+/// thread::spawn(move || tx.send(42).ok());
+/// assert_eq!(Some(42), rx.recv());
+/// ```
+pub fn channel<T: Copy + Send>(capacity: u32) -> Result<(Sender<T>, Receiver<T>), NullPointerError> {
+	let shared = Arc::new(Shared::new(capacity)?);
+	Ok((Sender(shared.clone()), Receiver(shared)))
+}
+
+/// Creates bounded channel for `capacity` boxed messages, only pointers are copied into the queue.
+///
+/// Boxes remaining in the queue are dropped with it.
+pub fn box_channel<T: Send>(capacity: u32) -> Result<(BoxSender<T>, BoxReceiver<T>), NullPointerError> {
+	let shared = Arc::new(Shared::new(capacity)?);
+	Ok((Sender(shared.clone()), Receiver(shared)))
+}
+
+
+pub type BoxSender<T> = Sender<Box<T>>;
+pub type BoxReceiver<T> = Receiver<Box<T>>;
+
+
+/// Queue of the channel, `None` is the wake-up item posted by the last dropped sender.
+struct Shared<T> {
+	queue: Queue<Option<T>>,
+	senders: AtomicUsize,
+}
+
+impl<T> Shared<T> {
+	fn new(capacity: u32) -> Result<Self, NullPointerError> {
+		Ok(Self { queue: Queue::new(capacity)?,
+		          senders: AtomicUsize::new(1) })
+	}
+
+	fn put(&self, msg: T, timeout: u32) -> Result<(), T> { self.queue.put(Some(msg), timeout).map_err(Option::unwrap) }
+
+	/// Skips the wake-up item, then the queue has only messages sent before it, so waiting is useless.
+	fn get(&self, mut timeout: u32) -> Option<T> {
+		loop {
+			match self.queue.get(timeout)? {
+				Some(msg) => return Some(msg),
+				None => timeout = 0,
+			}
+		}
+	}
+
+	fn is_disconnected(&self) -> bool { self.senders.load(Ordering::Acquire) == 0 }
+}
+
+
+/// Sending half of the [`channel`], can be cloned.
+pub struct Sender<T: Send>(Arc<Shared<T>>);
+
+impl<T: Send> Sender<T> {
+	/// Sends message, waits for a free space in the queue.
+	pub fn send(&self, msg: T) -> Result<(), T> { self.0.put(msg, WAIT_FOREVER) }
+
+	/// Sends message without waiting, returns it back if the queue is full.
+	///
+	/// Can be called from ISR.
+	pub fn try_send(&self, msg: T) -> Result<(), T> { self.0.put(msg, 0) }
+
+	/// Sends message, waits for a free space in the queue up to `timeout`.
+	pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), T> { self.0.put(msg, ticks(timeout)) }
+}
+
+impl<T: Send> Clone for Sender<T> {
+	fn clone(&self) -> Self {
+		self.0.senders.fetch_add(1, Ordering::Relaxed);
+		Self(self.0.clone())
+	}
+}
+
+impl<T: Send> Drop for Sender<T> {
+	fn drop(&mut self) {
+		if self.0.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+			// wakes the blocked receiver, if the queue is full the receiver isn't blocked:
+			self.0.queue.put(None, 0).ok();
+		}
+	}
+}
+
+
+/// Receiving half of the [`channel`].
+pub struct Receiver<T: Send>(Arc<Shared<T>>);
+
+impl<T: Send> Receiver<T> {
+	/// Waits for the next message, returns `None` if the channel is empty and all senders are dropped.
+	pub fn recv(&self) -> Option<T> {
+		loop {
+			if self.is_disconnected() {
+				// messages sent before the last sender was dropped are already in the queue:
+				return self.0.get(0);
+			}
+			// woken up by a message or by the last dropped sender:
+			if let Some(msg) = self.0.get(WAIT_FOREVER) {
+				return Some(msg);
+			}
+		}
+	}
+
+	/// Waits for the next message, returns `None` on timeout.
+	pub fn recv_timeout(&self, timeout: Duration) -> Option<T> { self.0.get(ticks(timeout)) }
+
+	/// Returns the next message if there is one.
+	///
+	/// Can be called from ISR.
+	pub fn try_recv(&self) -> Option<T> { self.0.get(0) }
+
+	/// Iterator over messages received until the queue is empty.
+	pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ { core::iter::from_fn(|| self.try_recv()) }
+
+	/// Iterator over messages, same as calling [`recv`](Receiver::recv) in the loop,
+	/// ends when the channel is empty and all senders are dropped.
+	pub fn iter(&self) -> impl Iterator<Item = T> + '_ { core::iter::from_fn(|| self.recv()) }
+
+	/// Checks whether all senders are dropped, there still can be messages in the queue.
+	pub fn is_disconnected(&self) -> bool { self.0.is_disconnected() }
+
+	/// Number of messages in the queue, after disconnection it can include the wake-up item.
+	pub fn len(&self) -> usize { self.0.queue.len() }
+
+	pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+	pub fn capacity(&self) -> usize { self.0.queue.capacity() }
+}
+
+
+/// Typed `FuriMessageQueue`, values are moved through the queue by bytes.
+///
+/// Zero-sized values are sent as a dummy byte because the queue can't have empty items.
+pub(crate) struct Queue<T>(NonNull<ffi::FuriMessageQueue>, PhantomData<T>);
+
+impl<T> Queue<T> {
+	pub(crate) fn new(capacity: u32) -> Result<Self, NullPointerError> {
+		let size = size_of::<T>().max(1) as u32;
+		let raw = unsafe { ffi::furi_message_queue_alloc(capacity, size) };
+		NonNull::new(raw).map(|raw| Self(raw, PhantomData)).ok_or(NullPointerError)
+	}
+
+	pub(crate) fn put(&self, msg: T, timeout: u32) -> Result<(), T> {
+		let msg = ManuallyDrop::new(msg);
+		let dummy = 0_u8;
+		let ptr = if size_of::<T>() == 0 {
+			&dummy as *const u8 as *const c_void
+		} else {
+			&*msg as *const T as *const c_void
+		};
+		match unsafe { ffi::furi_message_queue_put(self.0.as_ptr(), ptr, timeout) } {
+			ffi::FuriStatus::FuriStatusOk => Ok(()),
+			_ => Err(ManuallyDrop::into_inner(msg)),
+		}
+	}
+
+	pub(crate) fn get(&self, timeout: u32) -> Option<T> {
+		let mut msg = MaybeUninit::<T>::uninit();
+		let mut dummy = 0_u8;
+		let ptr = if size_of::<T>() == 0 {
+			&mut dummy as *mut u8 as *mut c_void
+		} else {
+			msg.as_mut_ptr() as *mut c_void
+		};
+		match unsafe { ffi::furi_message_queue_get(self.0.as_ptr(), ptr, timeout) } {
+			// zero-sized value has no bytes to initialize, and it was sent, so the type is inhabited:
+			ffi::FuriStatus::FuriStatusOk => Some(unsafe { msg.assume_init() }),
+			_ => None,
+		}
+	}
+
+	pub(crate) fn len(&self) -> usize { unsafe { ffi::furi_message_queue_get_count(self.0.as_ptr()) as usize } }
+
+	pub(crate) fn capacity(&self) -> usize { unsafe { ffi::furi_message_queue_get_capacity(self.0.as_ptr()) as usize } }
+}
+
+impl<T> Drop for Queue<T> {
+	fn drop(&mut self) {
+		// drop messages remaining in the queue:
+		while self.get(0).is_some() {}
+		unsafe { ffi::furi_message_queue_free(self.0.as_ptr()) }
+	}
+}
+
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Send> Sync for Queue<T> {}
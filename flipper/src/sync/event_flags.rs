@@ -0,0 +1,74 @@
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::time::timeout_ticks;
+use super::{Error, Result};
+
+
+/// `FuriFlagWaitAny`
+pub(crate) const WAIT_ANY: u32 = 0;
+/// `FuriFlagWaitAll`
+pub(crate) const WAIT_ALL: u32 = 1;
+/// `FuriFlagError`, set in all error codes.
+const ERROR: u32 = 0x8000_0000;
+
+
+/// Interprets result of `furi_*_flag*` functions, error codes are negated `FuriStatus`.
+pub(crate) fn check(result: u32) -> Result<u32> {
+	if result & ERROR == 0 {
+		return Ok(result);
+	}
+	match result as i32 {
+		-2 => Err(Error::Timeout),
+		-3 => Err(Error::Resource),
+		-4 => Err(Error::Parameter),
+		-6 => Err(Error::ISR),
+		_ => Err(Error::Error),
+	}
+}
+
+
+/// Set of 24 event flags, threads can wait for them.
+///
+/// Flags can be set and cleared from ISR.
+pub struct EventFlags(NonNull<ffi::FuriEventFlag>);
+
+impl EventFlags {
+	pub fn new() -> core::result::Result<Self, NullPointerError> {
+		NonNull::new(unsafe { ffi::furi_event_flag_alloc() }).map(Self)
+		                                                     .ok_or(NullPointerError)
+	}
+
+	/// Sets `flags`, returns flags after setting.
+	pub fn set(&self, flags: u32) -> Result<u32> { check(unsafe { ffi::furi_event_flag_set(self.as_ptr(), flags) }) }
+
+	/// Clears `flags`, returns flags before clearing.
+	pub fn clear(&self, flags: u32) -> Result<u32> { check(unsafe { ffi::furi_event_flag_clear(self.as_ptr(), flags) }) }
+
+	pub fn get(&self) -> u32 { unsafe { ffi::furi_event_flag_get(self.as_ptr()) } }
+
+	/// Waits for any of `flags`, `None` waits forever.
+	///
+	/// Returns flags before clearing, awaited flags are cleared.
+	pub fn wait_any(&self, flags: u32, timeout: Option<Duration>) -> Result<u32> { self.wait(flags, WAIT_ANY, timeout) }
+
+	/// Waits for all of `flags`, `None` waits forever.
+	///
+	/// Returns flags before clearing, awaited flags are cleared.
+	pub fn wait_all(&self, flags: u32, timeout: Option<Duration>) -> Result<u32> { self.wait(flags, WAIT_ALL, timeout) }
+
+	fn wait(&self, flags: u32, options: u32, timeout: Option<Duration>) -> Result<u32> {
+		check(unsafe { ffi::furi_event_flag_wait(self.as_ptr(), flags, options, timeout_ticks(timeout)) })
+	}
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriEventFlag { self.0.as_ptr() }
+}
+
+impl Drop for EventFlags {
+	fn drop(&mut self) { unsafe { ffi::furi_event_flag_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for EventFlags {}
+unsafe impl Sync for EventFlags {}
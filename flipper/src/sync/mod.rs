@@ -0,0 +1,131 @@
+//! Synchronization primitives and channels over the Furi kernel objects.
+//!
+//! Errors are reported as [`Error`], the same as `FuriStatus` of the firmware.
+//!
+//! ```ignore
+//! let counter = Arc::new(Mutex::new(0)?);
+//! let handle = {
+//!     let counter = counter.clone();
+//!     thread::spawn(move || *counter.lock().unwrap() += 1)
+//! };
+//! handle.join();
+//! assert_eq!(1, *counter.lock()?);
+//! ```
+
+pub use sys::error::furi::Error;
+pub use mutex::{Mutex, MutexGuard, RecursiveMutex, RecursiveMutexGuard};
+pub use semaphore::Semaphore;
+pub use event_flags::EventFlags;
+pub use channel::{channel, box_channel, Sender, Receiver, BoxSender, BoxReceiver};
+
+
+pub mod mutex;
+pub mod semaphore;
+pub mod event_flags;
+pub mod channel;
+
+
+pub type Result<T, E = Error> = sys::result::Result<T, E>;
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use core::time::Duration;
+	use crate::alloc::boxed::Box;
+	use crate::alloc::sync::Arc;
+	use crate::thread;
+	use super::*;
+
+	#[test]
+	fn mutex() {
+		let counter = Arc::new(Mutex::new(0).unwrap());
+		let handles: [_; 4] = core::array::from_fn(|_| {
+			let counter = counter.clone();
+			thread::spawn(move || {
+				for _ in 0..100 {
+					*counter.lock().unwrap() += 1;
+				}
+			})
+		});
+		handles.into_iter().for_each(thread::JoinHandle::join);
+		assert_eq!(400, *counter.lock().unwrap());
+
+		let guard = counter.lock().unwrap();
+		let other = counter.clone();
+		assert!(thread::spawn(move || matches!(other.try_lock(), Err(Error::Resource))).join());
+		drop(guard);
+
+		let mutex = RecursiveMutex::new(1).unwrap();
+		let outer = mutex.lock().unwrap();
+		let inner = mutex.try_lock().unwrap();
+		assert_eq!(*outer, *inner);
+	}
+
+	#[test]
+	fn semaphore() {
+		let semaphore = Semaphore::new(2, 1).unwrap();
+		semaphore.try_acquire().unwrap();
+		assert!(matches!(semaphore.try_acquire(), Err(Error::Resource)));
+		assert!(matches!(semaphore.acquire_timeout(Duration::from_millis(1)), Err(Error::Timeout)));
+		semaphore.release().unwrap();
+		semaphore.release().unwrap();
+		assert!(matches!(semaphore.release(), Err(Error::Resource)));
+		assert_eq!(2, semaphore.count());
+	}
+
+	#[test]
+	fn event_flags() {
+		let flags = Arc::new(EventFlags::new().unwrap());
+		let setter = flags.clone();
+		let handle = thread::spawn(move || setter.set(0b11).unwrap());
+
+		assert_eq!(0b11, flags.wait_all(0b11, None).unwrap() & 0b11);
+		handle.join();
+		assert_eq!(0, flags.get());
+		assert!(matches!(flags.wait_any(0b1, Some(Duration::ZERO)), Err(Error::Resource)));
+	}
+
+	#[test]
+	fn channels() {
+		let (tx, rx) = channel::<u32>(2).unwrap();
+		let handle = thread::spawn(move || (0..10).all(|i| tx.send(i).is_ok()));
+		assert_eq!(45, rx.iter().take(10).sum::<u32>());
+		assert!(handle.join());
+		assert_eq!(None, rx.recv_timeout(Duration::from_millis(1)));
+
+		let (tx, rx) = channel::<u32>(4).unwrap();
+		let handle = thread::spawn(move || (0..3).all(|i| tx.send(i).is_ok()));
+		assert_eq!(3, rx.iter().sum::<u32>());
+		assert!(handle.join());
+		assert!(rx.is_disconnected());
+		assert_eq!(None, rx.recv());
+
+		// blocked receiver is woken up by the last dropped sender:
+		let (tx, rx) = channel::<u32>(1).unwrap();
+		let tx2 = tx.clone();
+		let handle = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(10));
+			drop(tx2);
+			thread::sleep(Duration::from_millis(10));
+			drop(tx);
+		});
+		assert_eq!(None, rx.recv());
+		handle.join();
+
+		let (tx, rx) = channel::<()>(2).unwrap();
+		tx.send(()).unwrap();
+		tx.try_send(()).unwrap();
+		assert_eq!(Err(()), tx.try_send(()));
+		assert_eq!(Some(()), rx.recv());
+		assert_eq!(Some(()), rx.try_recv());
+		assert_eq!(None, rx.try_recv());
+
+		let (tx, rx) = box_channel::<crate::alloc::string::String>(1).unwrap();
+		tx.try_send(Box::new("first".into())).unwrap();
+		assert_eq!("second", *tx.try_send(Box::new("second".into())).unwrap_err());
+		assert_eq!(1, rx.len());
+		assert_eq!("first", *rx.try_recv().unwrap());
+		assert!(rx.is_empty());
+		tx.try_send(Box::new("dropped with the queue".into())).unwrap();
+	}
+}
@@ -0,0 +1,177 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::thread::Thread;
+use crate::time::{ticks, WAIT_FOREVER};
+use super::Result;
+
+
+/// Owned `FuriMutex`.
+struct RawMutex(NonNull<ffi::FuriMutex>);
+
+impl RawMutex {
+	fn new(kind: ffi::FuriMutexType) -> core::result::Result<Self, NullPointerError> {
+		NonNull::new(unsafe { ffi::furi_mutex_alloc(kind) }).map(Self)
+		                                                    .ok_or(NullPointerError)
+	}
+
+	fn acquire(&self, timeout: u32) -> Result<()> {
+		unsafe { ffi::furi_mutex_acquire(self.0.as_ptr(), timeout) }?;
+		Ok(())
+	}
+
+	fn release(&self) -> Result<()> {
+		unsafe { ffi::furi_mutex_release(self.0.as_ptr()) }?;
+		Ok(())
+	}
+
+	fn owner(&self) -> Option<Thread> {
+		let id = unsafe { ffi::furi_mutex_get_owner(self.0.as_ptr()) };
+		(!id.is_null()).then(|| Thread::from_id(id))
+	}
+}
+
+impl Drop for RawMutex {
+	fn drop(&mut self) { unsafe { ffi::furi_mutex_free(self.0.as_ptr()) } }
+}
+
+unsafe impl Send for RawMutex {}
+unsafe impl Sync for RawMutex {}
+
+
+/// Mutual exclusion primitive protecting the data, like `std::sync::Mutex`.
+///
+/// Can't be used from ISR.
+pub struct Mutex<T: ?Sized> {
+	raw: RawMutex,
+	data: UnsafeCell<T>,
+}
+
+impl<T> Mutex<T> {
+	pub fn new(value: T) -> core::result::Result<Self, NullPointerError> {
+		Ok(Self { raw: RawMutex::new(ffi::FuriMutexType::FuriMutexTypeNormal)?,
+		          data: UnsafeCell::new(value) })
+	}
+
+	pub fn into_inner(self) -> T { self.data.into_inner() }
+}
+
+impl<T: ?Sized> Mutex<T> {
+	/// Acquires the mutex, blocks until it is available.
+	pub fn lock(&self) -> Result<MutexGuard<'_, T>> { self.lock_ticks(WAIT_FOREVER) }
+
+	/// Acquires the mutex without blocking, [`Error::Resource`](super::Error::Resource) if it is locked.
+	pub fn try_lock(&self) -> Result<MutexGuard<'_, T>> { self.lock_ticks(0) }
+
+	/// Acquires the mutex, blocks up to `timeout`.
+	pub fn lock_timeout(&self, timeout: Duration) -> Result<MutexGuard<'_, T>> { self.lock_ticks(ticks(timeout)) }
+
+	fn lock_ticks(&self, timeout: u32) -> Result<MutexGuard<'_, T>> {
+		self.raw.acquire(timeout)?;
+		Ok(MutexGuard(self, PhantomData))
+	}
+
+	/// Thread holding the mutex, if it is locked.
+	pub fn owner(&self) -> Option<Thread> { self.raw.owner() }
+
+	/// Mutable access without locking, statically guaranteed to be exclusive.
+	pub fn get_mut(&mut self) -> &mut T { self.data.get_mut() }
+}
+
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T: ?Sized + core::fmt::Debug> core::fmt::Debug for Mutex<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let mut d = f.debug_struct("Mutex");
+		match self.try_lock() {
+			Ok(guard) => d.field("data", &&*guard),
+			Err(_) => d.field("data", &format_args!("<locked>")),
+		};
+		d.finish_non_exhaustive()
+	}
+}
+
+
+/// Scoped lock of the [`Mutex`], released on drop.
+///
+/// It is not `Send`, because the mutex must be released by the thread which locked it.
+#[must_use = "if unused the Mutex will immediately unlock"]
+pub struct MutexGuard<'a, T: ?Sized>(&'a Mutex<T>, PhantomData<*const ()>);
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+	type Target = T;
+	fn deref(&self) -> &T { unsafe { &*self.0.data.get() } }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+	fn deref_mut(&mut self) -> &mut T { unsafe { &mut *self.0.data.get() } }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+	fn drop(&mut self) { self.0.raw.release().ok(); }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+
+
+/// Mutex which can be locked again by the thread already holding it.
+///
+/// Gives only shared access to the data, use `Cell` or `RefCell` for mutation.
+pub struct RecursiveMutex<T: ?Sized> {
+	raw: RawMutex,
+	data: T,
+}
+
+impl<T> RecursiveMutex<T> {
+	pub fn new(value: T) -> core::result::Result<Self, NullPointerError> {
+		Ok(Self { raw: RawMutex::new(ffi::FuriMutexType::FuriMutexTypeRecursive)?,
+		          data: value })
+	}
+
+	pub fn into_inner(self) -> T { self.data }
+}
+
+impl<T: ?Sized> RecursiveMutex<T> {
+	/// Acquires the mutex, blocks until it is available.
+	pub fn lock(&self) -> Result<RecursiveMutexGuard<'_, T>> { self.lock_ticks(WAIT_FOREVER) }
+
+	/// Acquires the mutex without blocking, [`Error::Resource`](super::Error::Resource) if it is locked by other thread.
+	pub fn try_lock(&self) -> Result<RecursiveMutexGuard<'_, T>> { self.lock_ticks(0) }
+
+	/// Acquires the mutex, blocks up to `timeout`.
+	pub fn lock_timeout(&self, timeout: Duration) -> Result<RecursiveMutexGuard<'_, T>> { self.lock_ticks(ticks(timeout)) }
+
+	fn lock_ticks(&self, timeout: u32) -> Result<RecursiveMutexGuard<'_, T>> {
+		self.raw.acquire(timeout)?;
+		Ok(RecursiveMutexGuard(self, PhantomData))
+	}
+
+	/// Thread holding the mutex, if it is locked.
+	pub fn owner(&self) -> Option<Thread> { self.raw.owner() }
+
+	pub fn get_mut(&mut self) -> &mut T { &mut self.data }
+}
+
+unsafe impl<T: ?Sized + Send> Send for RecursiveMutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for RecursiveMutex<T> {}
+
+
+/// Scoped lock of the [`RecursiveMutex`], released on drop.
+#[must_use = "if unused the RecursiveMutex will immediately unlock"]
+pub struct RecursiveMutexGuard<'a, T: ?Sized>(&'a RecursiveMutex<T>, PhantomData<*const ()>);
+
+impl<T: ?Sized> Deref for RecursiveMutexGuard<'_, T> {
+	type Target = T;
+	fn deref(&self) -> &T { &self.0.data }
+}
+
+impl<T: ?Sized> Drop for RecursiveMutexGuard<'_, T> {
+	fn drop(&mut self) { self.0.raw.release().ok(); }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for RecursiveMutexGuard<'_, T> {}
@@ -0,0 +1,53 @@
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use crate::error::NullPointerError;
+use crate::time::{ticks, WAIT_FOREVER};
+use super::Result;
+
+
+/// Counting semaphore.
+///
+/// [`release`](Semaphore::release) and non-blocking [`try_acquire`](Semaphore::try_acquire) can be called from ISR.
+pub struct Semaphore(NonNull<ffi::FuriSemaphore>);
+
+impl Semaphore {
+	/// Creates semaphore with `initial` of `max` available permits.
+	pub fn new(max: u32, initial: u32) -> core::result::Result<Self, NullPointerError> {
+		NonNull::new(unsafe { ffi::furi_semaphore_alloc(max, initial) }).map(Self)
+		                                                                .ok_or(NullPointerError)
+	}
+
+	/// Takes a permit, blocks until it is available.
+	pub fn acquire(&self) -> Result<()> { self.acquire_ticks(WAIT_FOREVER) }
+
+	/// Takes a permit without blocking, [`Error::Resource`](super::Error::Resource) if there is no one.
+	pub fn try_acquire(&self) -> Result<()> { self.acquire_ticks(0) }
+
+	/// Takes a permit, blocks up to `timeout`.
+	pub fn acquire_timeout(&self, timeout: Duration) -> Result<()> { self.acquire_ticks(ticks(timeout)) }
+
+	fn acquire_ticks(&self, timeout: u32) -> Result<()> {
+		unsafe { ffi::furi_semaphore_acquire(self.as_ptr(), timeout) }?;
+		Ok(())
+	}
+
+	/// Gives a permit back, [`Error::Resource`](super::Error::Resource) if all permits are available.
+	pub fn release(&self) -> Result<()> {
+		unsafe { ffi::furi_semaphore_release(self.as_ptr()) }?;
+		Ok(())
+	}
+
+	/// Number of available permits.
+	pub fn count(&self) -> u32 { unsafe { ffi::furi_semaphore_get_count(self.as_ptr()) } }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriSemaphore { self.0.as_ptr() }
+}
+
+impl Drop for Semaphore {
+	fn drop(&mut self) { unsafe { ffi::furi_semaphore_free(self.as_ptr()) } }
+}
+
+unsafe impl Send for Semaphore {}
+unsafe impl Sync for Semaphore {}
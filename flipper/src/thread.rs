@@ -0,0 +1,257 @@
+//! Threads, `std::thread`-like API over `FuriThread`.
+//!
+//! ```ignore
+//! let handle = thread::Builder::new().name("worker")
+//!                                    .stack_size(4096)
+//!                                    .spawn(|| 40 + 2)?;
+//! assert_eq!(42, handle.join());
+//! ```
+
+use core::ffi::{c_void, CStr};
+use core::ptr::NonNull;
+use core::time::Duration;
+use sys::ffi;
+use sys::alloc::ffi::CString;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+use crate::string::IntoCString;
+use crate::sync::{self, event_flags};
+use crate::time::timeout_ticks;
+
+
+/// Stack size of spawned threads by default, in bytes.
+pub const DEFAULT_STACK_SIZE: usize = 2048;
+
+
+/// Thread factory to configure the thread before spawning.
+#[derive(Debug, Default)]
+pub struct Builder {
+	name: Option<CString>,
+	stack_size: Option<usize>,
+	priority: Option<Priority>,
+}
+
+impl Builder {
+	pub fn new() -> Self { Self::default() }
+
+	/// Name of the thread, truncated at the first nul.
+	pub fn name<S: IntoCString>(mut self, name: S) -> Self {
+		self.name = Some(name.into_c_string());
+		self
+	}
+
+	/// Stack size in bytes, [`DEFAULT_STACK_SIZE`] by default.
+	pub fn stack_size(mut self, size: usize) -> Self {
+		self.stack_size = Some(size);
+		self
+	}
+
+	pub fn priority(mut self, priority: Priority) -> Self {
+		self.priority = Some(priority);
+		self
+	}
+
+	/// Spawns new thread running `f`.
+	pub fn spawn<F, T>(self, f: F) -> Result<JoinHandle<T>, NullPointerError>
+		where F: FnOnce() -> T + Send + 'static,
+		      T: Send + 'static {
+		let raw = NonNull::new(unsafe { ffi::furi_thread_alloc() }).ok_or(NullPointerError)?;
+		let mut packet = Box::new(Packet::<T> { f: Some(Box::new(f)),
+		                                        result: None });
+
+		unsafe {
+			if let Some(name) = &self.name {
+				ffi::furi_thread_set_name(raw.as_ptr(), name.as_ptr());
+			}
+			ffi::furi_thread_set_stack_size(raw.as_ptr(), self.stack_size.unwrap_or(DEFAULT_STACK_SIZE));
+			if let Some(priority) = self.priority {
+				ffi::furi_thread_set_priority(raw.as_ptr(), priority.into());
+			}
+			let context = packet.as_mut() as *mut Packet<T> as *mut c_void;
+			ffi::furi_thread_set_context(raw.as_ptr(), context);
+			ffi::furi_thread_set_callback(raw.as_ptr(), Some(thread_proxy::<T>));
+			ffi::furi_thread_start(raw.as_ptr());
+		}
+
+		Ok(JoinHandle { raw,
+		                packet,
+		                joined: false })
+	}
+}
+
+
+/// Spawns new thread with default [`Builder`].
+///
+/// # Panics
+/// If the thread can't be allocated.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+	where F: FnOnce() -> T + Send + 'static,
+	      T: Send + 'static {
+	Builder::new().spawn(f).expect("failed to spawn thread")
+}
+
+
+type ThreadFn<T> = dyn FnOnce() -> T + Send;
+
+struct Packet<T> {
+	f: Option<Box<ThreadFn<T>>>,
+	result: Option<T>,
+}
+
+unsafe extern "C" fn thread_proxy<T>(context: *mut c_void) -> i32 {
+	let packet = &mut *(context as *mut Packet<T>);
+	if let Some(f) = packet.f.take() {
+		packet.result = Some(f());
+	}
+	0
+}
+
+
+/// Owned permission to join the thread.
+///
+/// Unlike `std`, dropping the handle doesn't detach the thread but waits for it,
+/// because the thread can be freed only when it is stopped.
+pub struct JoinHandle<T> {
+	raw: NonNull<ffi::FuriThread>,
+	packet: Box<Packet<T>>,
+	joined: bool,
+}
+
+impl<T> JoinHandle<T> {
+	/// Waits for the thread to finish and returns its result.
+	pub fn join(mut self) -> T {
+		self.wait();
+		self.packet.result.take().expect("thread finished without result")
+	}
+
+	/// Returns `true` if the thread has finished running.
+	pub fn is_finished(&self) -> bool {
+		unsafe { ffi::furi_thread_get_state(self.raw.as_ptr()) == ffi::FuriThreadState::FuriThreadStateStopped }
+	}
+
+	pub fn thread(&self) -> Thread { Thread(unsafe { ffi::furi_thread_get_id(self.raw.as_ptr()) }) }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriThread { self.raw.as_ptr() }
+
+	fn wait(&mut self) {
+		if !self.joined {
+			unsafe { ffi::furi_thread_join(self.raw.as_ptr()) };
+			self.joined = true;
+		}
+	}
+}
+
+impl<T> Drop for JoinHandle<T> {
+	fn drop(&mut self) {
+		self.wait();
+		unsafe { ffi::furi_thread_free(self.raw.as_ptr()) }
+	}
+}
+
+unsafe impl<T: Send> Send for JoinHandle<T> {}
+unsafe impl<T: Send> Sync for JoinHandle<T> {}
+
+
+/// Handle of a running thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thread(ffi::FuriThreadId);
+
+impl Thread {
+	pub(crate) fn from_id(id: ffi::FuriThreadId) -> Self { Self(id) }
+
+	#[inline]
+	pub fn id(&self) -> ffi::FuriThreadId { self.0 }
+
+	/// Name of the thread, if it has one.
+	pub fn name(&self) -> Option<&CStr> { unsafe { ffi::furi_thread_get_name(self.0).as_ref().map(|name| CStr::from_ptr(name)) } }
+
+	/// Sets `flags` of the thread, returns flags after setting.
+	///
+	/// Can be called from ISR.
+	pub fn set_flags(&self, flags: u32) -> sync::Result<u32> {
+		event_flags::check(unsafe { ffi::furi_thread_flags_set(self.0, flags) })
+	}
+}
+
+unsafe impl Send for Thread {}
+unsafe impl Sync for Thread {}
+
+
+/// Returns handle of the current thread.
+pub fn current() -> Thread { Thread(unsafe { ffi::furi_thread_get_current_id() }) }
+
+/// Gives up the rest of the time slice to other threads.
+pub fn yield_now() { unsafe { ffi::furi_thread_yield() } }
+
+/// Puts the current thread to sleep for at least `duration`, same as [`time::sleep`](crate::time::sleep).
+#[inline]
+pub fn sleep(duration: Duration) { crate::time::sleep(duration) }
+
+
+/// Returns flags of the current thread.
+pub fn flags() -> u32 { unsafe { ffi::furi_thread_flags_get() } }
+
+/// Clears `flags` of the current thread, returns flags before clearing.
+pub fn clear_flags(flags: u32) -> sync::Result<u32> { event_flags::check(unsafe { ffi::furi_thread_flags_clear(flags) }) }
+
+/// Waits for any of `flags` of the current thread, `None` waits forever.
+///
+/// Returns flags before clearing, awaited flags are cleared.
+pub fn wait_any_flags(flags: u32, timeout: Option<Duration>) -> sync::Result<u32> {
+	wait_flags(flags, event_flags::WAIT_ANY, timeout)
+}
+
+/// Waits for all of `flags` of the current thread, `None` waits forever.
+///
+/// Returns flags before clearing, awaited flags are cleared.
+pub fn wait_all_flags(flags: u32, timeout: Option<Duration>) -> sync::Result<u32> {
+	wait_flags(flags, event_flags::WAIT_ALL, timeout)
+}
+
+fn wait_flags(flags: u32, options: u32, timeout: Option<Duration>) -> sync::Result<u32> {
+	event_flags::check(unsafe { ffi::furi_thread_flags_wait(flags, options, timeout_ticks(timeout)) })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Priority {
+	Idle,
+	Lowest,
+	Low,
+	#[default]
+	Normal,
+	High,
+	Highest,
+	/// Deferred ISR, the highest possible.
+	Isr,
+}
+
+impl From<Priority> for ffi::FuriThreadPriority {
+	fn from(priority: Priority) -> Self {
+		match priority {
+			Priority::Idle => ffi::FuriThreadPriority::FuriThreadPriorityIdle,
+			Priority::Lowest => ffi::FuriThreadPriority::FuriThreadPriorityLowest,
+			Priority::Low => ffi::FuriThreadPriority::FuriThreadPriorityLow,
+			Priority::Normal => ffi::FuriThreadPriority::FuriThreadPriorityNormal,
+			Priority::High => ffi::FuriThreadPriority::FuriThreadPriorityHigh,
+			Priority::Highest => ffi::FuriThreadPriority::FuriThreadPriorityHighest,
+			Priority::Isr => ffi::FuriThreadPriority::FuriThreadPriorityIsr,
+		}
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spawn_join() {
+		let handle = Builder::new().name("worker").spawn(|| 40 + 2).unwrap();
+		assert_eq!(42, handle.join());
+
+		let data = crate::alloc::vec![1, 2, 3];
+		let handle = spawn(move || data.iter().sum::<i32>());
+		assert_eq!(6, handle.join());
+	}
+}
@@ -0,0 +1,220 @@
+//! Time: kernel ticks based [`Instant`], [`Timer`] with closure callback and delays.
+//!
+//! ```ignore
+//! let start = Instant::now();
+//! let timer = Timer::periodic(move || println!("{:?}", start.elapsed()))?;
+//! timer.start(Duration::from_millis(500))?;
+//! time::sleep(Duration::from_secs(2));
+//! ```
+
+use core::ffi::c_void;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+use sys::ffi;
+use sys::error::furi::Error;
+use crate::alloc::boxed::Box;
+use crate::error::NullPointerError;
+
+
+/// `FuriWaitForever`
+pub(crate) const WAIT_FOREVER: u32 = u32::MAX;
+
+
+/// Converts `duration` to kernel ticks rounding up to whole milliseconds, saturates below [`WAIT_FOREVER`].
+pub(crate) fn ticks(duration: Duration) -> u32 {
+	let ms = ((duration.as_micros() + 999) / 1000).min(WAIT_FOREVER as u128 - 1) as u32;
+	unsafe { ffi::furi_ms_to_ticks(ms) }
+}
+
+/// Converts optional `timeout` to kernel ticks, `None` means [`WAIT_FOREVER`].
+pub(crate) fn timeout_ticks(timeout: Option<Duration>) -> u32 { timeout.map_or(WAIT_FOREVER, ticks) }
+
+/// Converts kernel `ticks` to duration.
+fn duration(ticks: u32) -> Duration {
+	let frequency = unsafe { ffi::furi_kernel_get_tick_frequency() }.max(1);
+	Duration::from_micros(ticks as u64 * 1_000_000 / frequency as u64)
+}
+
+
+/// Puts the current thread to sleep for at least `duration`, with precision of the kernel tick.
+pub fn sleep(duration: Duration) { unsafe { ffi::furi_delay_tick(ticks(duration)) } }
+
+/// Busy-waits for `us` microseconds, doesn't yield to other threads.
+pub fn delay_us(us: u32) { unsafe { ffi::furi_delay_us(us) } }
+
+
+/// Point in time measured in kernel ticks.
+///
+/// Tick counter wraps around in about 49 days (at 1 kHz), so instants are not ordered,
+/// but durations between them are correct if they are less than that period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instant(u32);
+
+impl Instant {
+	pub fn now() -> Self { Self(unsafe { ffi::furi_get_tick() }) }
+
+	/// Value of the tick counter.
+	#[inline]
+	pub fn ticks(&self) -> u32 { self.0 }
+
+	/// Time passed from `earlier` to `self`.
+	pub fn duration_since(&self, earlier: Instant) -> Duration { duration(self.0.wrapping_sub(earlier.0)) }
+
+	/// Time passed since `self`.
+	pub fn elapsed(&self) -> Duration { Instant::now().duration_since(*self) }
+}
+
+impl Add<Duration> for Instant {
+	type Output = Instant;
+	fn add(self, rhs: Duration) -> Instant { Instant(self.0.wrapping_add(ticks(rhs))) }
+}
+
+impl AddAssign<Duration> for Instant {
+	fn add_assign(&mut self, rhs: Duration) { *self = *self + rhs }
+}
+
+impl Sub<Duration> for Instant {
+	type Output = Instant;
+	fn sub(self, rhs: Duration) -> Instant { Instant(self.0.wrapping_sub(ticks(rhs))) }
+}
+
+impl SubAssign<Duration> for Instant {
+	fn sub_assign(&mut self, rhs: Duration) { *self = *self - rhs }
+}
+
+impl Sub<Instant> for Instant {
+	type Output = Duration;
+	fn sub(self, rhs: Instant) -> Duration { self.duration_since(rhs) }
+}
+
+
+type TimerFn = dyn FnMut() + Send;
+
+
+/// Software timer calling the closure in the timer service thread.
+///
+/// The closure is dropped with the timer.
+pub struct Timer {
+	raw: NonNull<ffi::FuriTimer>,
+	period: AtomicU32,
+	_callback: Box<Box<TimerFn>>,
+}
+
+impl Timer {
+	pub fn new<F: FnMut() + Send + 'static>(kind: TimerType, f: F) -> Result<Self, NullPointerError> {
+		let mut callback: Box<Box<TimerFn>> = Box::new(Box::new(f));
+		let context = callback.as_mut() as *mut Box<TimerFn> as *mut c_void;
+		let raw = unsafe { ffi::furi_timer_alloc(Some(timer_proxy), kind.into(), context) };
+		NonNull::new(raw).map(|raw| {
+			                 Self { raw,
+			                        period: AtomicU32::new(0),
+			                        _callback: callback }
+		                 })
+		                 .ok_or(NullPointerError)
+	}
+
+	/// Creates timer calling `f` once after each start.
+	pub fn once<F: FnMut() + Send + 'static>(f: F) -> Result<Self, NullPointerError> { Self::new(TimerType::Once, f) }
+
+	/// Creates timer calling `f` every period while it is running.
+	pub fn periodic<F: FnMut() + Send + 'static>(f: F) -> Result<Self, NullPointerError> { Self::new(TimerType::Periodic, f) }
+
+	/// Starts the timer with specified `period`, restarts if it is already running.
+	pub fn start(&self, period: Duration) -> Result<(), Error> {
+		let ticks = ticks(period).max(1);
+		self.period.store(ticks, Ordering::Relaxed);
+		unsafe { ffi::furi_timer_start(self.as_ptr(), ticks) }?;
+		Ok(())
+	}
+
+	/// Starts the timer again with the period of the last [`start`](Timer::start).
+	pub fn restart(&self) -> Result<(), Error> {
+		let ticks = self.period.load(Ordering::Relaxed);
+		if ticks == 0 {
+			return Err(Error::Parameter);
+		}
+		unsafe { ffi::furi_timer_start(self.as_ptr(), ticks) }?;
+		Ok(())
+	}
+
+	pub fn stop(&self) -> Result<(), Error> {
+		unsafe { ffi::furi_timer_stop(self.as_ptr()) }?;
+		Ok(())
+	}
+
+	pub fn is_running(&self) -> bool { unsafe { ffi::furi_timer_is_running(self.as_ptr()) != 0 } }
+
+	#[inline]
+	pub fn as_ptr(&self) -> *mut ffi::FuriTimer { self.raw.as_ptr() }
+}
+
+impl Drop for Timer {
+	fn drop(&mut self) {
+		// firmware stops the timer and waits for its deletion, so the callback can be dropped after:
+		unsafe { ffi::furi_timer_free(self.as_ptr()) }
+	}
+}
+
+unsafe impl Send for Timer {}
+unsafe impl Sync for Timer {}
+
+
+unsafe extern "C" fn timer_proxy(context: *mut c_void) {
+	let callback = &mut *(context as *mut Box<TimerFn>);
+	callback();
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerType {
+	Once,
+	Periodic,
+}
+
+impl From<TimerType> for ffi::FuriTimerType {
+	fn from(kind: TimerType) -> Self {
+		match kind {
+			TimerType::Once => ffi::FuriTimerType::FuriTimerTypeOnce,
+			TimerType::Periodic => ffi::FuriTimerType::FuriTimerTypePeriodic,
+		}
+	}
+}
+
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+	use super::*;
+	use crate::sync::channel;
+
+	#[test]
+	fn timer() {
+		let start = Instant::now();
+		sleep(Duration::from_millis(20));
+		assert!(start.elapsed() >= Duration::from_millis(20));
+
+		let (tx, rx) = channel::<u32>(8).unwrap();
+		let mut count = 0;
+		let timer = Timer::periodic(move || {
+			            count += 1;
+			            tx.try_send(count).ok();
+		            }).unwrap();
+		assert!(timer.restart().is_err());
+		timer.start(Duration::from_millis(5)).unwrap();
+		assert!(timer.is_running());
+		assert_eq!(Some(1), rx.recv_timeout(Duration::from_secs(1)));
+		assert_eq!(Some(2), rx.recv_timeout(Duration::from_secs(1)));
+		timer.stop().unwrap();
+		assert!(!timer.is_running());
+		drop(timer);
+		rx.try_iter().for_each(drop);
+		assert_eq!(None, rx.recv_timeout(Duration::from_millis(20)));
+
+		let (tx, rx) = channel::<u8>(1).unwrap();
+		let timer = Timer::once(move || tx.try_send(1).unwrap()).unwrap();
+		timer.start(Duration::from_millis(1)).unwrap();
+		assert_eq!(Some(1), rx.recv_timeout(Duration::from_secs(1)));
+		assert!(!timer.is_running());
+	}
+}
@@ -0,0 +1,262 @@
+//! Key-value text format of the Flipper Zero firmware (`FlipperFormat`) used for saved keys, signals and settings.
+//!
+//! ```
+//! use flipper_format::Document;
+//!
+//! let mut doc = Document::with_header("My App Settings", 1);
+//! doc.push("Speed", 3_u32);
+//! doc.push_hex("Key", &[0xDE, 0xAD]);
+//! assert_eq!("Filetype: My App Settings\nVersion: 1\nSpeed: 3\nKey: DE AD\n", doc.to_string());
+//!
+//! let doc: Document = "Filetype: My App Settings\nVersion: 1\nSpeed: 3\n".parse().unwrap();
+//! assert_eq!(Ok(1), doc.check_header("My App Settings", 1..=1));
+//! assert_eq!(Ok(3), doc.read::<u32>("Speed"));
+//! ```
+//!
+//! Values are untyped text, so they are parsed by the [`Value`] type requested on read.
+//! Keys can repeat, e.g. signals of the IR file, [`Reader`] reads them one after another like the firmware does.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::fmt;
+use core::ops::RangeBounds;
+use core::str::FromStr;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+pub use reader::Reader;
+pub use value::Value;
+
+
+mod reader;
+mod value;
+
+
+/// Key of the file type in the header.
+pub const FILETYPE_KEY: &str = "Filetype";
+/// Key of the version in the header.
+pub const VERSION_KEY: &str = "Version";
+
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// Line isn't a comment, blank or `Key: value`, numbered from 1.
+	Syntax { line: usize },
+	/// Key isn't found.
+	Missing(String),
+	/// Value of the key can't be parsed as requested type.
+	Invalid(String),
+	/// Header has another file type or unsupported version.
+	Header { filetype: String, version: u32 },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Syntax { line } => write!(f, "invalid syntax at line {line}"),
+			Error::Missing(key) => write!(f, "key `{key}` not found"),
+			Error::Invalid(key) => write!(f, "invalid value of key `{key}`"),
+			Error::Header { filetype, version } => write!(f, "unsupported file `{filetype}` version {version}"),
+		}
+	}
+}
+
+
+/// Line of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+	/// `Key: value`, value is the rest of the line.
+	Pair { key: String, value: String },
+	/// `# text`, text is without `#` and the following space.
+	Comment(String),
+	/// Empty line.
+	Blank,
+}
+
+impl Entry {
+	pub fn pair<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
+		Entry::Pair { key: key.into(),
+		              value: value.into() }
+	}
+
+	pub fn key(&self) -> Option<&str> {
+		match self {
+			Entry::Pair { key, .. } => Some(key),
+			_ => None,
+		}
+	}
+
+	pub fn value(&self) -> Option<&str> {
+		match self {
+			Entry::Pair { value, .. } => Some(value),
+			_ => None,
+		}
+	}
+
+	fn parse(line: &str) -> Option<Self> {
+		let line = line.strip_suffix('\r').unwrap_or(line);
+		if line.trim().is_empty() {
+			Some(Entry::Blank)
+		} else if let Some(text) = line.strip_prefix('#') {
+			Some(Entry::Comment(text.strip_prefix(' ').unwrap_or(text).to_string()))
+		} else {
+			let (key, value) = line.split_once(':')?;
+			Some(Entry::pair(key, value.trim_start()))
+		}
+	}
+}
+
+impl fmt::Display for Entry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Entry::Pair { key, value } => write!(f, "{key}: {value}"),
+			Entry::Comment(text) if text.is_empty() => write!(f, "#"),
+			Entry::Comment(text) => write!(f, "# {text}"),
+			Entry::Blank => Ok(()),
+		}
+	}
+}
+
+
+/// Parsed file, entries are kept in order with comments, so it's written back as is.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document {
+	entries: Vec<Entry>,
+}
+
+impl Document {
+	pub fn new() -> Self { Self::default() }
+
+	/// New document with the header: file type and version.
+	pub fn with_header(filetype: &str, version: u32) -> Self {
+		let mut doc = Self::new();
+		doc.push_str(FILETYPE_KEY, filetype);
+		doc.push(VERSION_KEY, version);
+		doc
+	}
+
+	pub fn parse(text: &str) -> Result<Self> {
+		let entries = text.lines()
+		                  .enumerate()
+		                  .map(|(i, line)| Entry::parse(line).ok_or(Error::Syntax { line: i + 1 }))
+		                  .collect::<Result<_>>()?;
+		Ok(Self { entries })
+	}
+
+	pub fn entries(&self) -> &[Entry] { &self.entries }
+
+	pub fn entries_mut(&mut self) -> &mut Vec<Entry> { &mut self.entries }
+
+	/// Reader from the start.
+	pub fn reader(&self) -> Reader<'_> { Reader::new(&self.entries) }
+
+
+	/// Reads the header: file type and version.
+	pub fn header(&self) -> Result<(&str, u32)> {
+		let mut reader = self.reader();
+		let filetype = reader.read_str(FILETYPE_KEY)?;
+		Ok((filetype, reader.read(VERSION_KEY)?))
+	}
+
+	/// Checks that file type is `filetype` and version is in `versions`, returns the version.
+	pub fn check_header<R: RangeBounds<u32>>(&self, filetype: &str, versions: R) -> Result<u32> {
+		match self.header()? {
+			(actual, version) if actual == filetype && versions.contains(&version) => Ok(version),
+			(actual, version) => {
+				Err(Error::Header { filetype: actual.to_string(),
+				                    version })
+			},
+		}
+	}
+
+
+	/// Value of the first `key`.
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.entries
+		    .iter()
+		    .find(|entry| entry.key() == Some(key))
+		    .and_then(Entry::value)
+	}
+
+	/// Values of all `key`s.
+	pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+		self.entries
+		    .iter()
+		    .filter(move |entry| entry.key() == Some(key))
+		    .filter_map(Entry::value)
+	}
+
+	pub fn contains_key(&self, key: &str) -> bool { self.get(key).is_some() }
+
+	/// Reads the first value of the first `key`.
+	pub fn read<T: Value>(&self, key: &str) -> Result<T> { self.reader().read(key) }
+
+	/// Reads all values of the first `key`.
+	pub fn read_array<T: Value>(&self, key: &str) -> Result<Vec<T>> { self.reader().read_array(key) }
+
+	/// Reads bytes of the first `key`, e.g. `DE AD BE EF`.
+	pub fn read_hex(&self, key: &str) -> Result<Vec<u8>> { self.read_array(key) }
+
+
+	/// Appends `key` with the `value` which must be a single line.
+	pub fn push_str(&mut self, key: &str, value: &str) { self.entries.push(Entry::pair(key, value)) }
+
+	pub fn push<T: Value>(&mut self, key: &str, value: T) { self.push_array(key, &[value]) }
+
+	pub fn push_array<T: Value>(&mut self, key: &str, values: &[T]) { self.entries.push(Entry::pair(key, value::join(values))) }
+
+	/// Appends bytes as hex, e.g. `DE AD BE EF`.
+	pub fn push_hex(&mut self, key: &str, bytes: &[u8]) { self.push_array(key, bytes) }
+
+	/// Appends comment which must be a single line.
+	pub fn push_comment(&mut self, text: &str) { self.entries.push(Entry::Comment(text.to_string())) }
+
+	/// Updates the value of the first `key` or appends it if not exists.
+	pub fn set_str(&mut self, key: &str, value: &str) {
+		let existing = self.entries.iter_mut().find(|entry| entry.key() == Some(key));
+		match existing {
+			Some(Entry::Pair { value: existing, .. }) => *existing = value.to_string(),
+			_ => self.push_str(key, value),
+		}
+	}
+
+	/// Updates the value of the first `key` or appends it if not exists.
+	pub fn set<T: Value>(&mut self, key: &str, value: T) { self.set_array(key, &[value]) }
+
+	/// Updates values of the first `key` or appends it if not exists.
+	pub fn set_array<T: Value>(&mut self, key: &str, values: &[T]) { self.set_str(key, &value::join(values)) }
+
+	/// Removes the first `key`, returns its value.
+	pub fn remove(&mut self, key: &str) -> Option<String> {
+		let index = self.entries.iter().position(|entry| entry.key() == Some(key))?;
+		match self.entries.remove(index) {
+			Entry::Pair { value, .. } => Some(value),
+			_ => None,
+		}
+	}
+}
+
+impl FromStr for Document {
+	type Err = Error;
+	fn from_str(s: &str) -> Result<Self> { Self::parse(s) }
+}
+
+/// Writes entries as the firmware does, each line ends with `\n`.
+impl fmt::Display for Document {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for entry in &self.entries {
+			writeln!(f, "{entry}")?;
+		}
+		Ok(())
+	}
+}
+
+impl From<Vec<Entry>> for Document {
+	fn from(entries: Vec<Entry>) -> Self { Self { entries } }
+}
@@ -0,0 +1,56 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use crate::{Entry, Error, Result, Value};
+
+
+/// Sequential reader of the document, each key is searched from the current position to the end.
+///
+/// After successful read the position is after the read key, otherwise it isn't changed.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+	entries: &'a [Entry],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(entries: &'a [Entry]) -> Self { Self { entries, pos: 0 } }
+
+	/// Moves to the start.
+	pub fn rewind(&mut self) { self.pos = 0 }
+
+	/// Checks whether there are no more keys.
+	pub fn is_end(&self) -> bool { self.entries[self.pos..].iter().all(|entry| entry.key().is_none()) }
+
+	/// Reads the whole value of `key`.
+	pub fn read_str(&mut self, key: &str) -> Result<&'a str> {
+		let entries = &self.entries[self.pos..];
+		let i = entries.iter()
+		               .position(|entry| entry.key() == Some(key))
+		               .ok_or_else(|| Error::Missing(key.to_string()))?;
+		self.pos += i + 1;
+		Ok(entries[i].value().unwrap_or_default())
+	}
+
+	/// Reads the first value of `key`.
+	pub fn read<T: Value>(&mut self, key: &str) -> Result<T> {
+		let pos = self.pos;
+		let value = self.read_str(key)?.split_whitespace().next().and_then(T::parse);
+		value.ok_or_else(|| {
+			     self.pos = pos;
+			     Error::Invalid(key.to_string())
+		     })
+	}
+
+	/// Reads all values of `key`.
+	pub fn read_array<T: Value>(&mut self, key: &str) -> Result<Vec<T>> {
+		let pos = self.pos;
+		let values = self.read_str(key)?.split_whitespace().map(T::parse).collect::<Option<_>>();
+		values.ok_or_else(|| {
+			      self.pos = pos;
+			      Error::Invalid(key.to_string())
+		      })
+	}
+
+	/// Reads bytes of `key`, e.g. `DE AD BE EF`.
+	pub fn read_hex(&mut self, key: &str) -> Result<Vec<u8>> { self.read_array(key) }
+}
@@ -0,0 +1,69 @@
+use core::fmt::Write;
+use alloc::string::String;
+
+
+/// Type of the values, arrays are separated by spaces.
+///
+/// Formats are the same as in the firmware:
+/// - `u32` and `i32` are decimal
+/// - `bool` is `true` or `false`
+/// - `f32` is with 6 decimal places, like `%f`
+/// - `u8` is two upper-case hex digits, so byte arrays are hex strings like `DE AD BE EF`
+pub trait Value: Sized {
+	/// Parses single value.
+	fn parse(s: &str) -> Option<Self>;
+
+	/// Appends the value to `out`.
+	fn write(&self, out: &mut String);
+}
+
+impl Value for u32 {
+	fn parse(s: &str) -> Option<Self> { s.parse().ok() }
+	fn write(&self, out: &mut String) { write!(out, "{self}").ok(); }
+}
+
+impl Value for i32 {
+	fn parse(s: &str) -> Option<Self> { s.parse().ok() }
+	fn write(&self, out: &mut String) { write!(out, "{self}").ok(); }
+}
+
+impl Value for bool {
+	fn parse(s: &str) -> Option<Self> {
+		match s {
+			"true" => Some(true),
+			"false" => Some(false),
+			_ => None,
+		}
+	}
+
+	fn write(&self, out: &mut String) { out.push_str(if *self { "true" } else { "false" }) }
+}
+
+impl Value for f32 {
+	fn parse(s: &str) -> Option<Self> { s.parse().ok() }
+	fn write(&self, out: &mut String) { write!(out, "{self:.6}").ok(); }
+}
+
+impl Value for u8 {
+	fn parse(s: &str) -> Option<Self> {
+		if s.len() == 2 {
+			u8::from_str_radix(s, 16).ok()
+		} else {
+			None
+		}
+	}
+
+	fn write(&self, out: &mut String) { write!(out, "{self:02X}").ok(); }
+}
+
+
+pub(crate) fn join<T: Value>(values: &[T]) -> String {
+	let mut out = String::new();
+	for (i, value) in values.iter().enumerate() {
+		if i > 0 {
+			out.push(' ');
+		}
+		value.write(&mut out);
+	}
+	out
+}
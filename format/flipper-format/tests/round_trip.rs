@@ -0,0 +1,115 @@
+use flipper_format::{Document, Entry, Error};
+
+
+const IR_FILE: &str = "Filetype: IR signals file
+Version: 1
+#
+# Living room TV
+name: Power
+type: parsed
+protocol: NEC
+address: 04 00 00 00
+command: 08 00 00 00
+#
+name: Vol_up
+type: raw
+frequency: 38000
+duty_cycle: 0.330000
+data: 9024 4512 579 552 579 1683
+
+";
+
+
+#[test]
+fn parse_write_same() {
+	let doc: Document = IR_FILE.parse().unwrap();
+	assert_eq!(IR_FILE, doc.to_string());
+	assert_eq!(Entry::Comment("Living room TV".to_string()), doc.entries()[3]);
+	assert_eq!(Some(&Entry::Blank), doc.entries().last());
+	assert_eq!(doc, doc.to_string().parse().unwrap());
+}
+
+#[test]
+fn write_parse_same() {
+	let mut doc = Document::with_header("My App Settings", 2);
+	doc.push_comment("generated");
+	doc.push("Speed", 300_u32);
+	doc.push("Offset", -3_i32);
+	doc.push("Enabled", true);
+	doc.push_array("Ratios", &[0.5_f32, 1.25]);
+	doc.push_hex("Key", &[0xDE, 0xAD, 0x0B]);
+	doc.push_str("Name", "my settings");
+	doc.push_array::<u32>("Empty", &[]);
+
+	let text = doc.to_string();
+	assert_eq!(
+	           "Filetype: My App Settings
+Version: 2
+# generated
+Speed: 300
+Offset: -3
+Enabled: true
+Ratios: 0.500000 1.250000
+Key: DE AD 0B
+Name: my settings
+Empty: 
+",
+	           text
+	);
+
+	let parsed = Document::parse(&text).unwrap();
+	assert_eq!(doc, parsed);
+	assert_eq!(Ok(2), parsed.check_header("My App Settings", 1..=2));
+	assert_eq!(Ok(300), parsed.read::<u32>("Speed"));
+	assert_eq!(Ok(-3), parsed.read::<i32>("Offset"));
+	assert_eq!(Ok(true), parsed.read::<bool>("Enabled"));
+	assert_eq!(Ok(vec![0.5, 1.25]), parsed.read_array::<f32>("Ratios"));
+	assert_eq!(Ok(vec![0xDE, 0xAD, 0x0B]), parsed.read_hex("Key"));
+	assert_eq!(Some("my settings"), parsed.get("Name"));
+	assert_eq!(Ok(vec![]), parsed.read_array::<u32>("Empty"));
+}
+
+#[test]
+fn reader_repeated_keys() {
+	let doc = Document::parse(IR_FILE).unwrap();
+	assert_eq!(vec!["Power", "Vol_up"], doc.get_all("name").collect::<Vec<_>>());
+
+	let mut reader = doc.reader();
+	assert_eq!(Ok("Power"), reader.read_str("name"));
+	assert_eq!(Ok(vec![4, 0, 0, 0]), reader.read_hex("address"));
+	assert_eq!(Ok("Vol_up"), reader.read_str("name"));
+	assert_eq!(Ok(38000), reader.read::<u32>("frequency"));
+	assert_eq!(Ok(0.33), reader.read::<f32>("duty_cycle"));
+	assert_eq!(Ok(vec![9024, 4512, 579, 552, 579, 1683]), reader.read_array::<u32>("data"));
+	assert!(reader.is_end());
+	assert_eq!(Err(Error::Missing("name".to_string())), reader.read_str("name"));
+
+	reader.rewind();
+	assert_eq!(Err(Error::Invalid("type".to_string())), reader.read::<u32>("type"));
+	// position isn't changed on error:
+	assert_eq!(Ok("parsed"), reader.read_str("type"));
+}
+
+#[test]
+fn edit() {
+	let mut doc = Document::parse("Filetype: Test\nVersion: 1\nA: 1\n").unwrap();
+	doc.set("A", 2_u32);
+	doc.set_array("B", &[true, false]);
+	assert_eq!("Filetype: Test\nVersion: 1\nA: 2\nB: true false\n", doc.to_string());
+	assert_eq!(Some("2".to_string()), doc.remove("A"));
+	assert_eq!(None, doc.remove("A"));
+	assert!(!doc.contains_key("A"));
+}
+
+#[test]
+fn errors() {
+	assert_eq!(Err(Error::Syntax { line: 2 }), Document::parse("A: 1\nno separator\n"));
+
+	let doc = Document::parse("Filetype: Test\r\nVersion: 3\r\nHex: 0 1\r\n").unwrap();
+	assert_eq!(Ok(("Test", 3)), doc.header());
+	let header = Error::Header { filetype: "Test".to_string(),
+	                             version: 3 };
+	assert_eq!(Err(header), doc.check_header("Test", ..3));
+	assert_eq!(Err(Error::Invalid("Hex".to_string())), doc.read_hex("Hex"));
+	assert_eq!(Err(Error::Missing("Other".to_string())), doc.read::<u32>("Other"));
+}
@@ -6,9 +6,11 @@ use core::alloc::{Allocator, AllocError};
 use core::alloc::{GlobalAlloc, Layout};
 use core::ffi::c_void;
 use core::ptr::NonNull;
+#[cfg(not(feature = "mock"))]
 use alloc::ffi::CString;
 pub use alloc::format;
 use crate::ffi::*;
+#[cfg(not(feature = "mock"))]
 use crate::os::crash;
 
 
@@ -65,7 +67,7 @@ unsafe impl Allocator for Furi {
 
 
 /// Out of Memory handler.
-#[cfg(feature = "oom-global")]
+#[cfg(all(feature = "oom-global", not(feature = "mock")))]
 #[alloc_error_handler]
 fn oom(layout: Layout) -> ! {
 	unsafe {
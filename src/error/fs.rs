@@ -93,7 +93,21 @@ pub enum Error {
 
 impl core::error::Error for Error {}
 impl core::fmt::Display for Error {
-	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { todo!("fs-error display") }
+	/// Same descriptions as `storage_error_get_desc`.
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let desc = match self {
+			Error::NotReady => "filesystem not ready",
+			Error::Exist => "file/dir already exist",
+			Error::NotExist => "file/dir not exist",
+			Error::InvalidParameter => "invalid parameter",
+			Error::Denied => "access denied",
+			Error::InvalidName => "invalid name/path",
+			Error::Internal => "internal error",
+			Error::NotImplemented => "function not implemented",
+			Error::AlreadyOpen => "file is already open",
+		};
+		f.write_str(desc)
+	}
 }
 
 
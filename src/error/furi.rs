@@ -77,5 +77,15 @@ pub enum Error {
 
 impl core::error::Error for Error {}
 impl core::fmt::Display for Error {
-	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { todo!() }
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let desc = match self {
+			Error::Error => "unspecified error",
+			Error::Timeout => "operation not completed within the timeout period",
+			Error::Resource => "resource not available",
+			Error::Parameter => "parameter error",
+			Error::NoMemory => "out of memory",
+			Error::ISR => "not allowed in ISR context",
+		};
+		f.write_str(desc)
+	}
 }
@@ -15,7 +15,7 @@ pub struct Error;
 
 impl core::error::Error for Error {}
 impl core::fmt::Display for Error {
-	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { todo!() }
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { f.write_str("operation failed") }
 }
 
 
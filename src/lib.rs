@@ -1,4 +1,4 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "mock")), no_std)]
 #![feature(custom_inner_attributes)]
 #![cfg_attr(feature = "allocator", feature(alloc_error_handler))]
 #![cfg_attr(feature = "allocator", feature(allocator_api))]
@@ -68,3 +68,4 @@ pub mod process;
 pub mod error;
 pub mod result;
 pub mod os;
+pub mod mock;
@@ -0,0 +1,97 @@
+//! `args_*` implementation, same as in the firmware.
+
+use core::ffi::{c_char, c_int};
+use crate::ffi::FuriString;
+use super::string::mock;
+
+
+/// Removes first `len` bytes and trims spaces around.
+fn trim(args: &[u8], len: usize) -> &[u8] {
+	let rest = &args[len..];
+	let start = rest.iter().position(|b| !b" \n\r\t".contains(b)).unwrap_or(rest.len());
+	let end = rest.iter().rposition(|b| !b" \n\r\t".contains(b)).map_or(start, |i| i + 1);
+	&rest[start..end]
+}
+
+unsafe fn trim_args(args: *mut FuriString, len: usize) {
+	let rest = trim(mock(args).bytes(), len).to_vec();
+	mock(args).set(&rest);
+}
+
+fn hex(c: u8) -> Option<u8> { (c as char).to_digit(16).map(|d| d as u8) }
+
+
+#[no_mangle]
+pub unsafe extern "C" fn args_get_first_word_length(args: *mut FuriString) -> usize {
+	let bytes = mock(args).bytes();
+	bytes.iter().position(|b| *b == b' ').unwrap_or(bytes.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn args_length(args: *mut FuriString) -> usize { mock(args).bytes().len() }
+
+#[no_mangle]
+pub unsafe extern "C" fn args_read_int_and_trim(args: *mut FuriString, value: *mut c_int) -> bool {
+	let len = args_get_first_word_length(args);
+	let word = &mock(args).bytes()[..len];
+	// like `sscanf("%d")`, parses the longest prefix:
+	let sign = matches!(word.first(), Some(b'-' | b'+')) as usize;
+	let digits = word[sign..].iter().take_while(|b| b.is_ascii_digit()).count();
+	match core::str::from_utf8(&word[..sign + digits]).ok().and_then(|s| s.parse().ok()) {
+		Some(parsed) if digits > 0 => {
+			*value = parsed;
+			trim_args(args, len);
+			true
+		},
+		_ => false,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn args_read_string_and_trim(args: *mut FuriString, word: *mut FuriString) -> bool {
+	let len = args_get_first_word_length(args);
+	if len == 0 {
+		return false;
+	}
+	let first = mock(args).bytes()[..len].to_vec();
+	mock(word).set(&first);
+	trim_args(args, len);
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn args_read_probably_quoted_string_and_trim(args: *mut FuriString, word: *mut FuriString) -> bool {
+	let bytes = mock(args).bytes();
+	if bytes.len() > 1 && bytes[0] == b'"' {
+		return match bytes[1..].iter().position(|b| *b == b'"') {
+			Some(end) => {
+				let quoted = bytes[1..end + 1].to_vec();
+				mock(word).set(&quoted);
+				trim_args(args, end + 2);
+				true
+			},
+			None => false,
+		};
+	}
+	args_read_string_and_trim(args, word)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn args_char_to_hex(hi_nibble: c_char, low_nibble: c_char, byte: *mut u8) -> bool {
+	match (hex(hi_nibble as u8), hex(low_nibble as u8)) {
+		(Some(hi), Some(low)) => {
+			*byte = hi << 4 | low;
+			true
+		},
+		_ => false,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn args_read_hex_bytes(args: *mut FuriString, bytes: *mut u8, bytes_count: usize) -> bool {
+	if args_get_first_word_length(args) != bytes_count * 2 {
+		return false;
+	}
+	let word = mock(args).bytes();
+	(0..bytes_count).all(|i| args_char_to_hex(word[i * 2] as _, word[i * 2 + 1] as _, bytes.add(i)))
+}
@@ -0,0 +1,358 @@
+//! `flipper_format_*` implementation for string and file backed instances.
+//!
+//! Buffered file is the same as file. File content is kept in memory and written back after every change.
+//! Writing inserts the line at the current position.
+
+use core::ffi::{c_char, c_float};
+use core::fmt::Display;
+use std::boxed::Box;
+use std::fs;
+use std::path::PathBuf;
+use std::string::String;
+use std::vec::Vec;
+use crate::ffi::{FlipperFormat, FuriString, Storage};
+use super::c_str_bytes;
+use super::storage::host_path;
+use super::string::mock;
+
+
+struct MockFormat {
+	data: Vec<u8>,
+	pos: usize,
+	strict: bool,
+	is_file: bool,
+	/// Host path of the opened file.
+	file: Option<PathBuf>,
+}
+
+impl MockFormat {
+	fn new(is_file: bool) -> Self {
+		Self { data: Vec::new(),
+		       pos: 0,
+		       strict: false,
+		       is_file,
+		       file: None }
+	}
+
+	fn into_raw(self) -> *mut FlipperFormat { Box::into_raw(Box::new(self)) as _ }
+
+	/// Line starting at `pos` without line ending and position of the next line.
+	fn line(&self, pos: usize) -> (&[u8], usize) {
+		let rest = &self.data[pos..];
+		match rest.iter().position(|b| *b == b'\n') {
+			Some(end) => (rest[..end].strip_suffix(b"\r").unwrap_or(&rest[..end]), pos + end + 1),
+			None => (rest, self.data.len()),
+		}
+	}
+
+	/// Finds the line of `key` from the current position, returns its value and range of the line.
+	fn find(&self, key: &[u8]) -> Option<(String, usize, usize)> {
+		let mut pos = self.pos;
+		while pos < self.data.len() {
+			let (line, next) = self.line(pos);
+			if !line.is_empty() && !line.starts_with(b"#") {
+				let split = line.windows(2).position(|w| w == b": ");
+				let (k, value) = match split {
+					Some(i) => (&line[..i], &line[i + 2..]),
+					None => (line.strip_suffix(b":").unwrap_or(line), &[][..]),
+				};
+				if k == key {
+					return Some((String::from_utf8_lossy(value).into_owned(), pos, next));
+				}
+				if self.strict {
+					return None;
+				}
+			}
+			pos = next;
+		}
+		None
+	}
+
+	/// Reads the value of `key` and moves after its line.
+	fn read(&mut self, key: &[u8]) -> Option<String> {
+		match self.find(key) {
+			Some((value, _, next)) => {
+				self.pos = next;
+				Some(value)
+			},
+			None => {
+				self.pos = self.data.len();
+				None
+			},
+		}
+	}
+
+	fn write_line(&mut self, line: &[u8]) -> bool {
+		self.data.splice(self.pos..self.pos, line.iter().copied().chain([b'\n']));
+		self.pos += line.len() + 1;
+		self.sync()
+	}
+
+	fn write(&mut self, key: &[u8], value: &str) -> bool {
+		let mut line = key.to_vec();
+		line.extend_from_slice(b": ");
+		line.extend_from_slice(value.as_bytes());
+		self.write_line(&line)
+	}
+
+	fn sync(&self) -> bool { self.file.as_ref().map_or(true, |path| fs::write(path, &self.data).is_ok()) }
+
+	fn open(&mut self, path: &[u8], existing: bool, truncate: bool) -> bool {
+		let path = match host_path(path) {
+			Ok(path) => path,
+			Err(_) => return false,
+		};
+		match (path.exists(), existing, truncate) {
+			(false, true, _) | (true, false, false) => return false,
+			_ => {},
+		}
+		self.data = if truncate {
+			Vec::new()
+		} else {
+			fs::read(&path).unwrap_or_default()
+		};
+		self.pos = 0;
+		self.file = Some(path);
+		self.sync()
+	}
+}
+
+unsafe fn format<'a>(flipper_format: *mut FlipperFormat) -> &'a mut MockFormat { &mut *(flipper_format as *mut MockFormat) }
+
+
+fn parse_values<T>(value: &str, parse: impl Fn(&str) -> Option<T>, data: &mut [T]) -> bool {
+	let mut values = value.split_whitespace();
+	data.iter_mut()
+	    .all(|item| values.next().and_then(&parse).map(|value| *item = value).is_some())
+}
+
+fn join_values<T: Copy>(data: &[T], format: impl Fn(T) -> String) -> String {
+	data.iter().map(|value| format(*value)).collect::<Vec<_>>().join(" ")
+}
+
+fn hex(s: &str) -> Option<u8> {
+	if s.len() == 2 {
+		u8::from_str_radix(s, 16).ok()
+	} else {
+		None
+	}
+}
+
+fn boolean(s: &str) -> Option<bool> {
+	match s {
+		"true" => Some(true),
+		"false" => Some(false),
+		_ => None,
+	}
+}
+
+fn display<T: Display>(value: T) -> String { value.to_string() }
+
+
+#[no_mangle]
+pub extern "C" fn flipper_format_string_alloc() -> *mut FlipperFormat { MockFormat::new(false).into_raw() }
+
+#[no_mangle]
+pub extern "C" fn flipper_format_file_alloc(_storage: *mut Storage) -> *mut FlipperFormat { MockFormat::new(true).into_raw() }
+
+#[no_mangle]
+pub extern "C" fn flipper_format_buffered_file_alloc(_storage: *mut Storage) -> *mut FlipperFormat {
+	MockFormat::new(true).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_free(flipper_format: *mut FlipperFormat) {
+	drop(Box::from_raw(flipper_format as *mut MockFormat))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_file_open_existing(flipper_format: *mut FlipperFormat, path: *const c_char) -> bool {
+	assert!(format(flipper_format).is_file, "not a file");
+	format(flipper_format).open(c_str_bytes(path), true, false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_buffered_file_open_existing(flipper_format: *mut FlipperFormat, path: *const c_char) -> bool {
+	flipper_format_file_open_existing(flipper_format, path)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_file_open_append(flipper_format: *mut FlipperFormat, path: *const c_char) -> bool {
+	assert!(format(flipper_format).is_file, "not a file");
+	let format = format(flipper_format);
+	let opened = format.open(c_str_bytes(path), true, false);
+	format.pos = format.data.len();
+	opened
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_file_open_always(flipper_format: *mut FlipperFormat, path: *const c_char) -> bool {
+	assert!(format(flipper_format).is_file, "not a file");
+	format(flipper_format).open(c_str_bytes(path), false, true)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_file_open_new(flipper_format: *mut FlipperFormat, path: *const c_char) -> bool {
+	assert!(format(flipper_format).is_file, "not a file");
+	format(flipper_format).open(c_str_bytes(path), false, false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_file_close(flipper_format: *mut FlipperFormat) -> bool {
+	format(flipper_format).file.take().is_some()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_buffered_file_close(flipper_format: *mut FlipperFormat) -> bool {
+	flipper_format_file_close(flipper_format)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_set_strict_mode(flipper_format: *mut FlipperFormat, strict_mode: bool) {
+	format(flipper_format).strict = strict_mode
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_rewind(flipper_format: *mut FlipperFormat) -> bool {
+	format(flipper_format).pos = 0;
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_seek_to_end(flipper_format: *mut FlipperFormat) -> bool {
+	let format = format(flipper_format);
+	format.pos = format.data.len();
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_key_exist(flipper_format: *mut FlipperFormat, key: *const c_char) -> bool {
+	format(flipper_format).find(c_str_bytes(key)).is_some()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_get_value_count(flipper_format: *mut FlipperFormat,
+                                                        key: *const c_char,
+                                                        count: *mut u32)
+                                                        -> bool {
+	match format(flipper_format).find(c_str_bytes(key)) {
+		Some((value, ..)) => {
+			*count = value.split_whitespace().count() as u32;
+			true
+		},
+		None => false,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_read_header(flipper_format: *mut FlipperFormat,
+                                                    filetype: *mut FuriString,
+                                                    version: *mut u32)
+                                                    -> bool {
+	flipper_format_read_string(flipper_format, b"Filetype\0".as_ptr() as _, filetype) &&
+	flipper_format_read_uint32(flipper_format, b"Version\0".as_ptr() as _, version, 1)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_header(flipper_format: *mut FlipperFormat,
+                                                     filetype: *mut FuriString,
+                                                     version: u32)
+                                                     -> bool {
+	let filetype = mock(filetype).bytes().to_vec();
+	let filetype = std::ffi::CString::new(filetype).unwrap_or_default();
+	flipper_format_write_header_cstr(flipper_format, filetype.as_ptr(), version)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_header_cstr(flipper_format: *mut FlipperFormat,
+                                                          filetype: *const c_char,
+                                                          version: u32)
+                                                          -> bool {
+	flipper_format_write_string_cstr(flipper_format, b"Filetype\0".as_ptr() as _, filetype) &&
+	flipper_format_write_uint32(flipper_format, b"Version\0".as_ptr() as _, &version, 1)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_read_string(flipper_format: *mut FlipperFormat,
+                                                    key: *const c_char,
+                                                    data: *mut FuriString)
+                                                    -> bool {
+	match format(flipper_format).read(c_str_bytes(key)) {
+		Some(value) => {
+			mock(data).set(value.as_bytes());
+			true
+		},
+		None => false,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_string(flipper_format: *mut FlipperFormat,
+                                                     key: *const c_char,
+                                                     data: *mut FuriString)
+                                                     -> bool {
+	let value = String::from_utf8_lossy(mock(data).bytes()).into_owned();
+	format(flipper_format).write(c_str_bytes(key), &value)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_string_cstr(flipper_format: *mut FlipperFormat,
+                                                          key: *const c_char,
+                                                          data: *const c_char)
+                                                          -> bool {
+	let value = String::from_utf8_lossy(c_str_bytes(data)).into_owned();
+	format(flipper_format).write(c_str_bytes(key), &value)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_comment(flipper_format: *mut FlipperFormat, data: *mut FuriString) -> bool {
+	let data = std::ffi::CString::new(mock(data).bytes().to_vec()).unwrap_or_default();
+	flipper_format_write_comment_cstr(flipper_format, data.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_write_comment_cstr(flipper_format: *mut FlipperFormat, data: *const c_char) -> bool {
+	let mut line = b"# ".to_vec();
+	line.extend_from_slice(c_str_bytes(data));
+	format(flipper_format).write_line(&line)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn flipper_format_delete_key(flipper_format: *mut FlipperFormat, key: *const c_char) -> bool {
+	let format = format(flipper_format);
+	match format.find(c_str_bytes(key)) {
+		Some((_, start, end)) => {
+			format.data.drain(start..end);
+			format.pos = start;
+			format.sync()
+		},
+		None => false,
+	}
+}
+
+
+macro_rules! arrays {
+	($($read:ident, $write:ident: $ty:ty => $parse:expr, $format:expr;)*) => {
+		$(
+			#[no_mangle]
+			pub unsafe extern "C" fn $read(flipper_format: *mut FlipperFormat, key: *const c_char, data: *mut $ty, data_size: u16) -> bool {
+				let data = core::slice::from_raw_parts_mut(data, data_size as usize);
+				format(flipper_format).read(c_str_bytes(key)).map_or(false, |value| parse_values(&value, $parse, data))
+			}
+
+			#[no_mangle]
+			pub unsafe extern "C" fn $write(flipper_format: *mut FlipperFormat, key: *const c_char, data: *const $ty, data_size: u16) -> bool {
+				let data = core::slice::from_raw_parts(data, data_size as usize);
+				format(flipper_format).write(c_str_bytes(key), &join_values(data, $format))
+			}
+		)*
+	};
+}
+
+arrays! {
+	flipper_format_read_uint32, flipper_format_write_uint32: u32 => |s| s.parse().ok(), display;
+	flipper_format_read_int32, flipper_format_write_int32: i32 => |s| s.parse().ok(), display;
+	flipper_format_read_bool, flipper_format_write_bool: bool => boolean, display;
+	flipper_format_read_float, flipper_format_write_float: c_float => |s| s.parse().ok(), |v| format!("{v:.6}");
+	flipper_format_read_hex, flipper_format_write_hex: u8 => hex, |v| format!("{v:02X}");
+}
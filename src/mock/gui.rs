@@ -0,0 +1,666 @@
+//! `submenu_*`, `popup_*`, `dialog_ex_*`, `text_input_*`, `byte_input_*`, `widget_*` and `variable_item_*` implementation.
+//!
+//! Nothing is drawn, modules only keep their texts and callbacks.
+//! Like in the firmware, texts are not copied, so they must outlive the module.
+//! User actions are simulated by functions like [`submenu_select`], they call callbacks in the current thread.
+
+use core::ffi::{c_char, c_void, CStr};
+use core::ptr::{null, null_mut};
+use std::boxed::Box;
+use std::vec::Vec;
+use crate::ffi::{Align, Font, Icon, InputType};
+use crate::ffi::{ButtonCallback, GuiButtonType, Widget};
+use crate::ffi::{ByteChangedCallback, ByteInput, ByteInputCallback};
+use crate::ffi::{DialogEx, DialogExResult, DialogExResultCallback};
+use crate::ffi::{Popup, PopupCallback};
+use crate::ffi::{Submenu, SubmenuItemCallback};
+use crate::ffi::{TextInput, TextInputCallback, TextInputValidatorCallback};
+use crate::ffi::{VariableItem, VariableItemChangeCallback, VariableItemList, VariableItemListEnterCallback};
+use super::string::{furi_string_alloc, furi_string_free};
+
+
+unsafe fn text<'a>(ptr: *const c_char) -> Option<&'a CStr> { (!ptr.is_null()).then(|| CStr::from_ptr(ptr)) }
+
+unsafe fn alloc<T, M>(module: M) -> *mut T { Box::into_raw(Box::new(module)) as _ }
+unsafe fn free<T, M>(module: *mut T) { drop(Box::from_raw(module as *mut M)) }
+unsafe fn module<'a, T, M>(module: *mut T) -> &'a mut M { &mut *(module as *mut M) }
+
+
+// Submenu:
+
+struct SubmenuItem {
+	label: *const c_char,
+	index: u32,
+	callback: SubmenuItemCallback,
+	context: *mut c_void,
+}
+
+struct MockSubmenu {
+	header: *const c_char,
+	items: Vec<SubmenuItem>,
+	selected: u32,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_alloc() -> *mut Submenu {
+	alloc(MockSubmenu { header: null(),
+	                    items: Vec::new(),
+	                    selected: 0 })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_free(submenu: *mut Submenu) { free::<_, MockSubmenu>(submenu) }
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_add_item(submenu: *mut Submenu,
+                                          label: *const c_char,
+                                          index: u32,
+                                          callback: SubmenuItemCallback,
+                                          callback_context: *mut c_void) {
+	let item = SubmenuItem { label,
+	                         index,
+	                         callback,
+	                         context: callback_context };
+	module::<_, MockSubmenu>(submenu).items.push(item);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_reset(submenu: *mut Submenu) {
+	let submenu = module::<_, MockSubmenu>(submenu);
+	submenu.header = null();
+	submenu.items.clear();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_set_header(submenu: *mut Submenu, header: *const c_char) {
+	module::<_, MockSubmenu>(submenu).header = header
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn submenu_set_selected_item(submenu: *mut Submenu, index: u32) {
+	module::<_, MockSubmenu>(submenu).selected = index
+}
+
+/// Labels of the items in order of addition.
+pub unsafe fn submenu_labels<'a>(submenu: *mut Submenu) -> Vec<&'a CStr> {
+	module::<_, MockSubmenu>(submenu).items
+	                                 .iter()
+	                                 .filter_map(|item| text(item.label))
+	                                 .collect()
+}
+
+/// Selects the item with `index` and presses `Ok`.
+pub unsafe fn submenu_select(submenu: *mut Submenu, index: u32) {
+	let submenu = module::<_, MockSubmenu>(submenu);
+	submenu.selected = index;
+	let item = submenu.items.iter().find(|item| item.index == index).expect("no such item");
+	if let Some(callback) = item.callback {
+		callback(item.context, index)
+	}
+}
+
+
+// Popup:
+
+struct MockPopup {
+	header: *const c_char,
+	text: *const c_char,
+	callback: PopupCallback,
+	context: *mut c_void,
+	timeout: u32,
+	timeout_enabled: bool,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_alloc() -> *mut Popup {
+	alloc(MockPopup { header: null(),
+	                  text: null(),
+	                  callback: None,
+	                  context: null_mut(),
+	                  timeout: 1000,
+	                  timeout_enabled: false })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_free(popup: *mut Popup) { free::<_, MockPopup>(popup) }
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_reset(popup: *mut Popup) {
+	let popup = module::<_, MockPopup>(popup);
+	popup.header = null();
+	popup.text = null();
+	popup.callback = None;
+	popup.context = null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_callback(popup: *mut Popup, callback: PopupCallback) {
+	module::<_, MockPopup>(popup).callback = callback
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_context(popup: *mut Popup, context: *mut c_void) {
+	module::<_, MockPopup>(popup).context = context
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_header(popup: *mut Popup, text: *const c_char, _x: u8, _y: u8, _h: Align, _v: Align) {
+	module::<_, MockPopup>(popup).header = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_text(popup: *mut Popup, text: *const c_char, _x: u8, _y: u8, _h: Align, _v: Align) {
+	module::<_, MockPopup>(popup).text = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_icon(_popup: *mut Popup, _x: u8, _y: u8, _icon: *const Icon) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_set_timeout(popup: *mut Popup, timeout_in_ms: u32) {
+	module::<_, MockPopup>(popup).timeout = timeout_in_ms
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_enable_timeout(popup: *mut Popup) { module::<_, MockPopup>(popup).timeout_enabled = true }
+
+#[no_mangle]
+pub unsafe extern "C" fn popup_disable_timeout(popup: *mut Popup) { module::<_, MockPopup>(popup).timeout_enabled = false }
+
+/// Header and text of the popup.
+pub unsafe fn popup_texts<'a>(popup: *mut Popup) -> (Option<&'a CStr>, Option<&'a CStr>) {
+	let popup = module::<_, MockPopup>(popup);
+	(text(popup.header), text(popup.text))
+}
+
+/// Lets the timeout expire, does nothing if the timeout is disabled.
+pub unsafe fn popup_expire(popup: *mut Popup) {
+	let popup = module::<_, MockPopup>(popup);
+	if let (true, Some(callback)) = (popup.timeout_enabled, popup.callback) {
+		callback(popup.context)
+	}
+}
+
+
+// DialogEx:
+
+struct MockDialogEx {
+	header: *const c_char,
+	text: *const c_char,
+	buttons: [*const c_char; 3],
+	callback: DialogExResultCallback,
+	context: *mut c_void,
+	extended_events: bool,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_alloc() -> *mut DialogEx {
+	alloc(MockDialogEx { header: null(),
+	                     text: null(),
+	                     buttons: [null(); 3],
+	                     callback: None,
+	                     context: null_mut(),
+	                     extended_events: false })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_free(dialog_ex: *mut DialogEx) { free::<_, MockDialogEx>(dialog_ex) }
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_reset(dialog_ex: *mut DialogEx) {
+	let dialog = module::<_, MockDialogEx>(dialog_ex);
+	dialog.header = null();
+	dialog.text = null();
+	dialog.buttons = [null(); 3];
+	dialog.context = null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_result_callback(dialog_ex: *mut DialogEx, callback: DialogExResultCallback) {
+	module::<_, MockDialogEx>(dialog_ex).callback = callback
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_context(dialog_ex: *mut DialogEx, context: *mut c_void) {
+	module::<_, MockDialogEx>(dialog_ex).context = context
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_header(dialog_ex: *mut DialogEx, text: *const c_char, _x: u8, _y: u8, _h: Align, _v: Align) {
+	module::<_, MockDialogEx>(dialog_ex).header = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_text(dialog_ex: *mut DialogEx, text: *const c_char, _x: u8, _y: u8, _h: Align, _v: Align) {
+	module::<_, MockDialogEx>(dialog_ex).text = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_icon(_dialog_ex: *mut DialogEx, _x: u8, _y: u8, _icon: *const Icon) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_left_button_text(dialog_ex: *mut DialogEx, text: *const c_char) {
+	module::<_, MockDialogEx>(dialog_ex).buttons[0] = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_center_button_text(dialog_ex: *mut DialogEx, text: *const c_char) {
+	module::<_, MockDialogEx>(dialog_ex).buttons[1] = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_set_right_button_text(dialog_ex: *mut DialogEx, text: *const c_char) {
+	module::<_, MockDialogEx>(dialog_ex).buttons[2] = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_enable_extended_events(dialog_ex: *mut DialogEx) {
+	module::<_, MockDialogEx>(dialog_ex).extended_events = true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dialog_ex_disable_extended_events(dialog_ex: *mut DialogEx) {
+	module::<_, MockDialogEx>(dialog_ex).extended_events = false
+}
+
+/// Texts of the left, center and right buttons.
+pub unsafe fn dialog_ex_buttons<'a>(dialog_ex: *mut DialogEx) -> [Option<&'a CStr>; 3] {
+	module::<_, MockDialogEx>(dialog_ex).buttons.map(|ptr| text(ptr))
+}
+
+/// Reports `result`, `Press*` and `Release*` results are dropped unless extended events are enabled.
+pub unsafe fn dialog_ex_press(dialog_ex: *mut DialogEx, result: DialogExResult) {
+	use DialogExResult::*;
+
+	let dialog = module::<_, MockDialogEx>(dialog_ex);
+	let basic = matches!(result, DialogExResultLeft | DialogExResultCenter | DialogExResultRight);
+	if let (true, Some(callback)) = (basic || dialog.extended_events, dialog.callback) {
+		callback(result, dialog.context)
+	}
+}
+
+
+// TextInput:
+
+struct MockTextInput {
+	header: *const c_char,
+	callback: TextInputCallback,
+	context: *mut c_void,
+	buffer: *mut c_char,
+	size: usize,
+	validator: TextInputValidatorCallback,
+	validator_context: *mut c_void,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_alloc() -> *mut TextInput {
+	alloc(MockTextInput { header: null(),
+	                      callback: None,
+	                      context: null_mut(),
+	                      buffer: null_mut(),
+	                      size: 0,
+	                      validator: None,
+	                      validator_context: null_mut() })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_free(text_input: *mut TextInput) { free::<_, MockTextInput>(text_input) }
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_reset(text_input: *mut TextInput) {
+	let input = module::<_, MockTextInput>(text_input);
+	input.header = null();
+	input.callback = None;
+	input.context = null_mut();
+	input.buffer = null_mut();
+	input.size = 0;
+	input.validator = None;
+	input.validator_context = null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_set_header_text(text_input: *mut TextInput, text: *const c_char) {
+	module::<_, MockTextInput>(text_input).header = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_set_result_callback(text_input: *mut TextInput,
+                                                        callback: TextInputCallback,
+                                                        callback_context: *mut c_void,
+                                                        text_buffer: *mut c_char,
+                                                        text_buffer_size: usize,
+                                                        _clear_default_text: bool) {
+	let input = module::<_, MockTextInput>(text_input);
+	input.callback = callback;
+	input.context = callback_context;
+	input.buffer = text_buffer;
+	input.size = text_buffer_size;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_input_set_validator(text_input: *mut TextInput,
+                                                  callback: TextInputValidatorCallback,
+                                                  callback_context: *mut c_void) {
+	let input = module::<_, MockTextInput>(text_input);
+	input.validator = callback;
+	input.validator_context = callback_context;
+}
+
+/// Replaces the text with `text` truncated to the buffer and presses `Save`.
+///
+/// Returns the error message of the validator if the text is rejected, then the result callback isn't called.
+pub unsafe fn text_input_enter(text_input: *mut TextInput, text: &[u8]) -> Result<(), Vec<u8>> {
+	let input = module::<_, MockTextInput>(text_input);
+	assert!(!input.buffer.is_null() && input.size > 0, "text input is not started");
+	let len = text.len().min(input.size - 1);
+	core::ptr::copy_nonoverlapping(text.as_ptr(), input.buffer as *mut u8, len);
+	input.buffer.add(len).write(0);
+
+	if let Some(validator) = input.validator {
+		let error = furi_string_alloc();
+		let valid = validator(input.buffer, error, input.validator_context);
+		let message = super::string::mock(error).bytes().to_vec();
+		furi_string_free(error);
+		if !valid {
+			return Err(message);
+		}
+	}
+	if let Some(callback) = input.callback {
+		callback(input.context)
+	}
+	Ok(())
+}
+
+
+// ByteInput:
+
+struct MockByteInput {
+	header: *const c_char,
+	input: ByteInputCallback,
+	changed: ByteChangedCallback,
+	context: *mut c_void,
+	bytes: *mut u8,
+	count: u8,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn byte_input_alloc() -> *mut ByteInput {
+	alloc(MockByteInput { header: null(),
+	                      input: None,
+	                      changed: None,
+	                      context: null_mut(),
+	                      bytes: null_mut(),
+	                      count: 0 })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn byte_input_free(byte_input: *mut ByteInput) { free::<_, MockByteInput>(byte_input) }
+
+#[no_mangle]
+pub unsafe extern "C" fn byte_input_set_header_text(byte_input: *mut ByteInput, text: *const c_char) {
+	module::<_, MockByteInput>(byte_input).header = text
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn byte_input_set_result_callback(byte_input: *mut ByteInput,
+                                                        input_callback: ByteInputCallback,
+                                                        changed_callback: ByteChangedCallback,
+                                                        callback_context: *mut c_void,
+                                                        bytes: *mut u8,
+                                                        bytes_count: u8) {
+	let input = module::<_, MockByteInput>(byte_input);
+	input.input = input_callback;
+	input.changed = changed_callback;
+	input.context = callback_context;
+	input.bytes = bytes;
+	input.count = bytes_count;
+}
+
+/// Sets byte at `index` to `value`, as the user does it.
+pub unsafe fn byte_input_set(byte_input: *mut ByteInput, index: u8, value: u8) {
+	let input = module::<_, MockByteInput>(byte_input);
+	assert!(index < input.count, "index out of bounds");
+	input.bytes.add(index as usize).write(value);
+	if let Some(changed) = input.changed {
+		changed(input.context)
+	}
+}
+
+/// Presses `Save`.
+pub unsafe fn byte_input_save(byte_input: *mut ByteInput) {
+	let input = module::<_, MockByteInput>(byte_input);
+	if let Some(callback) = input.input {
+		callback(input.context)
+	}
+}
+
+
+// Widget:
+
+struct WidgetButton {
+	button: GuiButtonType,
+	callback: ButtonCallback,
+	context: *mut c_void,
+}
+
+/// Texts of elements are copied, like in the firmware.
+#[derive(Default)]
+struct MockWidget {
+	texts: Vec<Vec<u8>>,
+	buttons: Vec<WidgetButton>,
+}
+
+impl MockWidget {
+	unsafe fn add_text(widget: *mut Widget, text: *const c_char) {
+		module::<_, Self>(widget).texts.push(super::c_str_bytes(text).to_vec())
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_alloc() -> *mut Widget { alloc(MockWidget::default()) }
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_free(widget: *mut Widget) { free::<_, MockWidget>(widget) }
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_reset(widget: *mut Widget) { *module::<_, MockWidget>(widget) = MockWidget::default() }
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_string_multiline_element(widget: *mut Widget,
+                                                             _x: u8,
+                                                             _y: u8,
+                                                             _horizontal: Align,
+                                                             _vertical: Align,
+                                                             _font: Font,
+                                                             text: *const c_char) {
+	MockWidget::add_text(widget, text)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_string_element(widget: *mut Widget,
+                                                   _x: u8,
+                                                   _y: u8,
+                                                   _horizontal: Align,
+                                                   _vertical: Align,
+                                                   _font: Font,
+                                                   text: *const c_char) {
+	MockWidget::add_text(widget, text)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_text_box_element(widget: *mut Widget,
+                                                     _x: u8,
+                                                     _y: u8,
+                                                     _width: u8,
+                                                     _height: u8,
+                                                     _horizontal: Align,
+                                                     _vertical: Align,
+                                                     text: *const c_char,
+                                                     _strip_to_dots: bool) {
+	MockWidget::add_text(widget, text)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_text_scroll_element(widget: *mut Widget,
+                                                        _x: u8,
+                                                        _y: u8,
+                                                        _width: u8,
+                                                        _height: u8,
+                                                        text: *const c_char) {
+	MockWidget::add_text(widget, text)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_button_element(widget: *mut Widget,
+                                                   button_type: GuiButtonType,
+                                                   text: *const c_char,
+                                                   callback: ButtonCallback,
+                                                   context: *mut c_void) {
+	MockWidget::add_text(widget, text);
+	let button = WidgetButton { button: button_type,
+	                            callback,
+	                            context };
+	module::<_, MockWidget>(widget).buttons.push(button);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_icon_element(_widget: *mut Widget, _x: u8, _y: u8, _icon: *const Icon) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn widget_add_frame_element(_widget: *mut Widget, _x: u8, _y: u8, _width: u8, _height: u8, _radius: u8) {}
+
+/// Texts of all elements in order of addition.
+pub unsafe fn widget_texts(widget: *mut Widget) -> Vec<Vec<u8>> { module::<_, MockWidget>(widget).texts.clone() }
+
+/// Sends input event of `kind` to the `button`.
+pub unsafe fn widget_press(widget: *mut Widget, button: GuiButtonType, kind: InputType) {
+	let widget = module::<_, MockWidget>(widget);
+	let element = widget.buttons.iter().find(|b| b.button == button).expect("no such button");
+	if let Some(callback) = element.callback {
+		callback(button, kind, element.context)
+	}
+}
+
+
+// VariableItemList:
+
+struct MockVariableItem {
+	label: *const c_char,
+	values_count: u8,
+	current: u8,
+	value_text: Vec<u8>,
+	callback: VariableItemChangeCallback,
+	context: *mut c_void,
+}
+
+#[derive(Default)]
+struct MockVariableItemList {
+	// boxed, so item pointers are stable:
+	#[allow(clippy::vec_box)]
+	items: Vec<Box<MockVariableItem>>,
+	selected: u8,
+	enter: VariableItemListEnterCallback,
+	enter_context: usize,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_alloc() -> *mut VariableItemList { alloc(MockVariableItemList::default()) }
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_free(variable_item_list: *mut VariableItemList) {
+	free::<_, MockVariableItemList>(variable_item_list)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_reset(variable_item_list: *mut VariableItemList) {
+	module::<_, MockVariableItemList>(variable_item_list).items.clear()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_add(variable_item_list: *mut VariableItemList,
+                                                label: *const c_char,
+                                                values_count: u8,
+                                                change_callback: VariableItemChangeCallback,
+                                                context: *mut c_void)
+                                                -> *mut VariableItem {
+	let mut item = Box::new(MockVariableItem { label,
+	                                           values_count,
+	                                           current: 0,
+	                                           value_text: Vec::new(),
+	                                           callback: change_callback,
+	                                           context });
+	let raw = item.as_mut() as *mut MockVariableItem as *mut VariableItem;
+	module::<_, MockVariableItemList>(variable_item_list).items.push(item);
+	raw
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_set_enter_callback(variable_item_list: *mut VariableItemList,
+                                                               callback: VariableItemListEnterCallback,
+                                                               context: *mut c_void) {
+	let list = module::<_, MockVariableItemList>(variable_item_list);
+	list.enter = callback;
+	list.enter_context = context as usize;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_set_selected_item(variable_item_list: *mut VariableItemList, index: u8) {
+	module::<_, MockVariableItemList>(variable_item_list).selected = index
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_list_get_selected_item_index(variable_item_list: *mut VariableItemList) -> u8 {
+	module::<_, MockVariableItemList>(variable_item_list).selected
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_get_context(item: *mut VariableItem) -> *mut c_void {
+	module::<_, MockVariableItem>(item).context
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_get_current_value_index(item: *mut VariableItem) -> u8 {
+	module::<_, MockVariableItem>(item).current
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_set_current_value_index(item: *mut VariableItem, current_value_index: u8) {
+	module::<_, MockVariableItem>(item).current = current_value_index
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_set_values_count(item: *mut VariableItem, values_count: u8) {
+	module::<_, MockVariableItem>(item).values_count = values_count
+}
+
+/// Text is copied, like in the firmware.
+#[no_mangle]
+pub unsafe extern "C" fn variable_item_set_current_value_text(item: *mut VariableItem, current_value_text: *const c_char) {
+	module::<_, MockVariableItem>(item).value_text = super::c_str_bytes(current_value_text).to_vec()
+}
+
+/// Label and current value text of the item at `index`.
+pub unsafe fn variable_item_list_item<'a>(variable_item_list: *mut VariableItemList, index: usize) -> (&'a CStr, &'a [u8]) {
+	let item = &module::<_, MockVariableItemList>(variable_item_list).items[index];
+	(text(item.label).unwrap_or_default(), &item.value_text)
+}
+
+/// Selects the item at `index` and presses `Right`, the value wraps around.
+pub unsafe fn variable_item_list_next_value(variable_item_list: *mut VariableItemList, index: u8) {
+	let list = module::<_, MockVariableItemList>(variable_item_list);
+	list.selected = index;
+	let item = list.items[index as usize].as_mut();
+	item.current = (item.current + 1) % item.values_count.max(1);
+	if let Some(callback) = item.callback {
+		callback(item as *mut MockVariableItem as *mut VariableItem)
+	}
+}
+
+/// Selects the item at `index` and presses `Ok`.
+pub unsafe fn variable_item_list_enter(variable_item_list: *mut VariableItemList, index: u8) {
+	let list = module::<_, MockVariableItemList>(variable_item_list);
+	list.selected = index;
+	if let Some(callback) = list.enter {
+		callback(list.enter_context as *mut c_void, index as u32)
+	}
+}
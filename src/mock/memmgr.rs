@@ -0,0 +1,74 @@
+//! Memory manager implemented on top of the host system allocator.
+
+use core::ffi::c_void;
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::alloc::System;
+
+
+/// Size of the mocked heap, used for stats only.
+pub const MOCK_HEAP_SIZE: usize = 256 * 1024;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_MAX: AtomicUsize = AtomicUsize::new(0);
+
+
+/// Header stored right before the returned pointer, contains layout of the whole allocation.
+#[repr(C)]
+struct Header {
+	size: usize,
+	align: usize,
+}
+
+fn header_offset(alignment: usize) -> usize {
+	let header = core::mem::size_of::<Header>();
+	(header + alignment - 1) / alignment * alignment
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn aligned_malloc(size: usize, alignment: usize) -> *mut c_void {
+	let alignment = alignment.max(core::mem::align_of::<Header>());
+	let offset = header_offset(alignment);
+	let layout = match Layout::from_size_align(offset + size, alignment) {
+		Ok(layout) => layout,
+		Err(_) => return core::ptr::null_mut(),
+	};
+
+	let base = System.alloc_zeroed(layout);
+	if base.is_null() {
+		return core::ptr::null_mut();
+	}
+
+	let ptr = base.add(offset);
+	(ptr as *mut Header).sub(1).write(Header { size: layout.size(),
+	                                           align: layout.align() });
+
+	let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+	ALLOCATED_MAX.fetch_max(allocated, Ordering::Relaxed);
+	ptr as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aligned_free(p: *mut c_void) {
+	if p.is_null() {
+		return;
+	}
+	let header = (p as *mut Header).sub(1).read();
+	let layout = Layout::from_size_align_unchecked(header.size, header.align);
+	let base = (p as *mut u8).sub(header_offset(header.align));
+	ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+	System.dealloc(base, layout)
+}
+
+#[no_mangle]
+pub extern "C" fn memmgr_get_free_heap() -> usize { MOCK_HEAP_SIZE.saturating_sub(ALLOCATED.load(Ordering::Relaxed)) }
+
+#[no_mangle]
+pub extern "C" fn memmgr_get_total_heap() -> usize { MOCK_HEAP_SIZE }
+
+#[no_mangle]
+pub extern "C" fn memmgr_get_minimum_free_heap() -> usize { MOCK_HEAP_SIZE.saturating_sub(ALLOCATED_MAX.load(Ordering::Relaxed)) }
+
+#[no_mangle]
+pub extern "C" fn memmgr_heap_get_max_free_block() -> usize { memmgr_get_free_heap() }
@@ -0,0 +1,57 @@
+//! Host implementation of a subset of the firmware API.
+//!
+//! With feature `mock` this module exports (`#[no_mangle]`) symbols declared in [`crate::ffi`],
+//! so code that calls into the firmware can be linked and executed on the host, e.g. by `cargo test`.
+//!
+//! Implemented parts:
+//! - [`string`] - `furi_string_*`, except printf-like functions
+//! - [`path`] - `path_*`
+//! - [`record`] - `furi_record_*`, the storage record is created on demand
+//! - [`storage`] - `storage_*` and `dir_walk_*`, backed by a temporary directory, see [`storage::root`]
+//! - [`thread`] - thread's stdout, `furi_thread_*` except flags, `furi_delay_*` and `furi_ms_to_ticks`
+//! - [`sync`] - `furi_mutex_*`, `furi_semaphore_*`, `furi_event_flag_*`, `furi_message_queue_*` and `furi_stream_buffer_*`
+//! - [`args`] - `args_*` used to parse CLI arguments
+//! - [`pubsub`] - `furi_pubsub_*`
+//! - [`timer`] - `furi_timer_*`, `furi_get_tick` and `furi_kernel_get_tick_frequency`
+//! - [`flipper_format`] - `flipper_format_*` except `uint64`, update and raw stream functions
+//! - [`stream`] - string, file and buffered file `stream_*`, except copy, split, formatting and file load/save
+//! - [`saved_struct`] - `saved_struct_load` and `saved_struct_save`
+//! - [`memmgr`] - `aligned_malloc`, `aligned_free` and heap stats
+//! - [`gui`] - GUI modules: `submenu_*`, `popup_*`, `dialog_ex_*`, `text_input_*`, `byte_input_*`, `widget_*`
+//!   and `variable_item_*`, except `*_get_view`
+//!
+//! Everything else is still declared but not defined, so using it fails at link time.
+//!
+//! Usage:
+//! ```bash
+//! cargo test -p=flipper0 --features=mock --target=x86_64-unknown-linux-gnu
+//! ```
+#![cfg(feature = "mock")]
+#![allow(clippy::missing_safety_doc)]
+
+pub mod string;
+pub mod path;
+pub mod record;
+pub mod storage;
+pub mod thread;
+pub mod sync;
+pub mod timer;
+pub mod pubsub;
+pub mod args;
+pub mod flipper_format;
+pub mod stream;
+pub mod saved_struct;
+pub mod memmgr;
+pub mod gui;
+
+
+/// Converts c-string pointer to bytes without nul.
+///
+/// Returns empty slice for null-pointer.
+pub(crate) unsafe fn c_str_bytes<'a, T>(ptr: *const T) -> &'a [u8] {
+	if ptr.is_null() {
+		&[]
+	} else {
+		core::ffi::CStr::from_ptr(ptr as _).to_bytes()
+	}
+}
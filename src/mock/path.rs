@@ -0,0 +1,96 @@
+//! `path_*` implementation, same semantics as in the firmware.
+
+use core::ffi::c_char;
+use crate::ffi::FuriString;
+use super::c_str_bytes;
+use super::string::{mock, MockString};
+
+
+/// Trims whitespaces and removes trailing slashes.
+fn cleanup(bytes: &[u8]) -> &[u8] {
+	let is_space = |b: &u8| b.is_ascii_whitespace();
+	let start = bytes.iter().position(|b| !is_space(b)).unwrap_or(bytes.len());
+	let end = bytes.iter().rposition(|b| !is_space(b)).map_or(start, |i| i + 1);
+	let mut bytes = &bytes[start..end];
+	while let [rest @ .., b'/'] = bytes {
+		bytes = rest;
+	}
+	bytes
+}
+
+fn rfind(bytes: &[u8], c: u8) -> Option<usize> { bytes.iter().rposition(|b| *b == c) }
+
+fn append(path: &mut MockString, suffix: &[u8]) {
+	let mut result = cleanup(path.bytes()).to_vec();
+	result.push(b'/');
+	result.extend_from_slice(cleanup(suffix));
+	path.set(&result);
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn path_extract_filename_no_ext(path: *const c_char, filename: *mut FuriString) {
+	let bytes = c_str_bytes(path);
+	let start = rfind(bytes, b'/').map_or(0, |i| i + 1);
+	let end = rfind(bytes, b'.').unwrap_or(bytes.len());
+	mock(filename).set(bytes.get(start..end).unwrap_or_default());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_extract_filename(path: *mut FuriString, filename: *mut FuriString, trim_ext: bool) {
+	let bytes = mock(path).bytes().to_vec();
+	let start = rfind(&bytes, b'/').map_or(0, |i| i + 1);
+	let end = match rfind(&bytes, b'.') {
+		Some(dot) if trim_ext && dot > start => dot,
+		_ => bytes.len(),
+	};
+	mock(filename).set(&bytes[start..end]);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_extract_extension(path: *mut FuriString, ext: *mut c_char, ext_len_max: usize) {
+	let bytes = mock(path).bytes();
+	let dot = rfind(bytes, b'.');
+	let start = rfind(bytes, b'/');
+	if let Some(dot) = dot.filter(|dot| start.map_or(true, |start| start < *dot)) {
+		if ext_len_max > 0 {
+			let len = (bytes.len() - dot).min(ext_len_max - 1);
+			core::ptr::copy_nonoverlapping(bytes[dot..].as_ptr(), ext as *mut u8, len);
+			ext.add(len).write(0);
+		}
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_extract_basename(path: *const c_char, basename: *mut FuriString) {
+	let bytes = cleanup(c_str_bytes(path));
+	let start = rfind(bytes, b'/').map_or(0, |i| i + 1);
+	mock(basename).set(&bytes[start..]);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_extract_dirname(path: *const c_char, dirname: *mut FuriString) {
+	let bytes = cleanup(c_str_bytes(path));
+	let end = rfind(bytes, b'/').unwrap_or(bytes.len());
+	mock(dirname).set(&bytes[..end]);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_append(path: *mut FuriString, suffix: *const c_char) { append(mock(path), c_str_bytes(suffix)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn path_concat(path: *const c_char, suffix: *const c_char, out_path: *mut FuriString) {
+	let out = mock(out_path);
+	out.set(c_str_bytes(path));
+	append(out, c_str_bytes(suffix));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn path_contains_only_ascii(path: *const c_char) -> bool {
+	if path.is_null() {
+		return false;
+	}
+	let bytes = c_str_bytes(path);
+	let name = &bytes[rfind(bytes, b'/').map_or(0, |i| i + 1)..];
+	name.iter().all(|c| (b' '..=b'~').contains(c) && !b"\\<>*|\":?".contains(c))
+}
@@ -0,0 +1,69 @@
+//! `furi_pubsub_*` implementation.
+//!
+//! Like in the firmware, subscribers are called under the lock, so publishing
+//! or unsubscribing from the callback deadlocks.
+
+use core::ffi::c_void;
+use std::boxed::Box;
+use std::sync::Mutex;
+use std::vec::Vec;
+use crate::ffi::{FuriPubSub, FuriPubSubCallback, FuriPubSubSubscription};
+
+
+struct Subscriber {
+	callback: unsafe extern "C" fn(*const c_void, *mut c_void),
+	context: usize,
+}
+
+/// Boxed subscribers, their addresses are subscription handles.
+#[allow(clippy::vec_box)]
+struct MockPubSub(Mutex<Vec<Box<Subscriber>>>);
+
+impl MockPubSub {
+	fn with<R>(&self, f: impl FnOnce(&mut Vec<Box<Subscriber>>) -> R) -> R {
+		f(&mut self.0.lock().unwrap_or_else(|err| err.into_inner()))
+	}
+}
+
+#[no_mangle]
+pub extern "C" fn furi_pubsub_alloc() -> *mut FuriPubSub { Box::into_raw(Box::new(MockPubSub(Mutex::new(Vec::new())))) as _ }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_pubsub_free(pubsub: *mut FuriPubSub) {
+	let pubsub = Box::from_raw(pubsub as *mut MockPubSub);
+	assert!(pubsub.with(|subscribers| subscribers.is_empty()), "pubsub has subscribers");
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_pubsub_subscribe(pubsub: *mut FuriPubSub,
+                                               callback: FuriPubSubCallback,
+                                               callback_context: *mut c_void)
+                                               -> *mut FuriPubSubSubscription {
+	let subscriber = Box::new(Subscriber { callback: callback.expect("pubsub callback is not set"),
+	                                       context: callback_context as usize });
+	let raw = subscriber.as_ref() as *const Subscriber as *mut FuriPubSubSubscription;
+	(*(pubsub as *mut MockPubSub)).with(|subscribers| subscribers.push(subscriber));
+	raw
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_pubsub_unsubscribe(pubsub: *mut FuriPubSub, pubsub_subscription: *mut FuriPubSubSubscription) {
+	(*(pubsub as *mut MockPubSub)).with(|subscribers| {
+		                              let position = subscribers.iter()
+		                                                        .position(|s| {
+			                                                        s.as_ref() as *const Subscriber as *mut FuriPubSubSubscription ==
+			                                                        pubsub_subscription
+		                                                        })
+		                                                        .expect("subscription does not exist");
+		                              subscribers.remove(position);
+	                              })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_pubsub_publish(pubsub: *mut FuriPubSub, message: *mut c_void) {
+	(*(pubsub as *mut MockPubSub)).with(|subscribers| {
+		                              for subscriber in subscribers.iter() {
+			                              (subscriber.callback)(message, subscriber.context as *mut c_void);
+		                              }
+	                              })
+}
@@ -0,0 +1,106 @@
+//! `furi_record_*` implementation.
+//!
+//! Records are stored in the global registry.
+//! Unlike the firmware, [`furi_record_open`] does not wait for creation of a record
+//! and returns null-pointer if the record does not exist.
+//!
+//! Records created on demand:
+//! - [`RECORD_STORAGE`] - [`super::storage`]
+
+use core::ffi::{c_char, c_void};
+use std::ffi::CString;
+use std::sync::Mutex;
+use std::vec::Vec;
+use crate::ffi::RECORD_STORAGE;
+use super::c_str_bytes;
+
+
+struct Record {
+	name: CString,
+	data: usize,
+	holders: usize,
+}
+
+static RECORDS: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+
+fn with_records<R, F: FnOnce(&mut Vec<Record>) -> R>(f: F) -> R {
+	let mut records = RECORDS.lock().unwrap_or_else(|err| err.into_inner());
+	f(&mut records)
+}
+
+fn default_record(name: &[u8]) -> Option<*mut c_void> {
+	if name == &RECORD_STORAGE[..RECORD_STORAGE.len() - 1] {
+		Some(super::storage::storage_alloc() as _)
+	} else {
+		None
+	}
+}
+
+
+#[no_mangle]
+pub extern "C" fn furi_record_init() {}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_record_exists(name: *const c_char) -> bool {
+	let name = c_str_bytes(name);
+	with_records(|records| records.iter().any(|r| r.name.as_bytes() == name))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_record_create(name: *const c_char, data: *mut c_void) {
+	let name = c_str_bytes(name);
+	with_records(|records| {
+		assert!(!records.iter().any(|r| r.name.as_bytes() == name), "record already exists");
+		records.push(Record { name: CString::new(name).unwrap(),
+		                      data: data as usize,
+		                      holders: 0 });
+	})
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_record_destroy(name: *const c_char) -> bool {
+	let name = c_str_bytes(name);
+	with_records(|records| {
+		match records.iter().position(|r| r.name.as_bytes() == name) {
+			Some(i) if records[i].holders == 0 => {
+				records.remove(i);
+				true
+			},
+			_ => false,
+		}
+	})
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_record_open(name: *const c_char) -> *mut c_void {
+	let name = c_str_bytes(name);
+	with_records(|records| {
+		if !records.iter().any(|r| r.name.as_bytes() == name) {
+			if let Some(data) = default_record(name) {
+				records.push(Record { name: CString::new(name).unwrap(),
+				                      data: data as usize,
+				                      holders: 0 });
+			}
+		}
+
+		records.iter_mut()
+		       .find(|r| r.name.as_bytes() == name)
+		       .map_or(core::ptr::null_mut(), |r| {
+			       r.holders += 1;
+			       r.data as _
+		       })
+	})
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_record_close(name: *const c_char) {
+	let name = c_str_bytes(name);
+	with_records(|records| {
+		let record = records.iter_mut()
+		                    .find(|r| r.name.as_bytes() == name)
+		                    .expect("record does not exist");
+		assert!(record.holders > 0, "record is not open");
+		record.holders -= 1;
+	})
+}
@@ -0,0 +1,39 @@
+//! `saved_struct_*` implementation, file format is the same as in the firmware.
+
+use core::ffi::{c_char, c_void};
+use std::fs;
+use std::vec::Vec;
+use super::c_str_bytes;
+use super::storage::host_path;
+
+
+/// Magic, version, checksum, flags and timestamp.
+const HEADER_LEN: usize = 8;
+
+
+fn checksum(data: &[u8]) -> u8 { data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte)) }
+
+
+#[no_mangle]
+pub unsafe extern "C" fn saved_struct_load(path: *const c_char, data: *mut c_void, size: usize, magic: u8, version: u8) -> bool {
+	let bytes = match host_path(c_str_bytes(path)).ok().and_then(|path| fs::read(path).ok()) {
+		Some(bytes) if bytes.len() == HEADER_LEN + size => bytes,
+		_ => return false,
+	};
+	let (header, payload) = bytes.split_at(HEADER_LEN);
+	if header[0] != magic || header[1] != version || header[2] != checksum(payload) {
+		return false;
+	}
+	core::ptr::copy_nonoverlapping(payload.as_ptr(), data as *mut u8, size);
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn saved_struct_save(path: *const c_char, data: *mut c_void, size: usize, magic: u8, version: u8) -> bool {
+	let payload = core::slice::from_raw_parts(data as *const u8, size);
+	let mut bytes = Vec::with_capacity(HEADER_LEN + size);
+	bytes.extend_from_slice(&[magic, version, checksum(payload), 0]);
+	bytes.extend_from_slice(&0_u32.to_le_bytes());
+	bytes.extend_from_slice(payload);
+	host_path(c_str_bytes(path)).map_or(false, |path| fs::write(path, bytes).is_ok())
+}
@@ -0,0 +1,672 @@
+//! `storage_*` and `dir_walk_*` implementation backed by a directory on the host.
+//!
+//! Firmware paths are mapped into the [`root`] directory:
+//! - `/ext/...` and `/any/...` to `{root}/ext/...`
+//! - `/int/...` to `{root}/int/...`
+
+use core::ffi::{c_char, c_void};
+use std::boxed::Box;
+use std::vec::Vec;
+use std::fs;
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::ffi::{Storage, File, FileInfo, DirWalk, DirWalkResult, DirWalkFilterCb, FuriString};
+use crate::ffi::{FS_Error, FS_AccessMode, FS_OpenMode};
+use super::c_str_bytes;
+
+
+/// Env var name, optional. Path to the directory used as root of the mocked storage.
+pub const MOCK_STORAGE_ROOT_ENV: &str = "FLIPPER0_MOCK_STORAGE";
+
+/// Size of the mocked file-systems, reported by [`storage_common_fs_info`].
+pub const MOCK_FS_SIZE: u64 = 1 << 30;
+
+const FSF_DIRECTORY: u8 = 1 << 0;
+
+static ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+
+/// Returns root directory of the mocked storage.
+///
+/// It is value of env var [`MOCK_STORAGE_ROOT_ENV`] if set,
+/// or `flipper0-mock-{pid}` in the temporary directory otherwise.
+/// Directories `ext` and `int` are created if needed.
+pub fn root() -> PathBuf {
+	let mut root = ROOT.lock().unwrap_or_else(|err| err.into_inner());
+	root.get_or_insert_with(|| {
+		    let path = std::env::var_os(MOCK_STORAGE_ROOT_ENV).map(PathBuf::from).unwrap_or_else(|| {
+			                                                                         let name =
+				                                                                         format!("flipper0-mock-{}", std::process::id());
+			                                                                         std::env::temp_dir().join(name)
+		                                                                         });
+		    fs::create_dir_all(path.join("ext")).expect("unable to create mocked storage");
+		    fs::create_dir_all(path.join("int")).expect("unable to create mocked storage");
+		    path
+	    })
+	    .clone()
+}
+
+
+/// Converts firmware path to the host path.
+pub(crate) fn host_path(path: &[u8]) -> Result<PathBuf, FS_Error> {
+	let path = core::str::from_utf8(path).map_err(|_| FS_Error::FSE_INVALID_NAME)?;
+	let path = path.trim_end_matches('/');
+	let (fs, rest) = match path.get(..4) {
+		Some("/ext") | Some("/any") => ("ext", &path[4..]),
+		Some("/int") => ("int", &path[4..]),
+		_ => return Err(FS_Error::FSE_INVALID_NAME),
+	};
+	if !rest.is_empty() && !rest.starts_with('/') {
+		return Err(FS_Error::FSE_INVALID_NAME);
+	}
+
+	let mut result = root().join(fs);
+	for part in rest.split('/').filter(|s| !s.is_empty()) {
+		match part {
+			"." => {},
+			".." => return Err(FS_Error::FSE_INVALID_NAME),
+			part => result.push(part),
+		}
+	}
+	Ok(result)
+}
+
+fn io_error(err: io::Error) -> FS_Error {
+	match err.kind() {
+		io::ErrorKind::NotFound => FS_Error::FSE_NOT_EXIST,
+		io::ErrorKind::AlreadyExists => FS_Error::FSE_EXIST,
+		io::ErrorKind::PermissionDenied => FS_Error::FSE_DENIED,
+		io::ErrorKind::InvalidInput => FS_Error::FSE_INVALID_PARAMETER,
+		_ => FS_Error::FSE_INTERNAL,
+	}
+}
+
+fn status(result: Result<(), FS_Error>) -> FS_Error { result.err().unwrap_or(FS_Error::FSE_OK) }
+
+fn file_info(meta: &fs::Metadata) -> FileInfo {
+	FileInfo { flags: if meta.is_dir() { FSF_DIRECTORY } else { 0 },
+	           size: if meta.is_dir() { 0 } else { meta.len() } }
+}
+
+
+struct Entry {
+	name: Vec<u8>,
+	info: FileInfo,
+}
+
+fn read_entries(path: &Path) -> Result<Vec<Entry>, FS_Error> {
+	if !path.is_dir() {
+		return Err(FS_Error::FSE_NOT_EXIST);
+	}
+	let mut entries = Vec::new();
+	for entry in fs::read_dir(path).map_err(io_error)? {
+		let entry = entry.map_err(io_error)?;
+		let meta = entry.metadata().map_err(io_error)?;
+		entries.push(Entry { name: entry.file_name().to_string_lossy().as_bytes().to_vec(),
+		                     info: file_info(&meta) });
+	}
+	entries.sort_by(|a, b| a.name.cmp(&b.name));
+	Ok(entries)
+}
+
+fn copy_recursive(from: &Path, to: &Path, merge: bool) -> Result<(), FS_Error> {
+	let meta = fs::metadata(from).map_err(io_error)?;
+	if meta.is_dir() {
+		if !to.exists() {
+			fs::create_dir(to).map_err(io_error)?;
+		} else if !merge {
+			return Err(FS_Error::FSE_EXIST);
+		}
+		for entry in fs::read_dir(from).map_err(io_error)? {
+			let entry = entry.map_err(io_error)?;
+			copy_recursive(&entry.path(), &to.join(entry.file_name()), merge)?;
+		}
+	} else {
+		let to = match (to.exists(), merge) {
+			(false, _) => to.to_owned(),
+			(true, true) => next_name(to),
+			(true, false) => return Err(FS_Error::FSE_EXIST),
+		};
+		fs::copy(from, to).map_err(io_error)?;
+	}
+	Ok(())
+}
+
+/// Returns first non-existing path such as `{name}{N}.{ext}`.
+fn next_name(path: &Path) -> PathBuf {
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+	let ext = path.extension().map(|ext| format!(".{}", ext.to_string_lossy()));
+	(1..).map(|n| path.with_file_name(format!("{stem}{n}{}", ext.as_deref().unwrap_or_default())))
+	     .find(|path| !path.exists())
+	     .unwrap()
+}
+
+
+/// Host representation of `Storage`.
+pub struct MockStorage {
+	_files: usize,
+}
+
+/// Allocates storage instance, used by the record registry.
+pub(crate) fn storage_alloc() -> *mut Storage { Box::into_raw(Box::new(MockStorage { _files: 0 })) as _ }
+
+
+/// Host representation of `File`.
+pub struct MockFile {
+	state: State,
+	error: FS_Error,
+}
+
+enum State {
+	Closed,
+	File(fs::File),
+	Dir { entries: Vec<Entry>, pos: usize },
+}
+
+impl MockFile {
+	fn result<T: Default>(&mut self, result: Result<T, FS_Error>) -> T {
+		match result {
+			Ok(value) => {
+				self.error = FS_Error::FSE_OK;
+				value
+			},
+			Err(err) => {
+				self.error = err;
+				T::default()
+			},
+		}
+	}
+
+	fn file(&mut self) -> Result<&mut fs::File, FS_Error> {
+		match &mut self.state {
+			State::File(file) => Ok(file),
+			_ => Err(FS_Error::FSE_INVALID_PARAMETER),
+		}
+	}
+}
+
+unsafe fn file<'a>(file: *mut File) -> &'a mut MockFile { &mut *(file as *mut MockFile) }
+
+unsafe fn bool_result(file: *mut File, result: Result<(), FS_Error>) -> bool {
+	let ok = result.is_ok();
+	self::file(file).result(result);
+	ok
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_alloc(_storage: *mut Storage) -> *mut File {
+	Box::into_raw(Box::new(MockFile { state: State::Closed,
+	                                  error: FS_Error::FSE_OK })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_free(file: *mut File) {
+	if !file.is_null() {
+		drop(Box::from_raw(file as *mut MockFile));
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_open(file: *mut File,
+                                           path: *const c_char,
+                                           access_mode: FS_AccessMode,
+                                           open_mode: FS_OpenMode)
+                                           -> bool {
+	let result = host_path(c_str_bytes(path)).and_then(|path| open_file(&path, access_mode, open_mode));
+	let this = self::file(file);
+	match result {
+		Ok(f) => {
+			this.state = State::File(f);
+			this.result(Ok(true))
+		},
+		Err(err) => this.result(Err(err)),
+	}
+}
+
+pub(crate) fn open_file(path: &Path, access_mode: FS_AccessMode, open_mode: FS_OpenMode) -> Result<fs::File, FS_Error> {
+	if path.is_dir() {
+		return Err(FS_Error::FSE_INVALID_NAME);
+	}
+
+	let access = access_mode as u8;
+	let mut options = fs::OpenOptions::new();
+	options.read(access & FS_AccessMode::FSAM_READ as u8 != 0)
+	       .write(access & FS_AccessMode::FSAM_WRITE as u8 != 0);
+
+	let append = matches!(open_mode, FS_OpenMode::FSOM_OPEN_APPEND);
+	match open_mode {
+		FS_OpenMode::FSOM_OPEN_EXISTING => {},
+		FS_OpenMode::FSOM_OPEN_ALWAYS | FS_OpenMode::FSOM_OPEN_APPEND => {
+			options.write(true).create(true);
+		},
+		FS_OpenMode::FSOM_CREATE_NEW => {
+			options.write(true).create_new(true);
+		},
+		FS_OpenMode::FSOM_CREATE_ALWAYS => {
+			options.write(true).create(true).truncate(true);
+		},
+	}
+
+	let mut file = options.open(path).map_err(io_error)?;
+	if append {
+		file.seek(SeekFrom::End(0)).map_err(io_error)?;
+	}
+	Ok(file)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_close(file: *mut File) -> bool {
+	let this = self::file(file);
+	let result = match this.state {
+		State::File(_) => Ok(()),
+		_ => Err(FS_Error::FSE_INVALID_PARAMETER),
+	};
+	this.state = State::Closed;
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_is_open(file: *mut File) -> bool { !matches!(self::file(file).state, State::Closed) }
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_is_dir(file: *mut File) -> bool { matches!(self::file(file).state, State::Dir { .. }) }
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_read(file: *mut File, buff: *mut c_void, bytes_to_read: u16) -> u16 {
+	let this = self::file(file);
+	let buf = core::slice::from_raw_parts_mut(buff as *mut u8, bytes_to_read as usize);
+	let result = this.file().and_then(|f| {
+		                        let mut read = 0;
+		                        while read < buf.len() {
+			                        match f.read(&mut buf[read..]).map_err(io_error)? {
+				                        0 => break,
+			                           n => read += n,
+			                        }
+		                        }
+		                        Ok(read as u16)
+	                        });
+	this.result(result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_write(file: *mut File, buff: *const c_void, bytes_to_write: u16) -> u16 {
+	let this = self::file(file);
+	let buf = core::slice::from_raw_parts(buff as *const u8, bytes_to_write as usize);
+	let result = this.file()
+	                 .and_then(|f| f.write_all(buf).map_err(io_error).map(|_| buf.len() as u16));
+	this.result(result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_seek(file: *mut File, offset: u32, from_start: bool) -> bool {
+	let pos = if from_start {
+		SeekFrom::Start(offset as _)
+	} else {
+		SeekFrom::Current(offset as _)
+	};
+	let result = self::file(file).file().and_then(|f| f.seek(pos).map_err(io_error).map(drop));
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_tell(file: *mut File) -> u64 {
+	let this = self::file(file);
+	let result = this.file().and_then(|f| f.stream_position().map_err(io_error));
+	this.result(result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_truncate(file: *mut File) -> bool {
+	let result = self::file(file).file().and_then(|f| {
+		                                    let pos = f.stream_position().map_err(io_error)?;
+		                                    f.set_len(pos).map_err(io_error)
+	                                    });
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_size(file: *mut File) -> u64 {
+	let this = self::file(file);
+	let result = this.file().and_then(|f| f.metadata().map_err(io_error).map(|m| m.len()));
+	this.result(result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_sync(file: *mut File) -> bool {
+	let result = self::file(file).file().and_then(|f| f.sync_all().map_err(io_error));
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_eof(file: *mut File) -> bool {
+	let this = self::file(file);
+	let result = this.file().and_then(|f| {
+		                        let pos = f.stream_position().map_err(io_error)?;
+		                        let len = f.metadata().map_err(io_error)?.len();
+		                        Ok(pos >= len)
+	                        });
+	this.result(result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_exists(_storage: *mut Storage, path: *const c_char) -> bool {
+	host_path(c_str_bytes(path)).map(|path| path.is_file()).unwrap_or(false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_dir_open(file: *mut File, path: *const c_char) -> bool {
+	let this = self::file(file);
+	let result = host_path(c_str_bytes(path)).and_then(|path| read_entries(&path));
+	match result {
+		Ok(entries) => {
+			this.state = State::Dir { entries, pos: 0 };
+			this.result(Ok(true))
+		},
+		Err(err) => this.result(Err(err)),
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_dir_close(file: *mut File) -> bool {
+	let this = self::file(file);
+	let result = match this.state {
+		State::Dir { .. } => Ok(()),
+		_ => Err(FS_Error::FSE_INVALID_PARAMETER),
+	};
+	this.state = State::Closed;
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_dir_read(file: *mut File, fileinfo: *mut FileInfo, name: *mut c_char, name_length: u16) -> bool {
+	let this = self::file(file);
+	let result = match &mut this.state {
+		State::Dir { entries, pos } => {
+			match entries.get(*pos) {
+				Some(entry) => {
+					*pos += 1;
+					if let Some(info) = fileinfo.as_mut() {
+						info.flags = entry.info.flags;
+						info.size = entry.info.size;
+					}
+					if !name.is_null() && name_length > 0 {
+						let len = entry.name.len().min(name_length as usize - 1);
+						core::ptr::copy_nonoverlapping(entry.name.as_ptr(), name as *mut u8, len);
+						*name.add(len) = 0;
+					}
+					Ok(())
+				},
+				None => Err(FS_Error::FSE_NOT_EXIST),
+			}
+		},
+		_ => Err(FS_Error::FSE_INVALID_PARAMETER),
+	};
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_dir_rewind(file: *mut File) -> bool {
+	let result = match &mut self::file(file).state {
+		State::Dir { pos, .. } => {
+			*pos = 0;
+			Ok(())
+		},
+		_ => Err(FS_Error::FSE_INVALID_PARAMETER),
+	};
+	bool_result(file, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_get_error(file: *mut File) -> FS_Error { self::file(file).error.clone() }
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_get_internal_error(file: *mut File) -> i32 { self::file(file).error.clone() as _ }
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_file_get_error_desc(file: *mut File) -> *const c_char {
+	storage_error_get_desc(self::file(file).error.clone())
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_timestamp(_storage: *mut Storage, path: *const c_char, timestamp: *mut u32) -> FS_Error {
+	status(host_path(c_str_bytes(path)).and_then(|path| {
+		                                   let modified = fs::metadata(path).and_then(|m| m.modified()).map_err(io_error)?;
+		                                   let secs = modified.duration_since(std::time::UNIX_EPOCH)
+		                                                      .map_err(|_| FS_Error::FSE_INTERNAL)?
+		                                                      .as_secs();
+		                                   *timestamp = secs as _;
+		                                   Ok(())
+	                                   }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_stat(_storage: *mut Storage, path: *const c_char, fileinfo: *mut FileInfo) -> FS_Error {
+	status(host_path(c_str_bytes(path)).and_then(|path| {
+		                                   let meta = fs::metadata(path).map_err(io_error)?;
+		                                   if let Some(info) = fileinfo.as_mut() {
+			                                   *info = file_info(&meta);
+		                                   }
+		                                   Ok(())
+	                                   }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_remove(_storage: *mut Storage, path: *const c_char) -> FS_Error {
+	status(host_path(c_str_bytes(path)).and_then(|path| {
+		                                   if path.is_dir() {
+			                                   fs::remove_dir(path).map_err(|_| FS_Error::FSE_DENIED)
+		                                   } else {
+			                                   fs::remove_file(path).map_err(io_error)
+		                                   }
+	                                   }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_rename(_storage: *mut Storage, old_path: *const c_char, new_path: *const c_char) -> FS_Error {
+	let from = host_path(c_str_bytes(old_path));
+	let to = host_path(c_str_bytes(new_path));
+	status(from.and_then(|from| {
+		           let to = to?;
+		           if to.exists() {
+			           return Err(FS_Error::FSE_EXIST);
+		           }
+		           fs::rename(from, to).map_err(io_error)
+	           }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_copy(_storage: *mut Storage, old_path: *const c_char, new_path: *const c_char) -> FS_Error {
+	let from = host_path(c_str_bytes(old_path));
+	let to = host_path(c_str_bytes(new_path));
+	status(from.and_then(|from| copy_recursive(&from, &to?, false)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_merge(_storage: *mut Storage, old_path: *const c_char, new_path: *const c_char) -> FS_Error {
+	let from = host_path(c_str_bytes(old_path));
+	let to = host_path(c_str_bytes(new_path));
+	status(from.and_then(|from| copy_recursive(&from, &to?, true)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_mkdir(_storage: *mut Storage, path: *const c_char) -> FS_Error {
+	status(host_path(c_str_bytes(path)).and_then(|path| fs::create_dir(path).map_err(io_error)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_common_fs_info(_storage: *mut Storage,
+                                                fs_path: *const c_char,
+                                                total_space: *mut u64,
+                                                free_space: *mut u64)
+                                                -> FS_Error {
+	let path = c_str_bytes(fs_path);
+	if !matches!(path, b"/ext" | b"/int" | b"/any" | b"/ext/" | b"/int/" | b"/any/") {
+		return FS_Error::FSE_INVALID_NAME;
+	}
+	if let Some(total) = total_space.as_mut() {
+		*total = MOCK_FS_SIZE;
+	}
+	if let Some(free) = free_space.as_mut() {
+		*free = MOCK_FS_SIZE;
+	}
+	FS_Error::FSE_OK
+}
+
+#[no_mangle]
+pub extern "C" fn storage_error_get_desc(error_id: FS_Error) -> *const c_char { filesystem_api_error_get_desc(error_id) }
+
+#[no_mangle]
+pub extern "C" fn filesystem_api_error_get_desc(error_id: FS_Error) -> *const c_char {
+	let desc: &[u8] = match error_id {
+		FS_Error::FSE_OK => b"OK\0",
+		FS_Error::FSE_NOT_READY => b"filesystem not ready\0",
+		FS_Error::FSE_EXIST => b"file/dir already exist\0",
+		FS_Error::FSE_NOT_EXIST => b"file/dir not exist\0",
+		FS_Error::FSE_INVALID_PARAMETER => b"invalid parameter\0",
+		FS_Error::FSE_DENIED => b"access denied\0",
+		FS_Error::FSE_INVALID_NAME => b"invalid name/path\0",
+		FS_Error::FSE_INTERNAL => b"internal error\0",
+		FS_Error::FSE_NOT_IMPLEMENTED => b"function not implemented\0",
+		FS_Error::FSE_ALREADY_OPEN => b"file is already open\0",
+	};
+	desc.as_ptr() as _
+}
+
+
+/// Host representation of `DirWalk`.
+pub struct MockDirWalk {
+	recursive: bool,
+	filter: DirWalkFilterCb,
+	context: *mut c_void,
+	stack: Vec<Level>,
+	error: FS_Error,
+}
+
+struct Level {
+	path: Vec<u8>,
+	entries: Vec<Entry>,
+	pos: usize,
+}
+
+impl Level {
+	fn open(path: &[u8]) -> Result<Self, FS_Error> {
+		let entries = read_entries(&host_path(path)?)?;
+		Ok(Self { path: path.to_vec(),
+		          entries,
+		          pos: 0 })
+	}
+}
+
+unsafe fn dir_walk<'a>(dir_walk: *mut DirWalk) -> &'a mut MockDirWalk { &mut *(dir_walk as *mut MockDirWalk) }
+
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_alloc(_storage: *mut Storage) -> *mut DirWalk {
+	Box::into_raw(Box::new(MockDirWalk { recursive: true,
+	                                     filter: None,
+	                                     context: core::ptr::null_mut(),
+	                                     stack: Vec::new(),
+	                                     error: FS_Error::FSE_OK })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_free(dir_walk: *mut DirWalk) {
+	if !dir_walk.is_null() {
+		drop(Box::from_raw(dir_walk as *mut MockDirWalk));
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_set_recursive(dir_walk: *mut DirWalk, recursive: bool) {
+	self::dir_walk(dir_walk).recursive = recursive
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_set_filter_cb(dir_walk: *mut DirWalk, cb: DirWalkFilterCb, context: *mut c_void) {
+	let this = self::dir_walk(dir_walk);
+	this.filter = cb;
+	this.context = context;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_open(dir_walk: *mut DirWalk, path: *const c_char) -> bool {
+	let this = self::dir_walk(dir_walk);
+	this.stack.clear();
+	match Level::open(c_str_bytes(path)) {
+		Ok(level) => {
+			this.stack.push(level);
+			this.error = FS_Error::FSE_OK;
+			true
+		},
+		Err(err) => {
+			this.error = err;
+			false
+		},
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_get_error(dir_walk: *mut DirWalk) -> FS_Error { self::dir_walk(dir_walk).error.clone() }
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_read(dir_walk: *mut DirWalk,
+                                       return_path: *mut FuriString,
+                                       fileinfo: *mut FileInfo)
+                                       -> DirWalkResult {
+	let this = self::dir_walk(dir_walk);
+	loop {
+		let level = match this.stack.last_mut() {
+			Some(level) => level,
+			None => return DirWalkResult::DirWalkLast,
+		};
+		let entry = match level.entries.get(level.pos) {
+			Some(entry) => entry,
+			None => {
+				this.stack.pop();
+				continue;
+			},
+		};
+		level.pos += 1;
+
+		let mut path = level.path.clone();
+		path.push(b'/');
+		path.extend_from_slice(&entry.name);
+		let mut info = FileInfo { flags: entry.info.flags,
+		                          size: entry.info.size };
+		let is_dir = info.flags & FSF_DIRECTORY != 0;
+
+		if this.recursive && is_dir {
+			match Level::open(&path) {
+				Ok(level) => this.stack.push(level),
+				Err(err) => {
+					this.error = err;
+					return DirWalkResult::DirWalkError;
+				},
+			}
+		}
+
+		let passed = match this.filter {
+			Some(filter) => {
+				let name = std::ffi::CString::new(path.clone()).unwrap();
+				filter(name.as_ptr(), &mut info, this.context)
+			},
+			None => true,
+		};
+
+		if passed {
+			if !return_path.is_null() {
+				super::string::mock(return_path).set(&path);
+			}
+			if let Some(result) = fileinfo.as_mut() {
+				*result = info;
+			}
+			return DirWalkResult::DirWalkOK;
+		}
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dir_walk_close(dir_walk: *mut DirWalk) { self::dir_walk(dir_walk).stack.clear() }
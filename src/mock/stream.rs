@@ -0,0 +1,248 @@
+//! `stream_*` implementation for string, file and buffered file streams.
+//!
+//! Buffered file stream is the same as file stream. File content is kept in memory and written back after every change.
+
+use core::ffi::{c_char, c_void};
+use std::boxed::Box;
+use std::fs;
+use std::path::PathBuf;
+use std::vec::Vec;
+use crate::ffi::{Stream, StreamOffset, StreamWriteCB, Storage, FuriString};
+use crate::ffi::{FS_Error, FS_AccessMode, FS_OpenMode};
+use super::c_str_bytes;
+use super::storage::{host_path, open_file};
+use super::string::mock;
+
+
+struct MockStream {
+	data: Vec<u8>,
+	pos: usize,
+	/// Host path of the opened file.
+	file: Option<PathBuf>,
+	error: FS_Error,
+}
+
+impl MockStream {
+	fn new() -> Self {
+		Self { data: Vec::new(),
+		       pos: 0,
+		       file: None,
+		       error: FS_Error::FSE_OK }
+	}
+
+	fn into_raw(self) -> *mut Stream { Box::into_raw(Box::new(self)) as _ }
+
+	fn sync(&mut self) -> bool {
+		let result = self.file.as_ref().map_or(Ok(()), |path| fs::write(path, &self.data));
+		self.error = if result.is_ok() {
+			FS_Error::FSE_OK
+		} else {
+			FS_Error::FSE_INTERNAL
+		};
+		result.is_ok()
+	}
+
+	fn open(&mut self, path: &[u8], access_mode: FS_AccessMode, open_mode: FS_OpenMode) -> bool {
+		let append = matches!(open_mode, FS_OpenMode::FSOM_OPEN_APPEND);
+		let result = host_path(path).and_then(|path| open_file(&path, access_mode, open_mode).map(|_| path));
+		match result.map(|path| (fs::read(&path), path)) {
+			Ok((Ok(data), path)) => {
+				self.pos = if append { data.len() } else { 0 };
+				self.data = data;
+				self.file = Some(path);
+				self.error = FS_Error::FSE_OK;
+				true
+			},
+			Ok((Err(_), _)) => {
+				self.error = FS_Error::FSE_INTERNAL;
+				false
+			},
+			Err(err) => {
+				self.error = err;
+				false
+			},
+		}
+	}
+
+	fn close(&mut self) -> bool {
+		let closed = self.file.is_some() && self.sync();
+		self.file = None;
+		self.data.clear();
+		self.pos = 0;
+		closed
+	}
+
+	fn insert(&mut self, bytes: &[u8]) -> bool {
+		self.data.splice(self.pos..self.pos, bytes.iter().copied());
+		self.pos += bytes.len();
+		self.sync()
+	}
+
+	fn delete(&mut self, size: usize) -> bool {
+		let end = (self.pos + size).min(self.data.len());
+		self.data.drain(self.pos..end);
+		self.sync()
+	}
+}
+
+unsafe fn stream<'a>(stream: *mut Stream) -> &'a mut MockStream { &mut *(stream as *mut MockStream) }
+
+
+#[no_mangle]
+pub extern "C" fn string_stream_alloc() -> *mut Stream { MockStream::new().into_raw() }
+
+#[no_mangle]
+pub extern "C" fn file_stream_alloc(_storage: *mut Storage) -> *mut Stream { string_stream_alloc() }
+
+#[no_mangle]
+pub extern "C" fn buffered_file_stream_alloc(_storage: *mut Storage) -> *mut Stream { string_stream_alloc() }
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_free(stream: *mut Stream) {
+	if !stream.is_null() {
+		drop(Box::from_raw(stream as *mut MockStream));
+	}
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn file_stream_open(stream: *mut Stream,
+                                          path: *const c_char,
+                                          access_mode: FS_AccessMode,
+                                          open_mode: FS_OpenMode)
+                                          -> bool {
+	self::stream(stream).open(c_str_bytes(path), access_mode, open_mode)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn buffered_file_stream_open(stream: *mut Stream,
+                                                   path: *const c_char,
+                                                   access_mode: FS_AccessMode,
+                                                   open_mode: FS_OpenMode)
+                                                   -> bool {
+	file_stream_open(stream, path, access_mode, open_mode)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn file_stream_close(stream: *mut Stream) -> bool { self::stream(stream).close() }
+
+#[no_mangle]
+pub unsafe extern "C" fn buffered_file_stream_close(stream: *mut Stream) -> bool { self::stream(stream).close() }
+
+#[no_mangle]
+pub unsafe extern "C" fn buffered_file_stream_sync(stream: *mut Stream) -> bool { self::stream(stream).sync() }
+
+#[no_mangle]
+pub unsafe extern "C" fn file_stream_get_error(stream: *mut Stream) -> FS_Error { self::stream(stream).error.clone() }
+
+#[no_mangle]
+pub unsafe extern "C" fn buffered_file_stream_get_error(stream: *mut Stream) -> FS_Error { self::stream(stream).error.clone() }
+
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_clean(stream: *mut Stream) {
+	let this = self::stream(stream);
+	this.data.clear();
+	this.pos = 0;
+	this.sync();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_eof(stream: *mut Stream) -> bool {
+	let this = self::stream(stream);
+	this.pos >= this.data.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_seek(stream: *mut Stream, offset: i32, offset_type: StreamOffset) -> bool {
+	let this = self::stream(stream);
+	let base = match offset_type {
+		StreamOffset::StreamOffsetFromCurrent => this.pos,
+		StreamOffset::StreamOffsetFromStart => 0,
+		StreamOffset::StreamOffsetFromEnd => this.data.len(),
+	};
+	// like the firmware, position is clamped to the stream bounds:
+	let pos = (base as i64 + offset as i64).clamp(0, this.data.len() as i64) as usize;
+	let exact = base as i64 + offset as i64 == pos as i64;
+	this.pos = pos;
+	exact
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_rewind(stream: *mut Stream) -> bool {
+	self::stream(stream).pos = 0;
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_tell(stream: *mut Stream) -> usize { self::stream(stream).pos }
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_size(stream: *mut Stream) -> usize { self::stream(stream).data.len() }
+
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_read(stream: *mut Stream, data: *mut u8, count: usize) -> usize {
+	let this = self::stream(stream);
+	let len = count.min(this.data.len() - this.pos);
+	core::ptr::copy_nonoverlapping(this.data[this.pos..].as_ptr(), data, len);
+	this.pos += len;
+	len
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_write(stream: *mut Stream, data: *const u8, size: usize) -> usize {
+	let this = self::stream(stream);
+	let bytes = core::slice::from_raw_parts(data, size);
+	let end = (this.pos + size).min(this.data.len());
+	this.data.splice(this.pos..end, bytes.iter().copied());
+	this.pos += size;
+	if this.sync() {
+		size
+	} else {
+		0
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_write_cstring(stream: *mut Stream, string: *const c_char) -> usize {
+	let bytes = c_str_bytes(string);
+	stream_write(stream, bytes.as_ptr(), bytes.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_read_line(stream: *mut Stream, str_result: *mut FuriString) -> bool {
+	let this = self::stream(stream);
+	let rest = &this.data[this.pos..];
+	let len = rest.iter().position(|b| *b == b'\n').map_or(rest.len(), |i| i + 1);
+	mock(str_result).set(&rest[..len]);
+	this.pos += len;
+	len != 0
+}
+
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_insert(stream: *mut Stream, data: *const u8, size: usize) -> bool {
+	self::stream(stream).insert(core::slice::from_raw_parts(data, size))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_delete(stream: *mut Stream, size: usize) -> bool { self::stream(stream).delete(size) }
+
+#[no_mangle]
+pub unsafe extern "C" fn stream_delete_and_insert(stream: *mut Stream,
+                                                  delete_size: usize,
+                                                  write_callback: StreamWriteCB,
+                                                  context: *const c_void)
+                                                  -> bool {
+	if !self::stream(stream).delete(delete_size) {
+		return false;
+	}
+	// the callback writes with overwrite, so the tail is moved away and appended back after:
+	let this = self::stream(stream);
+	let tail = this.data.split_off(this.pos);
+	let written = write_callback.map_or(true, |callback| callback(stream, context));
+	let this = self::stream(stream);
+	this.data.extend_from_slice(&tail);
+	this.sync() && written
+}
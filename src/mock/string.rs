@@ -0,0 +1,400 @@
+//! `furi_string_*` implementation.
+//!
+//! Printf-like functions are not implemented.
+
+use core::ffi::{c_char, c_int};
+use std::boxed::Box;
+use std::vec::Vec;
+use crate::ffi::{FuriString, FuriStringUTF8State, FuriStringUnicodeValue};
+use super::c_str_bytes;
+
+
+/// Returned by search functions if nothing found.
+pub const STRING_FAILURE: usize = usize::MAX;
+
+/// Default set of characters for [`furi_string_trim`].
+const TRIM_DEFAULT: &[u8] = b" \n\r\t";
+
+
+/// Host representation of `FuriString`.
+/// Content is always terminated with nul.
+pub struct MockString(Vec<u8>);
+
+impl MockString {
+	fn new() -> Self { Self(vec![0]) }
+
+	fn from_bytes(bytes: &[u8]) -> Self {
+		let mut s = Self::new();
+		s.set(bytes);
+		s
+	}
+
+	pub fn bytes(&self) -> &[u8] { &self.0[..self.0.len() - 1] }
+
+	pub fn set(&mut self, bytes: &[u8]) {
+		self.0.clear();
+		self.0.extend_from_slice(bytes);
+		self.0.push(0);
+	}
+
+	fn edit<R, F: FnOnce(&mut Vec<u8>) -> R>(&mut self, f: F) -> R {
+		self.0.pop();
+		let result = f(&mut self.0);
+		self.0.push(0);
+		result
+	}
+
+	fn into_raw(self) -> *mut FuriString { Box::into_raw(Box::new(self)) as _ }
+}
+
+
+/// Returns mock-string behind the pointer.
+pub(crate) unsafe fn mock<'a>(string: *const FuriString) -> &'a mut MockString { &mut *(string as *mut MockString) }
+
+
+fn find(haystack: &[u8], needle: &[u8], start: usize) -> usize {
+	if start > haystack.len() {
+		return STRING_FAILURE;
+	}
+	if needle.is_empty() {
+		return start;
+	}
+	haystack[start..].windows(needle.len())
+	                 .position(|w| w == needle)
+	                 .map_or(STRING_FAILURE, |i| i + start)
+}
+
+fn replace_first(string: &mut MockString, needle: &[u8], replace: &[u8], start: usize) -> usize {
+	let pos = find(string.bytes(), needle, start);
+	if pos != STRING_FAILURE {
+		string.edit(|v| {
+			      v.splice(pos..(pos + needle.len()), replace.iter().copied());
+		      });
+	}
+	pos
+}
+
+fn replace_all(string: &mut MockString, needle: &[u8], replace: &[u8]) {
+	if needle.is_empty() {
+		return;
+	}
+	let mut start = 0;
+	loop {
+		match replace_first(string, needle, replace, start) {
+			STRING_FAILURE => break,
+			pos => start = pos + replace.len(),
+		}
+	}
+}
+
+/// Same as `strcmp`.
+fn cmp(a: &[u8], b: &[u8]) -> c_int {
+	let len = a.len().max(b.len());
+	(0..len).map(|i| (a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0)))
+	        .find(|(a, b)| a != b)
+	        .map_or(0, |(a, b)| a as c_int - b as c_int)
+}
+
+/// Same as `strcasecmp`.
+fn cmpi(a: &[u8], b: &[u8]) -> c_int { cmp(&a.to_ascii_lowercase(), &b.to_ascii_lowercase()) }
+
+
+#[no_mangle]
+pub extern "C" fn furi_string_alloc() -> *mut FuriString { MockString::new().into_raw() }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_alloc_set(source: *const FuriString) -> *mut FuriString {
+	MockString::from_bytes(mock(source).bytes()).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_alloc_set_str(cstr_source: *const [c_char; 0]) -> *mut FuriString {
+	MockString::from_bytes(c_str_bytes(cstr_source)).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_alloc_move(source: *mut FuriString) -> *mut FuriString { source }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_free(string: *mut FuriString) {
+	if !string.is_null() {
+		drop(Box::from_raw(string as *mut MockString));
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_reserve(string: *mut FuriString, size: usize) { mock(string).0.reserve(size) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_reset(string: *mut FuriString) { mock(string).set(&[]) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_swap(string_1: *mut FuriString, string_2: *mut FuriString) {
+	core::mem::swap(&mut mock(string_1).0, &mut mock(string_2).0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_move(string_1: *mut FuriString, string_2: *mut FuriString) {
+	furi_string_swap(string_1, string_2);
+	furi_string_free(string_2);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_hash(string: *const FuriString) -> usize {
+	// FNV-1a
+	mock(string).bytes().iter().fold(0xcbf29ce484222325_u64, |hash, b| {
+		                           (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+	                           }) as usize
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_size(string: *const FuriString) -> usize { mock(string).bytes().len() }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_empty(string: *const FuriString) -> bool { mock(string).bytes().is_empty() }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_get_char(string: *const FuriString, index: usize) -> c_char { mock(string).bytes()[index] as _ }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_get_cstr(string: *const FuriString) -> *const c_char { mock(string).0.as_ptr() as _ }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_set(string: *mut FuriString, source: *mut FuriString) {
+	let bytes = mock(source).bytes().to_vec();
+	mock(string).set(&bytes)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_set_str(string: *mut FuriString, source: *const [c_char; 0]) {
+	mock(string).set(c_str_bytes(source))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_set_strn(string: *mut FuriString, source: *const [c_char; 0], length: usize) {
+	let bytes = c_str_bytes(source);
+	mock(string).set(&bytes[..length.min(bytes.len())])
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_set_char(string: *mut FuriString, index: usize, c: c_char) {
+	mock(string).edit(|v| v[index] = c as _)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_set_n(string: *mut FuriString, source: *const FuriString, offset: usize, length: usize) {
+	let source = mock(source).bytes();
+	let start = offset.min(source.len());
+	let end = offset.saturating_add(length).min(source.len());
+	let bytes = source[start..end].to_vec();
+	mock(string).set(&bytes)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_push_back(string: *mut FuriString, c: c_char) { mock(string).edit(|v| v.push(c as _)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cat(string_1: *mut FuriString, string_2: *const FuriString) {
+	let bytes = mock(string_2).bytes().to_vec();
+	mock(string_1).edit(|v| v.extend_from_slice(&bytes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cat_str(string_1: *mut FuriString, cstring_2: *const [c_char; 0]) {
+	let bytes = c_str_bytes(cstring_2);
+	mock(string_1).edit(|v| v.extend_from_slice(bytes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cmp(string_1: *const FuriString, string_2: *const FuriString) -> c_int {
+	cmp(mock(string_1).bytes(), mock(string_2).bytes())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cmp_str(string_1: *const FuriString, cstring_2: *const [c_char; 0]) -> c_int {
+	cmp(mock(string_1).bytes(), c_str_bytes(cstring_2))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cmpi(string_1: *const FuriString, string_2: *const FuriString) -> c_int {
+	cmpi(mock(string_1).bytes(), mock(string_2).bytes())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_cmpi_str(string_1: *const FuriString, cstring_2: *const [c_char; 0]) -> c_int {
+	cmpi(mock(string_1).bytes(), c_str_bytes(cstring_2))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_search(string: *const FuriString, needle: *const FuriString, start: usize) -> usize {
+	find(mock(string).bytes(), mock(needle).bytes(), start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_search_str(string: *const FuriString, needle: *const [c_char; 0], start: usize) -> usize {
+	find(mock(string).bytes(), c_str_bytes(needle), start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_search_char(string: *const FuriString, c: c_char, start: usize) -> usize {
+	find(mock(string).bytes(), &[c as u8], start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_search_rchar(string: *const FuriString, c: c_char, start: usize) -> usize {
+	let bytes = mock(string).bytes();
+	if start > bytes.len() {
+		return STRING_FAILURE;
+	}
+	bytes[start..].iter()
+	              .rposition(|b| *b == c as u8)
+	              .map_or(STRING_FAILURE, |i| i + start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_equal(string_1: *const FuriString, string_2: *const FuriString) -> bool {
+	mock(string_1).bytes() == mock(string_2).bytes()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_equal_str(string_1: *const FuriString, cstring_2: *const [c_char; 0]) -> bool {
+	mock(string_1).bytes() == c_str_bytes(cstring_2)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_replace_at(string: *mut FuriString, pos: usize, len: usize, replace: *const [c_char; 0]) {
+	let replace = c_str_bytes(replace);
+	mock(string).edit(|v| {
+		            let start = pos.min(v.len());
+		            let end = pos.saturating_add(len).min(v.len());
+		            v.splice(start..end, replace.iter().copied());
+	            })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_replace(string: *mut FuriString,
+                                             needle: *mut FuriString,
+                                             replace: *mut FuriString,
+                                             start: usize)
+                                             -> usize {
+	let needle = mock(needle).bytes().to_vec();
+	let replace = mock(replace).bytes().to_vec();
+	replace_first(mock(string), &needle, &replace, start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_replace_str(string: *mut FuriString,
+                                                 needle: *const [c_char; 0],
+                                                 replace: *const [c_char; 0],
+                                                 start: usize)
+                                                 -> usize {
+	replace_first(mock(string), c_str_bytes(needle), c_str_bytes(replace), start)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_replace_all(string: *mut FuriString, needle: *const FuriString, replace: *const FuriString) {
+	let needle = mock(needle).bytes().to_vec();
+	let replace = mock(replace).bytes().to_vec();
+	replace_all(mock(string), &needle, &replace)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_replace_all_str(string: *mut FuriString,
+                                                     needle: *const [c_char; 0],
+                                                     replace: *const [c_char; 0]) {
+	replace_all(mock(string), c_str_bytes(needle), c_str_bytes(replace))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_start_with(string: *const FuriString, start: *const FuriString) -> bool {
+	mock(string).bytes().starts_with(mock(start).bytes())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_start_with_str(string: *const FuriString, start: *const [c_char; 0]) -> bool {
+	mock(string).bytes().starts_with(c_str_bytes(start))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_end_with(string: *const FuriString, end: *const FuriString) -> bool {
+	mock(string).bytes().ends_with(mock(end).bytes())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_end_with_str(string: *const FuriString, end: *const [c_char; 0]) -> bool {
+	mock(string).bytes().ends_with(c_str_bytes(end))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_left(string: *mut FuriString, index: usize) { mock(string).edit(|v| v.truncate(index)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_right(string: *mut FuriString, index: usize) {
+	mock(string).edit(|v| {
+		            v.drain(..index.min(v.len()));
+	            })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_mid(string: *mut FuriString, index: usize, size: usize) {
+	furi_string_set_n(string, string, index, size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_trim(string: *mut FuriString, chars: *const [c_char; 0]) {
+	let chars = if chars.is_null() { TRIM_DEFAULT } else { c_str_bytes(chars) };
+	let bytes = mock(string).bytes();
+	let start = bytes.iter().position(|b| !chars.contains(b)).unwrap_or(bytes.len());
+	let end = bytes.iter().rposition(|b| !chars.contains(b)).map_or(start, |i| i + 1);
+	let trimmed = bytes[start..end].to_vec();
+	mock(string).set(&trimmed)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_utf8_length(string: *mut FuriString) -> usize {
+	let mut state = FuriStringUTF8State::FuriStringUTF8StateStarting;
+	let mut unicode = 0;
+	let mut len = 0;
+	for c in mock(string).bytes() {
+		furi_string_utf8_decode(*c as _, &mut state, &mut unicode);
+		match state {
+			FuriStringUTF8State::FuriStringUTF8StateStarting => len += 1,
+			FuriStringUTF8State::FuriStringUTF8StateError => {
+				len += 1;
+				state = FuriStringUTF8State::FuriStringUTF8StateStarting;
+			},
+			_ => {},
+		}
+	}
+	len
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_utf8_push(string: *mut FuriString, unicode: FuriStringUnicodeValue) {
+	let mut buf = [0; 4];
+	let bytes = match char::from_u32(unicode as _) {
+		Some(c) => c.encode_utf8(&mut buf).as_bytes(),
+		None => return,
+	};
+	mock(string).edit(|v| v.extend_from_slice(bytes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_string_utf8_decode(c: c_char, state: *mut FuriStringUTF8State, unicode: *mut FuriStringUnicodeValue) {
+	use FuriStringUTF8State::*;
+
+	let c = c as u8;
+	let leading_ones = c.leading_ones();
+	let (next, value) = match (&*state, leading_ones) {
+		(FuriStringUTF8StateStarting, 0) => (FuriStringUTF8StateStarting, c as FuriStringUnicodeValue),
+		(FuriStringUTF8StateStarting, 2) => (FuriStringUTF8StateDecoding1, (c & 0x1F) as _),
+		(FuriStringUTF8StateStarting, 3) => (FuriStringUTF8StateDecoding2, (c & 0x0F) as _),
+		(FuriStringUTF8StateStarting, 4) => (FuriStringUTF8StateDecoding3, (c & 0x07) as _),
+		(FuriStringUTF8StateDecoding1, 1) => (FuriStringUTF8StateStarting, (*unicode << 6) | (c & 0x3F) as FuriStringUnicodeValue),
+		(FuriStringUTF8StateDecoding2, 1) => (FuriStringUTF8StateDecoding1, (*unicode << 6) | (c & 0x3F) as FuriStringUnicodeValue),
+		(FuriStringUTF8StateDecoding3, 1) => (FuriStringUTF8StateDecoding2, (*unicode << 6) | (c & 0x3F) as FuriStringUnicodeValue),
+		_ => (FuriStringUTF8StateError, *unicode),
+	};
+	*state = next;
+	*unicode = value;
+}
@@ -0,0 +1,388 @@
+//! `furi_mutex_*`, `furi_semaphore_*`, `furi_event_flag_*`, `furi_message_queue_*` and `furi_stream_buffer_*` implemented with `std::sync`.
+//!
+//! Timeouts are in ticks of the [`super::thread`] mock, one millisecond.
+
+use core::ffi::c_void;
+use std::boxed::Box;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use crate::ffi::{FuriMutex, FuriMutexType, FuriSemaphore, FuriEventFlag, FuriMessageQueue, FuriStatus, FuriThreadId};
+use crate::ffi::FuriStreamBuffer;
+use super::thread::furi_thread_get_current_id;
+
+
+const WAIT_FOREVER: u32 = u32::MAX;
+
+const FLAG_WAIT_ALL: u32 = 1 << 0;
+const FLAG_NO_CLEAR: u32 = 1 << 1;
+const FLAG_ERROR_TIMEOUT: u32 = 0xFFFF_FFFE;
+const FLAG_ERROR_RESOURCE: u32 = 0xFFFF_FFFD;
+
+
+/// State guarded by std mutex with condvar notified on each change.
+struct Sync<T> {
+	state: Mutex<T>,
+	changed: Condvar,
+}
+
+impl<T> Sync<T> {
+	fn new(state: T) -> Self {
+		Self { state: Mutex::new(state),
+		       changed: Condvar::new() }
+	}
+
+	fn lock(&self) -> MutexGuard<'_, T> { self.state.lock().unwrap_or_else(|err| err.into_inner()) }
+
+	/// Waits up to `timeout` ticks until `ready` returns `true`, then calls `f` with the state.
+	///
+	/// Returns `None` on timeout.
+	fn wait<R>(&self, timeout: u32, mut ready: impl FnMut(&T) -> bool, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+		let deadline = (timeout != WAIT_FOREVER).then(|| Instant::now() + Duration::from_millis(timeout as _));
+		let mut state = self.lock();
+		while !ready(&state) {
+			state = match deadline {
+				None => self.changed.wait(state).unwrap_or_else(|err| err.into_inner()),
+				Some(deadline) => {
+					let now = Instant::now();
+					if now >= deadline {
+						return None;
+					}
+					self.changed
+					    .wait_timeout(state, deadline - now)
+					    .unwrap_or_else(|err| err.into_inner())
+					    .0
+				},
+			};
+		}
+		let result = f(&mut state);
+		self.changed.notify_all();
+		Some(result)
+	}
+
+	fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R { self.wait(0, |_| true, f).unwrap() }
+}
+
+
+struct MockMutex {
+	recursive: bool,
+	/// Owner thread id and count of acquisitions.
+	sync: Sync<(usize, u32)>,
+}
+
+#[no_mangle]
+pub extern "C" fn furi_mutex_alloc(kind: FuriMutexType) -> *mut FuriMutex {
+	let recursive = kind == FuriMutexType::FuriMutexTypeRecursive;
+	Box::into_raw(Box::new(MockMutex { recursive,
+	                                   sync: Sync::new((0, 0)) })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_mutex_free(instance: *mut FuriMutex) { drop(Box::from_raw(instance as *mut MockMutex)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_mutex_acquire(instance: *mut FuriMutex, timeout: u32) -> FuriStatus {
+	let mutex = &*(instance as *mut MockMutex);
+	let current = furi_thread_get_current_id() as usize;
+	let ready = |(owner, count): &(usize, u32)| *count == 0 || (mutex.recursive && *owner == current);
+	match mutex.sync.wait(timeout, ready, |(owner, count)| {
+		                *owner = current;
+		                *count += 1;
+	                }) {
+		Some(_) => FuriStatus::FuriStatusOk,
+		None if timeout == 0 => FuriStatus::FuriStatusErrorResource,
+		None => FuriStatus::FuriStatusErrorTimeout,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_mutex_release(instance: *mut FuriMutex) -> FuriStatus {
+	let mutex = &*(instance as *mut MockMutex);
+	let current = furi_thread_get_current_id() as usize;
+	mutex.sync.update(|(owner, count)| {
+		          if *count == 0 || *owner != current {
+			          return FuriStatus::FuriStatusErrorResource;
+		          }
+		          *count -= 1;
+		          if *count == 0 {
+			          *owner = 0;
+		          }
+		          FuriStatus::FuriStatusOk
+	          })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_mutex_get_owner(instance: *mut FuriMutex) -> FuriThreadId {
+	let mutex = &*(instance as *mut MockMutex);
+	mutex.sync.lock().0 as _
+}
+
+
+struct MockSemaphore {
+	max: u32,
+	count: Sync<u32>,
+}
+
+#[no_mangle]
+pub extern "C" fn furi_semaphore_alloc(max_count: u32, initial_count: u32) -> *mut FuriSemaphore {
+	assert!(max_count > 0 && initial_count <= max_count);
+	Box::into_raw(Box::new(MockSemaphore { max: max_count,
+	                                       count: Sync::new(initial_count) })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_semaphore_free(instance: *mut FuriSemaphore) { drop(Box::from_raw(instance as *mut MockSemaphore)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_semaphore_acquire(instance: *mut FuriSemaphore, timeout: u32) -> FuriStatus {
+	let semaphore = &*(instance as *mut MockSemaphore);
+	match semaphore.count.wait(timeout, |count| *count > 0, |count| *count -= 1) {
+		Some(_) => FuriStatus::FuriStatusOk,
+		None if timeout == 0 => FuriStatus::FuriStatusErrorResource,
+		None => FuriStatus::FuriStatusErrorTimeout,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_semaphore_release(instance: *mut FuriSemaphore) -> FuriStatus {
+	let semaphore = &*(instance as *mut MockSemaphore);
+	semaphore.count.update(|count| {
+		               if *count < semaphore.max {
+			               *count += 1;
+			               FuriStatus::FuriStatusOk
+		               } else {
+			               FuriStatus::FuriStatusErrorResource
+		               }
+	               })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_semaphore_get_count(instance: *mut FuriSemaphore) -> u32 {
+	*(*(instance as *mut MockSemaphore)).count.lock()
+}
+
+
+#[no_mangle]
+pub extern "C" fn furi_event_flag_alloc() -> *mut FuriEventFlag { Box::into_raw(Box::new(Sync::new(0u32))) as _ }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_event_flag_free(instance: *mut FuriEventFlag) { drop(Box::from_raw(instance as *mut Sync<u32>)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_event_flag_set(instance: *mut FuriEventFlag, flags: u32) -> u32 {
+	(*(instance as *mut Sync<u32>)).update(|state| {
+		                               *state |= flags;
+		                               *state
+	                               })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_event_flag_clear(instance: *mut FuriEventFlag, flags: u32) -> u32 {
+	(*(instance as *mut Sync<u32>)).update(|state| {
+		                               let previous = *state;
+		                               *state &= !flags;
+		                               previous
+	                               })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_event_flag_get(instance: *mut FuriEventFlag) -> u32 { *(*(instance as *mut Sync<u32>)).lock() }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_event_flag_wait(instance: *mut FuriEventFlag, flags: u32, options: u32, timeout: u32) -> u32 {
+	let sync = &*(instance as *mut Sync<u32>);
+	let ready = |state: &u32| {
+		if options & FLAG_WAIT_ALL != 0 {
+			state & flags == flags
+		} else {
+			state & flags != 0
+		}
+	};
+	let result = sync.wait(timeout, ready, |state| {
+		                 let previous = *state;
+		                 if options & FLAG_NO_CLEAR == 0 {
+			                 *state &= !flags;
+		                 }
+		                 previous
+	                 });
+	match result {
+		Some(flags) => flags,
+		None if timeout == 0 => FLAG_ERROR_RESOURCE,
+		None => FLAG_ERROR_TIMEOUT,
+	}
+}
+
+
+struct MockQueue {
+	capacity: usize,
+	msg_size: usize,
+	messages: Sync<VecDeque<Vec<u8>>>,
+}
+
+#[no_mangle]
+pub extern "C" fn furi_message_queue_alloc(msg_count: u32, msg_size: u32) -> *mut FuriMessageQueue {
+	assert!(msg_count > 0 && msg_size > 0);
+	Box::into_raw(Box::new(MockQueue { capacity: msg_count as _,
+	                                   msg_size: msg_size as _,
+	                                   messages: Sync::new(VecDeque::new()) })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_free(instance: *mut FuriMessageQueue) { drop(Box::from_raw(instance as *mut MockQueue)) }
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_put(instance: *mut FuriMessageQueue, msg_ptr: *const c_void, timeout: u32) -> FuriStatus {
+	let queue = &*(instance as *mut MockQueue);
+	let msg = core::slice::from_raw_parts(msg_ptr as *const u8, queue.msg_size).to_vec();
+	match queue.messages.wait(
+	                          timeout,
+	                          |messages| messages.len() < queue.capacity,
+	                          |messages| messages.push_back(msg),
+	) {
+		Some(_) => FuriStatus::FuriStatusOk,
+		None if timeout == 0 => FuriStatus::FuriStatusErrorResource,
+		None => FuriStatus::FuriStatusErrorTimeout,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_get(instance: *mut FuriMessageQueue, msg_ptr: *mut c_void, timeout: u32) -> FuriStatus {
+	let queue = &*(instance as *mut MockQueue);
+	match queue.messages
+	           .wait(timeout, |messages| !messages.is_empty(), |messages| messages.pop_front())
+	{
+		Some(Some(msg)) => {
+			core::ptr::copy_nonoverlapping(msg.as_ptr(), msg_ptr as *mut u8, queue.msg_size);
+			FuriStatus::FuriStatusOk
+		},
+		_ if timeout == 0 => FuriStatus::FuriStatusErrorResource,
+		_ => FuriStatus::FuriStatusErrorTimeout,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_get_capacity(instance: *mut FuriMessageQueue) -> u32 {
+	(*(instance as *mut MockQueue)).capacity as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_get_message_size(instance: *mut FuriMessageQueue) -> u32 {
+	(*(instance as *mut MockQueue)).msg_size as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_get_count(instance: *mut FuriMessageQueue) -> u32 {
+	(*(instance as *mut MockQueue)).messages.lock().len() as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_get_space(instance: *mut FuriMessageQueue) -> u32 {
+	let queue = &*(instance as *mut MockQueue);
+	(queue.capacity - queue.messages.lock().len()) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_message_queue_reset(instance: *mut FuriMessageQueue) -> FuriStatus {
+	(*(instance as *mut MockQueue)).messages.update(|messages| messages.clear());
+	FuriStatus::FuriStatusOk
+}
+
+
+struct MockStreamBuffer {
+	size: usize,
+	/// Data and trigger level.
+	state: Sync<(VecDeque<u8>, usize)>,
+}
+
+unsafe fn buffer<'a>(instance: *mut FuriStreamBuffer) -> &'a MockStreamBuffer { &*(instance as *mut MockStreamBuffer) }
+
+#[no_mangle]
+pub extern "C" fn furi_stream_buffer_alloc(size: usize, trigger_level: usize) -> *mut FuriStreamBuffer {
+	assert!(size > 0);
+	Box::into_raw(Box::new(MockStreamBuffer { size,
+	                                          state: Sync::new((VecDeque::new(), trigger_level)) })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_free(stream_buffer: *mut FuriStreamBuffer) {
+	drop(Box::from_raw(stream_buffer as *mut MockStreamBuffer))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_set_trigger_level(stream_buffer: *mut FuriStreamBuffer, trigger_level: usize) -> bool {
+	let buffer = buffer(stream_buffer);
+	if trigger_level > buffer.size {
+		return false;
+	}
+	buffer.state.update(|(_, trigger)| *trigger = trigger_level);
+	true
+}
+
+/// Waits until all bytes fit, on timeout sends as many as fit.
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_send(stream_buffer: *mut FuriStreamBuffer,
+                                                 data: *const c_void,
+                                                 length: usize,
+                                                 timeout: u32)
+                                                 -> usize {
+	let buffer = buffer(stream_buffer);
+	let data = core::slice::from_raw_parts(data as *const u8, length);
+	let required = length.min(buffer.size);
+	buffer.state
+	      .wait(timeout, |(bytes, _)| buffer.size - bytes.len() >= required, |_| ());
+	buffer.state.update(|(bytes, _)| {
+		            let len = length.min(buffer.size - bytes.len());
+		            bytes.extend(&data[..len]);
+		            len
+	            })
+}
+
+/// If the buffer is empty, waits until there are trigger level bytes, on timeout receives what is available.
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_receive(stream_buffer: *mut FuriStreamBuffer,
+                                                    data: *mut c_void,
+                                                    length: usize,
+                                                    timeout: u32)
+                                                    -> usize {
+	let buffer = buffer(stream_buffer);
+	let data = core::slice::from_raw_parts_mut(data as *mut u8, length);
+	if buffer.state.lock().0.is_empty() {
+		buffer.state
+		      .wait(timeout, |(bytes, trigger)| bytes.len() >= (*trigger).max(1), |_| ());
+	}
+	buffer.state.update(|(bytes, _)| {
+		            let len = length.min(bytes.len());
+		            for (dst, src) in data.iter_mut().zip(bytes.drain(..len)) {
+			            *dst = src;
+		            }
+		            len
+	            })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_bytes_available(stream_buffer: *mut FuriStreamBuffer) -> usize {
+	buffer(stream_buffer).state.lock().0.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_spaces_available(stream_buffer: *mut FuriStreamBuffer) -> usize {
+	let buffer = buffer(stream_buffer);
+	buffer.size - buffer.state.lock().0.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_is_full(stream_buffer: *mut FuriStreamBuffer) -> bool {
+	furi_stream_buffer_spaces_available(stream_buffer) == 0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_is_empty(stream_buffer: *mut FuriStreamBuffer) -> bool {
+	furi_stream_buffer_bytes_available(stream_buffer) == 0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_stream_buffer_reset(stream_buffer: *mut FuriStreamBuffer) -> FuriStatus {
+	buffer(stream_buffer).state.update(|(bytes, _)| bytes.clear());
+	FuriStatus::FuriStatusOk
+}
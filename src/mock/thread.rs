@@ -0,0 +1,160 @@
+//! Thread's stdout, `furi_thread_*` functions and delays implemented on top of `std::thread`.
+//!
+//! Without a callback set by [`furi_thread_set_stdout_callback`] output goes to the `std::print!`,
+//! so it is captured by the test harness.
+//!
+//! Kernel tick is one millisecond. Thread flags, priorities and stack sizes are not implemented.
+
+use core::cell::{Cell, RefCell};
+use core::ffi::{c_char, c_void};
+use std::boxed::Box;
+use std::ffi::CString;
+use std::string::String;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::ffi::{FuriThread, FuriThreadId, FuriThreadStdoutWriteCallback, FuriThreadCallback, FuriThreadState, FuriThreadPriority};
+use super::c_str_bytes;
+
+
+std::thread_local! {
+	static STDOUT_CALLBACK: Cell<FuriThreadStdoutWriteCallback> = Cell::new(None);
+	static NAME: RefCell<Option<CString>> = RefCell::new(None);
+	static ID: u8 = 0;
+}
+
+
+#[no_mangle]
+pub extern "C" fn furi_thread_set_stdout_callback(callback: FuriThreadStdoutWriteCallback) -> bool {
+	STDOUT_CALLBACK.with(|cb| cb.set(callback));
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_stdout_write(data: *const c_char, size: usize) -> usize {
+	match STDOUT_CALLBACK.with(|cb| cb.get()) {
+		Some(callback) => callback(data, size),
+		None => {
+			let bytes = core::slice::from_raw_parts(data as *const u8, size);
+			std::print!("{}", String::from_utf8_lossy(bytes));
+		},
+	}
+	size
+}
+
+#[no_mangle]
+pub extern "C" fn furi_thread_stdout_flush() -> i32 { 0 }
+
+#[no_mangle]
+pub extern "C" fn furi_thread_yield() { std::thread::yield_now() }
+
+/// Returns unique id for the current thread.
+#[no_mangle]
+pub extern "C" fn furi_thread_get_current_id() -> FuriThreadId { ID.with(|id| id as *const u8 as _) }
+
+/// Returns name of the current thread, `thread_id` is ignored.
+#[no_mangle]
+pub extern "C" fn furi_thread_get_name(_thread_id: FuriThreadId) -> *const c_char {
+	NAME.with(|name| {
+		    name.borrow_mut()
+		        .get_or_insert_with(|| {
+			        let name = std::thread::current().name().unwrap_or("n/a").replace('\0', "");
+			        CString::new(name).unwrap()
+		        })
+		        .as_ptr()
+	    })
+}
+
+
+struct MockThread {
+	name: Option<String>,
+	callback: FuriThreadCallback,
+	context: usize,
+	handle: Option<JoinHandle<i32>>,
+	return_code: i32,
+}
+
+#[no_mangle]
+pub extern "C" fn furi_thread_alloc() -> *mut FuriThread {
+	Box::into_raw(Box::new(MockThread { name: None,
+	                                    callback: None,
+	                                    context: 0,
+	                                    handle: None,
+	                                    return_code: 0 })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_free(thread: *mut FuriThread) {
+	let thread = Box::from_raw(thread as *mut MockThread);
+	assert!(thread.handle.is_none(), "thread is not joined");
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_set_name(thread: *mut FuriThread, name: *const c_char) {
+	let thread = &mut *(thread as *mut MockThread);
+	thread.name = (!name.is_null()).then(|| String::from_utf8_lossy(c_str_bytes(name)).into_owned());
+}
+
+#[no_mangle]
+pub extern "C" fn furi_thread_set_stack_size(_thread: *mut FuriThread, _stack_size: usize) {}
+
+#[no_mangle]
+pub extern "C" fn furi_thread_set_priority(_thread: *mut FuriThread, _priority: FuriThreadPriority) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_set_callback(thread: *mut FuriThread, callback: FuriThreadCallback) {
+	(*(thread as *mut MockThread)).callback = callback;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_set_context(thread: *mut FuriThread, context: *mut c_void) {
+	(*(thread as *mut MockThread)).context = context as usize;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_start(thread: *mut FuriThread) {
+	let thread = &mut *(thread as *mut MockThread);
+	let callback = thread.callback.expect("thread callback is not set");
+	let context = thread.context;
+
+	let mut builder = std::thread::Builder::new();
+	if let Some(name) = &thread.name {
+		builder = builder.name(name.clone());
+	}
+	let handle = builder.spawn(move || unsafe { callback(context as *mut c_void) })
+	                    .expect("failed to spawn thread");
+	thread.handle = Some(handle);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_join(thread: *mut FuriThread) -> bool {
+	let thread = &mut *(thread as *mut MockThread);
+	if let Some(handle) = thread.handle.take() {
+		thread.return_code = handle.join().expect("thread panicked");
+	}
+	true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_get_state(thread: *mut FuriThread) -> FuriThreadState {
+	let thread = &*(thread as *mut MockThread);
+	match &thread.handle {
+		Some(handle) if !handle.is_finished() => FuriThreadState::FuriThreadStateRunning,
+		_ => FuriThreadState::FuriThreadStateStopped,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_thread_get_return_code(thread: *mut FuriThread) -> i32 { (*(thread as *mut MockThread)).return_code }
+
+
+#[no_mangle]
+pub extern "C" fn furi_ms_to_ticks(milliseconds: u32) -> u32 { milliseconds }
+
+#[no_mangle]
+pub extern "C" fn furi_delay_tick(ticks: u32) { std::thread::sleep(Duration::from_millis(ticks as _)) }
+
+#[no_mangle]
+pub extern "C" fn furi_delay_ms(milliseconds: u32) { std::thread::sleep(Duration::from_millis(milliseconds as _)) }
+
+#[no_mangle]
+pub extern "C" fn furi_delay_us(microseconds: u32) { std::thread::sleep(Duration::from_micros(microseconds as _)) }
@@ -0,0 +1,135 @@
+//! Kernel tick counter and `furi_timer_*` implemented with `std::thread`.
+//!
+//! Kernel tick is one millisecond, counted from the first call of [`furi_get_tick`].
+//! Unlike the firmware, each started timer calls its callback in its own thread.
+
+use core::ffi::c_void;
+use std::boxed::Box;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use crate::ffi::{FuriTimer, FuriTimerCallback, FuriTimerType, FuriStatus};
+
+
+static START: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[no_mangle]
+pub extern "C" fn furi_get_tick() -> u32 {
+	let mut start = START.lock().unwrap_or_else(|err| err.into_inner());
+	start.get_or_insert_with(Instant::now).elapsed().as_millis() as u32
+}
+
+#[no_mangle]
+pub extern "C" fn furi_kernel_get_tick_frequency() -> u32 { 1000 }
+
+
+/// Started timer is identified by generation, any start or stop makes workers of previous ones exit.
+#[derive(Default)]
+struct State {
+	generation: u64,
+	running: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+	state: Mutex<State>,
+	changed: Condvar,
+}
+
+impl Shared {
+	fn lock(&self) -> MutexGuard<'_, State> { self.state.lock().unwrap_or_else(|err| err.into_inner()) }
+}
+
+struct MockTimer {
+	callback: unsafe extern "C" fn(*mut c_void),
+	context: usize,
+	periodic: bool,
+	shared: Arc<Shared>,
+	workers: Vec<JoinHandle<()>>,
+}
+
+impl MockTimer {
+	/// Invalidates running worker, returns new generation.
+	fn next_generation(&self, running: bool) -> u64 {
+		let mut state = self.shared.lock();
+		state.generation += 1;
+		state.running = running;
+		self.shared.changed.notify_all();
+		state.generation
+	}
+}
+
+#[no_mangle]
+pub extern "C" fn furi_timer_alloc(func: FuriTimerCallback, kind: FuriTimerType, context: *mut c_void) -> *mut FuriTimer {
+	Box::into_raw(Box::new(MockTimer { callback: func.expect("timer callback is not set"),
+	                                   context: context as usize,
+	                                   periodic: kind == FuriTimerType::FuriTimerTypePeriodic,
+	                                   shared: Default::default(),
+	                                   workers: Vec::new() })) as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_timer_free(instance: *mut FuriTimer) {
+	let timer = Box::from_raw(instance as *mut MockTimer);
+	timer.next_generation(false);
+	for worker in timer.workers {
+		worker.join().expect("timer callback panicked");
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_timer_start(instance: *mut FuriTimer, ticks: u32) -> FuriStatus {
+	if ticks == 0 {
+		return FuriStatus::FuriStatusErrorParameter;
+	}
+	let timer = &mut *(instance as *mut MockTimer);
+	let generation = timer.next_generation(true);
+	timer.workers.retain(|worker| !worker.is_finished());
+
+	let (callback, context, periodic) = (timer.callback, timer.context, timer.periodic);
+	let shared = timer.shared.clone();
+	let period = Duration::from_millis(ticks as _);
+	let worker = std::thread::spawn(move || {
+		let mut deadline = Instant::now() + period;
+		loop {
+			let mut state = shared.lock();
+			loop {
+				if state.generation != generation {
+					return;
+				}
+				let now = Instant::now();
+				if now >= deadline {
+					break;
+				}
+				state = shared.changed
+				              .wait_timeout(state, deadline - now)
+				              .unwrap_or_else(|err| err.into_inner())
+				              .0;
+			}
+			if !periodic {
+				state.running = false;
+			}
+			drop(state);
+
+			unsafe { callback(context as *mut c_void) };
+			if !periodic {
+				return;
+			}
+			deadline += period;
+		}
+	});
+	timer.workers.push(worker);
+	FuriStatus::FuriStatusOk
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_timer_stop(instance: *mut FuriTimer) -> FuriStatus {
+	(*(instance as *mut MockTimer)).next_generation(false);
+	FuriStatus::FuriStatusOk
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn furi_timer_is_running(instance: *mut FuriTimer) -> u32 {
+	(*(instance as *mut MockTimer)).shared.lock().running as u32
+}
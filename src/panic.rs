@@ -1,4 +1,5 @@
-#![cfg(feature = "panic")]
+// There is panic handler in the std, so it is disabled with `mock`.
+#![cfg(all(feature = "panic", not(feature = "mock")))]
 use crate::ffi::*;
 use crate::alloc::ffi::CString;
 use core::ffi::CStr;